@@ -0,0 +1,67 @@
+//! Benchmarks for parsing responses and building queries.
+//!
+//! Run with `cargo bench --features test-support`, add the `streaming`
+//! feature to measure the `quick-xml` backend instead.
+
+#[macro_use]
+extern crate criterion;
+extern crate musicbrainz;
+
+use criterion::Criterion;
+use musicbrainz::entities::{PartialDate, Release, ReleaseOptions};
+use musicbrainz::search::fields::release_group::{ArtistName, ReleaseGroupName};
+use musicbrainz::test_support;
+
+fn parse_release(c: &mut Criterion) {
+    let small = test_support::release_xml(1, 12);
+    c.bench_function("parse release (12 tracks)", move |b| {
+        b.iter(|| {
+            test_support::parse_entity::<Release>(&small, ReleaseOptions::everything()).unwrap()
+        })
+    });
+
+    let large = test_support::release_xml(6, 25);
+    c.bench_function("parse release (150 tracks)", move |b| {
+        b.iter(|| {
+            test_support::parse_entity::<Release>(&large, ReleaseOptions::everything()).unwrap()
+        })
+    });
+}
+
+fn parse_search(c: &mut Criterion) {
+    let page = test_support::release_group_search_xml(25);
+    c.bench_function("parse release group search page", move |b| {
+        b.iter(|| test_support::parse_release_group_search(&page).unwrap())
+    });
+}
+
+fn build_query(c: &mut Criterion) {
+    c.bench_function("escape query value", |b| {
+        b.iter(|| test_support::escape_query_value(r#"AC/DC && "Back in Black" (1980) [Remaster]"#))
+    });
+
+    let mut client = test_support::offline_client();
+    c.bench_function("build search url", move |b| {
+        b.iter(|| {
+            let builder = client
+                .search_release_group()
+                .add(ReleaseGroupName("霊魂消滅 && friends".to_owned()))
+                .add(ArtistName("NECRONOMIDOL".to_owned()));
+            test_support::release_group_search_url(&builder).unwrap()
+        })
+    });
+}
+
+fn format_date(c: &mut Criterion) {
+    let date: PartialDate = "1992-09".parse().unwrap();
+    c.bench_function("format partial date", move |b| b.iter(|| date.to_string()));
+}
+
+criterion_group!(
+    benches,
+    parse_release,
+    parse_search,
+    build_query,
+    format_date
+);
+criterion_main!(benches);
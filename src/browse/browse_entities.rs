@@ -0,0 +1,111 @@
+//! Entities as contained in browse results.
+//!
+//! Like the search entities these only contain the data returned for every
+//! entity of a page. The full entity can be fetched with a further request.
+
+use super::BrowseEntity;
+#[cfg(feature = "streaming")]
+use super::BrowsePage;
+#[cfg(feature = "streaming")]
+use crate::error::Error;
+use crate::entities::{ArtistRef, Country, EventRef, LabelInfo, Mbid, PartialDate, ReleaseGroupRef,
+                      ReleaseStatus, TrackLength};
+
+use xpath_reader::{FromXml, Reader};
+
+#[cfg(feature = "streaming")]
+use std::io::BufRead;
+
+impl BrowseEntity for EventRef {
+    const NAME: &'static str = "event";
+
+    #[cfg(feature = "streaming")]
+    fn parse_page_streaming(source: &mut dyn BufRead) -> Option<Result<BrowsePage<Self>, Error>> {
+        Some(super::streaming::parse_page(source, EventRef::from_node))
+    }
+}
+
+impl BrowseEntity for ReleaseGroupRef {
+    const NAME: &'static str = "release-group";
+
+    #[cfg(feature = "streaming")]
+    fn parse_page_streaming(source: &mut dyn BufRead) -> Option<Result<BrowsePage<Self>, Error>> {
+        Some(super::streaming::parse_page(
+            source,
+            ReleaseGroupRef::from_node,
+        ))
+    }
+}
+
+/// A release as contained in browse results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Release {
+    pub mbid: Mbid,
+    pub title: String,
+    pub status: Option<ReleaseStatus>,
+    pub date: Option<PartialDate>,
+    /// The country the release was issued in.
+    pub country: Option<Country>,
+    pub barcode: Option<String>,
+    pub disambiguation: Option<String>,
+    /// The release group of the release, only available if it was requested.
+    pub release_group: Option<ReleaseGroupRef>,
+    /// The labels and catalog numbers of the release, only available if they
+    /// were requested.
+    pub labels: Vec<LabelInfo>,
+}
+
+impl BrowseEntity for Release {
+    const NAME: &'static str = "release";
+}
+
+impl FromXml for Release {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested release group has a title too, so only direct children
+        // are read.
+        Ok(Release {
+            mbid: reader.read("./@id")?,
+            title: reader.read("./mb:title/text()")?,
+            status: reader.read("./mb:status/text()")?,
+            date: reader.read("./mb:date/text()")?,
+            country: reader.read("./mb:country/text()")?,
+            barcode: reader.read("./mb:barcode/text()")?,
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+            release_group: reader.read("./mb:release-group")?,
+            labels: reader.read("./mb:label-info-list/mb:label-info")?,
+        })
+    }
+}
+
+/// A recording as contained in browse results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recording {
+    pub mbid: Mbid,
+    pub title: String,
+    pub length: Option<TrackLength>,
+    pub disambiguation: Option<String>,
+    /// Whether this is a video recording.
+    pub video: bool,
+    /// The artists the recording is credited to, only available if they were
+    /// requested.
+    pub artists: Vec<ArtistRef>,
+}
+
+impl BrowseEntity for Recording {
+    const NAME: &'static str = "recording";
+}
+
+impl FromXml for Recording {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        let video: Option<String> = reader.read("./mb:video/text()")?;
+
+        Ok(Recording {
+            mbid: reader.read("./@id")?,
+            title: reader.read("./mb:title/text()")?,
+            length: reader.read("./mb:length/text()")?,
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+            video: video.map(|v| v == "true").unwrap_or(false),
+            artists: reader.read("./mb:artist-credit/mb:name-credit/mb:artist")?,
+        })
+    }
+}
@@ -0,0 +1,479 @@
+//! Lists all entities linked to another entity, e.g. all release groups of an
+//! artist.
+//!
+//! In contrast to searching, browsing returns every linked entity. The results
+//! are split into pages of at most 100 entities, which can either be fetched
+//! one by one with `Browse::page` or all at once with `Browse::all`. As every
+//! page is a separate request, the rate limit of the client applies to each
+//! of them.
+
+use crate::client::Client;
+use crate::entities::{join_includes, Include, Mbid, ReleaseGroupPrimaryType, ReleaseGroupRef,
+                      ReleaseStatus};
+use crate::error::Error;
+use crate::filter::ReleaseFilter;
+use crate::paging::{Limit, Offset};
+use crate::progress::Progress;
+use crate::search::fields::SearchValue;
+
+use url::Url;
+use xpath_reader::{FromXml, Reader};
+
+#[cfg(feature = "streaming")]
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+pub mod browse_entities;
+
+/// Entities which can be browsed.
+pub trait BrowseEntity: FromXml {
+    /// Name of the entity in urls and responses, e.g. `release-group`.
+    const NAME: &'static str;
+
+    /// Parse a page of results from `source` with the `quick-xml` backend,
+    /// which reads the entities of the page one at a time.
+    ///
+    /// Returns `None` if the entity doesn't support the backend, in which
+    /// case the XPath backend is used instead.
+    #[cfg(feature = "streaming")]
+    fn parse_page_streaming(_source: &mut dyn BufRead) -> Option<Result<BrowsePage<Self>, Error>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// One page of browse results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BrowsePage<E> {
+    /// The entities of this page.
+    pub entities: Vec<E>,
+
+    /// The offset of the first entity of this page.
+    pub offset: Offset,
+
+    /// The total number of linked entities.
+    pub count: u32,
+
+    /// Offset of the next page, determined before filtering the entities.
+    next_offset: Option<Offset>,
+}
+
+impl<E> BrowsePage<E> {
+    fn new(entities: Vec<E>, offset: Offset, count: u32) -> Self {
+        let next = offset.advance(entities.len() as u32);
+        BrowsePage {
+            next_offset: if entities.is_empty() || next.get() >= count {
+                None
+            } else {
+                Some(next)
+            },
+            entities: entities,
+            offset: offset,
+            count: count,
+        }
+    }
+
+    /// The offset of the next page, or `None` if this is the last one.
+    pub fn next_offset(&self) -> Option<Offset> {
+        self.next_offset
+    }
+}
+
+/// A request listing the entities of type `E` linked to another entity.
+///
+/// Created by the `browse_*` methods of `Client`.
+pub struct Browse<'cl, E> {
+    client: &'cl mut Client,
+    /// Name and MBID of the entity the results are linked to.
+    linked: (&'static str, Mbid),
+    params: Vec<(&'static str, String)>,
+    includes: Vec<Include>,
+    limit: Limit,
+    /// Entities not matching this are dropped from the results.
+    filter: Option<Box<dyn Fn(&E) -> bool + 'cl>>,
+    /// Told about every page fetched by `all`.
+    progress: Option<Box<dyn Progress + 'cl>>,
+    entity: PhantomData<E>,
+}
+
+impl<'cl, E> Browse<'cl, E>
+where
+    E: BrowseEntity,
+{
+    pub(crate) fn new(client: &'cl mut Client, linked: &'static str, mbid: &Mbid) -> Self {
+        Browse {
+            client: client,
+            linked: (linked, mbid.clone()),
+            params: Vec::new(),
+            includes: Vec::new(),
+            limit: Limit::MAX,
+            filter: None,
+            progress: None,
+            entity: PhantomData,
+        }
+    }
+
+    /// Add a filter parameter to the request.
+    pub(crate) fn param(mut self, name: &'static str, value: String) -> Self {
+        self.params.push((name, value));
+        self
+    }
+
+    /// Request additional data for every entity.
+    pub(crate) fn include(mut self, include: Include) -> Self {
+        if !self.includes.contains(&include) {
+            self.includes.push(include);
+        }
+        self
+    }
+
+    /// Drop entities not matching `filter` from the results, in addition to
+    /// any filters added before.
+    ///
+    /// This is used for filters the server doesn't support. The filtering is
+    /// done after receiving a page, so pages might contain fewer entities
+    /// than the limit.
+    pub(crate) fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&E) -> bool + 'cl,
+    {
+        self.filter = Some(match self.filter.take() {
+            Some(previous) => Box::new(move |e: &E| previous(e) && filter(e)),
+            None => Box::new(filter),
+        });
+        self
+    }
+
+    /// The number of entities per page.
+    ///
+    /// Defaults to the maximum of 100, which requires the fewest requests.
+    pub fn limit(mut self, limit: Limit) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Report the number of fetched and linked entities to `progress` after
+    /// every page fetched by `all`.
+    pub fn progress<P: Progress + 'cl>(mut self, progress: P) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Builds the url of the page starting at `offset`, without performing
+    /// the request.
+    pub fn build_url<O: Into<Offset>>(&self, offset: O) -> Result<Url, Error> {
+        let mut url = format!(
+            "https://musicbrainz.org/ws/2/{}?{}={}&limit={}&offset={}",
+            E::NAME,
+            self.linked.0,
+            self.linked.1,
+            self.limit,
+            offset.into()
+        );
+        if !self.includes.is_empty() {
+            url.push_str(&format!("&inc={}", join_includes(&self.includes)));
+        }
+        for &(name, ref value) in self.params.iter() {
+            url.push_str(&format!("&{}={}", name, value));
+        }
+        Ok(Url::parse(&url)?)
+    }
+
+    /// Fetch the page starting at `offset`.
+    pub fn page<O: Into<Offset>>(&mut self, offset: O) -> Result<BrowsePage<E>, Error> {
+        let url = self.build_url(offset)?;
+        let body = self.client.get_body(url.clone())?;
+        let mut page =
+            parse_any_page(&body).map_err(|e| self.client.parse_failed(url.as_str(), &body, e))?;
+        if let Some(ref filter) = self.filter {
+            page.entities.retain(|e| filter(e));
+        }
+        Ok(page)
+    }
+
+    /// Fetch all pages and return the entities of all of them.
+    pub fn all(mut self) -> Result<Vec<E>, Error> {
+        let mut entities = Vec::new();
+        let mut offset = Some(Offset::default());
+        while let Some(o) = offset {
+            let page = self.page(o)?;
+            offset = page.next_offset();
+            if let Some(ref mut progress) = self.progress {
+                // Filtered entities count as fetched too.
+                let done = offset.map(|o| o.get()).unwrap_or(page.count);
+                progress.on_progress(u64::from(done), u64::from(page.count));
+            }
+            entities.extend(page.entities);
+        }
+        Ok(entities)
+    }
+}
+
+impl<'cl> Browse<'cl, ReleaseGroupRef> {
+    /// Only list release groups with one of the given primary types.
+    pub fn release_types(self, types: &[ReleaseGroupPrimaryType]) -> Self {
+        let types: Vec<String> = types.iter().map(SearchValue::to_search_value).collect();
+        self.param("type", types.join("|"))
+    }
+}
+
+impl<'cl> Browse<'cl, browse_entities::Release> {
+    /// Include the release group of every release.
+    pub fn with_release_groups(self) -> Self {
+        self.include(Include::ReleaseGroups)
+    }
+
+    /// Include the labels and catalog numbers of every release.
+    pub fn with_labels(self) -> Self {
+        self.include(Include::Labels)
+    }
+
+    /// Only list releases with one of the given statuses.
+    pub fn statuses(self, statuses: &[ReleaseStatus]) -> Self {
+        let statuses: Vec<String> = statuses.iter().map(SearchValue::to_search_value).collect();
+        self.param("status", statuses.join("|"))
+    }
+
+    /// Only list releases matching `filter`.
+    ///
+    /// The statuses are filtered by the server, all other criteria are
+    /// applied to the received pages.
+    pub fn filtered(self, filter: &ReleaseFilter) -> Self {
+        let browse = if filter.statuses().is_empty() {
+            self
+        } else {
+            self.statuses(filter.statuses())
+        };
+        if !filter.has_local_criteria() {
+            return browse;
+        }
+        let filter = filter.clone();
+        browse.filter(move |release| {
+            filter.matches(
+                release.country.as_ref().map(|c| c.as_str()),
+                release.status.clone(),
+                release.date.as_ref(),
+            )
+        })
+    }
+
+    /// Only list releases issued between the years `from` and `to`, both
+    /// inclusive.
+    ///
+    /// Releases without a known year are dropped. The server doesn't support
+    /// this filter, so all releases are still transferred.
+    pub fn released_between(self, from: u16, to: u16) -> Self {
+        self.filter(move |release| {
+            match release.date.as_ref().and_then(|d| d.year()) {
+                Some(year) => from <= year && year <= to,
+                None => false,
+            }
+        })
+    }
+}
+
+impl<'cl> Browse<'cl, browse_entities::Recording> {
+    /// Include the artists credited for every recording.
+    pub fn with_artists(self) -> Self {
+        self.include(Include::ArtistCredits)
+    }
+}
+
+/// Parse a page of browse results.
+pub(crate) fn parse_page<E: BrowseEntity>(xml: &str) -> Result<BrowsePage<E>, Error> {
+    let context = crate::util::musicbrainz_context();
+    let reader = Reader::from_str(xml, Some(&context))?;
+    crate::client::check_response_error(&reader)?;
+
+    let list = format!("//mb:metadata/mb:{}-list", E::NAME);
+    let entities: Vec<E> = reader.read(format!("{}/mb:{}", list, E::NAME).as_str())?;
+    let offset = Offset::new(reader.read(format!("{}/@offset", list).as_str())?);
+    let count = reader.read(format!("{}/@count", list).as_str())?;
+
+    Ok(BrowsePage::new(entities, offset, count))
+}
+
+/// Parse a page of browse results with the backend supported by `E`.
+fn parse_any_page<E: BrowseEntity>(xml: &str) -> Result<BrowsePage<E>, Error> {
+    #[cfg(feature = "streaming")]
+    {
+        if let Some(page) = E::parse_page_streaming(&mut xml.as_bytes()) {
+            return page;
+        }
+    }
+
+    parse_page(xml)
+}
+
+#[cfg(feature = "streaming")]
+mod streaming {
+    use super::*;
+    use crate::streaming::{check_response_error, parse_elements, require, Node};
+
+    /// Parse a page of browse results from `source`, converting the entities
+    /// with `from_node` as soon as they were read.
+    pub(crate) fn parse_page<E, R, F>(source: R, from_node: F) -> Result<BrowsePage<E>, Error>
+    where
+        E: BrowseEntity,
+        R: BufRead,
+        F: Fn(&Node) -> Result<E, Error>,
+    {
+        let mut entities = Vec::new();
+        let root = parse_elements(source, E::NAME, |node| {
+            entities.push(from_node(&node)?);
+            Ok(())
+        })?;
+        check_response_error(&root)?;
+
+        let list_name = format!("{}-list", E::NAME);
+        let list = root
+            .child(&list_name)
+            .ok_or_else(|| Error::parse_error(format!("Missing {} element.", list_name)))?;
+        let offset = Offset::new(require(list.attribute("offset"), "list offset")?);
+        let count = require(list.attribute("count"), "list count")?;
+        Ok(BrowsePage::new(entities, offset, count))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::entities::TrackLength;
+    use crate::util::test_utils::{config, response, stub_transport, FnTransport};
+
+    const PAGE: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-group-list count="3" offset="0"><release-group type="Album" id="b1392450-e666-3926-a536-22c65f834433"><title>OK Computer</title><first-release-date>1997-05-21</first-release-date><primary-type>Album</primary-type></release-group><release-group type="Single" id="a0b0e1f4-9e3c-4e8e-8cd0-0e6e6e4b5c1a"><title>Creep</title><first-release-date>1992-09-21</first-release-date><primary-type>Single</primary-type></release-group></release-group-list></metadata>"#;
+
+    #[test]
+    fn page() {
+        let page: BrowsePage<ReleaseGroupRef> = parse_page(PAGE).unwrap();
+
+        assert_eq!(page.count, 3);
+        assert_eq!(page.offset, Offset::new(0));
+        assert_eq!(page.entities.len(), 2);
+        assert_eq!(page.entities[1].title, "Creep".to_string());
+        assert_eq!(page.next_offset(), Some(Offset::new(2)));
+    }
+
+    #[cfg(feature = "streaming")]
+    #[test]
+    fn streaming_page() {
+        let page: BrowsePage<ReleaseGroupRef> =
+            ReleaseGroupRef::parse_page_streaming(&mut PAGE.as_bytes())
+                .unwrap()
+                .unwrap();
+        assert_eq!(page, parse_page(PAGE).unwrap());
+    }
+
+    #[test]
+    fn events() {
+        use crate::entities::{EventRef, EventType, PartialDate};
+
+        const EVENTS: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><event-list count="2" offset="0"><event type="Concert" id="6e2ab7d5-f340-4c38-a2c8-1ba7d8d0d5a5"><name>Radiohead at Madison Square Garden</name><life-span><begin>2016-07-27</begin><end>2016-07-27</end></life-span></event><event type="Festival" id="73df2f48-383b-4930-bad3-9d0bc2e8e3fd"><name>Glastonbury 1997</name><life-span><begin>1997-06-27</begin><end>1997-06-29</end></life-span></event></event-list></metadata>"#;
+
+        let page: BrowsePage<EventRef> = parse_page(EVENTS).unwrap();
+        assert_eq!(page.entities.len(), 2);
+        assert_eq!(page.next_offset(), None);
+        assert_eq!(page.entities[1].event_type, Some(EventType::Festival));
+        assert_eq!(
+            page.entities[1].end_date,
+            Some(PartialDate::new(Some(1997), Some(6), Some(29)))
+        );
+    }
+
+    #[test]
+    fn work_recordings() {
+        const RECORDINGS: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><recording-list count="250" offset="100"><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title><length>238640</length><video>true</video><artist-credit><name-credit><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit></recording></recording-list></metadata>"#;
+
+        let page: BrowsePage<browse_entities::Recording> = parse_page(RECORDINGS).unwrap();
+        assert_eq!(page.next_offset(), Some(Offset::new(101)));
+
+        let recording = &page.entities[0];
+        assert_eq!(recording.title, "Creep".to_string());
+        assert_eq!(recording.length, Some(TrackLength::from_millis(238640)));
+        assert!(recording.video);
+        assert_eq!(recording.artists[0].name, "Radiohead".to_string());
+    }
+
+    #[test]
+    fn label_releases() {
+        const RELEASES: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-list count="2" offset="0"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><status>Official</status><date>1992-09-21</date><country>GB</country><label-info-list count="1"><label-info><catalog-number>CDR 6078</catalog-number><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name><sort-name>Parlophone</sort-name><label-code>299</label-code></label></label-info></label-info-list></release><release id="02432b8e-7f1e-4a4e-8b8d-3ff7c8e2d8b0"><title>OK Computer</title><status>Official</status><date>1997-05-21</date></release></release-list></metadata>"#;
+
+        let page: BrowsePage<browse_entities::Release> = parse_page(RELEASES).unwrap();
+        assert_eq!(page.next_offset(), None);
+        assert_eq!(
+            page.entities[0].labels[0].catalog_number,
+            Some("CDR 6078".to_string())
+        );
+        assert_eq!(
+            page.entities[0].labels[0].label.as_ref().unwrap().name,
+            "Parlophone".to_string()
+        );
+        assert!(page.entities[1].labels.is_empty());
+
+        let mut client = Client::with_transport(config().build().unwrap(), stub_transport(""));
+        let mbid = "df7d1c7f-ef95-425f-8eef-445b3d7bcbd9".parse().unwrap();
+        let browse = client
+            .browse_releases_by_label(&mbid)
+            .with_labels()
+            .statuses(&[
+                ReleaseStatus::Official,
+                ReleaseStatus::PseudoRelease,
+                ReleaseStatus::Unknown("Withdrawn".to_string()),
+            ])
+            .released_between(1990, 1995);
+        assert_eq!(
+            browse.build_url(100).unwrap().as_str(),
+            "https://musicbrainz.org/ws/2/release?label=df7d1c7f-ef95-425f-8eef-445b3d7bcbd9&limit=100&offset=100&inc=labels&status=official|pseudo-release|withdrawn"
+        );
+
+        let filter = browse.filter.as_ref().unwrap();
+        assert!(filter(&page.entities[0]));
+        assert!(!filter(&page.entities[1]));
+        drop(browse);
+
+        let url = client
+            .browse_releases_by_label(&mbid)
+            .limit(Limit::new(25).unwrap())
+            .build_url(Offset::new(50))
+            .unwrap();
+        assert!(url.as_str().ends_with("&limit=25&offset=50"));
+    }
+
+    /// Answers with the release groups of `PAGE`, one per page.
+    fn paged_response(request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let offset: u32 = request
+            .url
+            .query_pairs()
+            .find(|&(ref name, _)| name == "offset")
+            .map(|(_, value)| value.parse().unwrap())
+            .unwrap();
+        let page: BrowsePage<ReleaseGroupRef> = parse_page(PAGE).unwrap();
+        let entity = &page.entities[offset as usize];
+        let body = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-group-list count="2" offset="{}"><release-group id="{}"><title>{}</title></release-group></release-group-list></metadata>"#,
+            offset, entity.mbid, entity.title
+        );
+        Ok(response(200, body))
+    }
+
+    #[test]
+    fn all_with_progress() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let mut client = Client::with_transport(config, FnTransport(paged_response));
+        let mbid = "a74b1b7f-71a5-4011-9441-d0b5e4122711".parse().unwrap();
+
+        let mut reports = Vec::new();
+        let release_groups = client
+            .browse_release_groups_by_artist(&mbid)
+            .limit(Limit::new(1).unwrap())
+            .progress(|done, total| reports.push((done, total)))
+            .all()
+            .unwrap();
+        assert_eq!(release_groups.len(), 2);
+        assert_eq!(release_groups[1].title, "Creep".to_string());
+        assert_eq!(reports, vec![(1, 2), (2, 2)]);
+    }
+}
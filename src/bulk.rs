@@ -0,0 +1,267 @@
+//! Fetching many entities with multiple threads.
+//!
+//! The rate limit of the MusicBrainz API applies to all requests of an
+//! application, so starting a few threads each with its own `Client` quickly
+//! exceeds it and gets the application blocked. A `BulkFetch` instead takes
+//! its clients from a `ClientPool`, whose clients share one rate limit: The
+//! threads only let requests overlap with the latency of other requests,
+//! together they never perform more requests than a single client would.
+//!
+//! A handful of threads is therefore plenty, more threads just wait for the
+//! rate limit.
+
+use crate::client::{CancellationToken, Client, ClientPool, HttpTransport};
+use crate::entities::{Mbid, Resource};
+use crate::error::{Error, ErrorKind};
+use crate::progress::Progress;
+
+#[cfg(feature = "default-transport")]
+use crate::client::ReqwestTransport;
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// The MBIDs which weren't fetched yet.
+type Jobs = Arc<Mutex<VecDeque<Mbid>>>;
+
+/// Fetches entities on multiple worker threads, see the module
+/// documentation.
+///
+/// The results are delivered in the order the requests finish, along with
+/// the MBID of the requested entity. Iterating over the `BulkFetch` blocks
+/// until the next result is available and ends once all entities were
+/// fetched.
+///
+/// Dropping the `BulkFetch` cancels the fetches which weren't performed yet.
+pub struct BulkFetch<Res> {
+    results: Receiver<(Mbid, Result<Res, Error>)>,
+    jobs: Jobs,
+    cancellation: CancellationToken,
+    workers: Vec<JoinHandle<()>>,
+    /// Number of requested entities.
+    total: u64,
+    /// Number of results returned by the iterator.
+    done: u64,
+    progress: Option<Box<dyn Progress + Send>>,
+}
+
+impl<Res> BulkFetch<Res>
+where
+    Res: Resource + Send + 'static,
+    Res::Options: Clone + Send + 'static,
+{
+    /// Fetch the entities with the MBIDs `mbids` on `threads` worker threads,
+    /// using clients of `pool`.
+    ///
+    /// Returns a usage error if `threads` is zero.
+    #[cfg(feature = "default-transport")]
+    pub fn start<I>(
+        pool: &ClientPool,
+        threads: usize,
+        mbids: I,
+        options: Res::Options,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Mbid>,
+    {
+        BulkFetch::start_with_transport(pool, threads, mbids, options, ReqwestTransport::new)
+    }
+
+    /// Like `start`, but the clients perform their requests with transports
+    /// created by `transport`.
+    pub fn start_with_transport<I, T, F>(
+        pool: &ClientPool,
+        threads: usize,
+        mbids: I,
+        options: Res::Options,
+        transport: F,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = Mbid>,
+        T: HttpTransport + Send + 'static,
+        F: Fn() -> T,
+    {
+        if threads == 0 {
+            return Err(Error::new(
+                "A bulk fetch needs at least one thread.",
+                ErrorKind::UsageError,
+            ));
+        }
+
+        let mbids: VecDeque<Mbid> = mbids.into_iter().collect();
+        let total = mbids.len() as u64;
+        let jobs: Jobs = Arc::new(Mutex::new(mbids));
+        // The clients don't use the token of the pool, so cancelling this
+        // fetch doesn't affect other clients of the pool.
+        let cancellation = CancellationToken::new();
+        let (sender, results) = channel();
+
+        let workers = (0..threads)
+            .map(|_| {
+                let mut client = pool.client_with_transport(transport());
+                client.set_cancellation_token(cancellation.clone());
+                let jobs = jobs.clone();
+                let sender = sender.clone();
+                let options = options.clone();
+                thread::spawn(move || work(client, &jobs, &sender, options))
+            })
+            .collect();
+
+        Ok(BulkFetch {
+            results,
+            jobs,
+            cancellation,
+            workers,
+            total,
+            done: 0,
+            progress: None,
+        })
+    }
+}
+
+impl<Res> BulkFetch<Res> {
+    /// The channel the results are delivered on, e.g. to wait for them with
+    /// a timeout.
+    pub fn results(&self) -> &Receiver<(Mbid, Result<Res, Error>)> {
+        &self.results
+    }
+
+    /// Report the number of fetched and requested entities to `progress`
+    /// whenever the iterator returns a result.
+    ///
+    /// Results received directly from the `results` channel are not
+    /// reported.
+    pub fn progress<P: Progress + Send + 'static>(mut self, progress: P) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// Number of entities which weren't requested yet.
+    pub fn pending(&self) -> usize {
+        lock(&self.jobs).len()
+    }
+
+    /// Stop fetching further entities.
+    ///
+    /// Requests which are currently performed fail with a cancelled error,
+    /// which is delivered like any other result.
+    pub fn cancel(&self) {
+        lock(&self.jobs).clear();
+        self.cancellation.cancel();
+    }
+}
+
+impl<Res> Iterator for BulkFetch<Res> {
+    type Item = (Mbid, Result<Res, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The channel is closed once all workers finished.
+        let result = self.results.recv().ok()?;
+        self.done += 1;
+        if let Some(ref mut progress) = self.progress {
+            progress.on_progress(self.done, self.total);
+        }
+        Some(result)
+    }
+}
+
+impl<Res> Drop for BulkFetch<Res> {
+    fn drop(&mut self) {
+        self.cancel();
+        for worker in self.workers.drain(..) {
+            // A panicking worker only loses its own result.
+            let _ = worker.join();
+        }
+    }
+}
+
+fn lock(jobs: &Jobs) -> ::std::sync::MutexGuard<VecDeque<Mbid>> {
+    // The queue is never left inconsistent, so a poisoned lock can be used.
+    jobs.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Fetch entities until no jobs are left or nobody is interested in the
+/// results anymore.
+fn work<Res>(
+    mut client: Client,
+    jobs: &Jobs,
+    results: &Sender<(Mbid, Result<Res, Error>)>,
+    options: Res::Options,
+) where
+    Res: Resource,
+    Res::Options: Clone,
+{
+    loop {
+        // The lock must not be held while performing the request.
+        let mbid = match lock(jobs).pop_front() {
+            Some(mbid) => mbid,
+            None => return,
+        };
+        let result = client.get_by_mbid(&mbid, options.clone());
+        if results.send((mbid, result)).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpRequest;
+    use crate::entities::Area;
+    use crate::util::test_utils::{area_xml, requested_mbid, response, stub_transport, FnTransport};
+
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn shared_rate_limit() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let pool = ClientPool::new(config);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mbids: Vec<Mbid> = vec![
+            "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap(),
+            "489ce91b-6658-3307-9877-795b68554c98".parse().unwrap(),
+            "8a754a16-0027-3a29-b6d7-2b40ea0481ed".parse().unwrap(),
+        ];
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let progress = reports.clone();
+
+        // Records when the requests were performed and answers with an area.
+        let fetch = BulkFetch::<Area>::start_with_transport(&pool, 3, mbids.clone(), (), || {
+            let requests = requests.clone();
+            FnTransport(move |request: &HttpRequest| {
+                requests.lock().unwrap().push(Instant::now());
+                Ok(response(200, area_xml(&requested_mbid(request), "Area")))
+            })
+        })
+        .unwrap()
+        .progress(move |done, total| progress.lock().unwrap().push((done, total)));
+        let mut fetched: Vec<Mbid> = fetch
+            .map(|(mbid, area)| {
+                assert_eq!(area.unwrap().mbid(), &mbid);
+                mbid
+            })
+            .collect();
+        fetched.sort_by_key(|mbid| mbid.to_string());
+        assert_eq!(fetched, mbids);
+        assert_eq!(*reports.lock().unwrap(), vec![(1, 3), (2, 3), (3, 3)]);
+
+        // Three threads, but still only one request per second.
+        let mut requests = requests.lock().unwrap().clone();
+        requests.sort();
+        assert_eq!(requests.len(), 3);
+        for pair in requests.windows(2) {
+            assert!(pair[1] - pair[0] >= Duration::from_millis(900));
+        }
+
+        assert!(
+            BulkFetch::<Area>::start_with_transport(&pool, 0, mbids, (), || stub_transport(""))
+                .err()
+                .unwrap()
+                .is_usage_error()
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Resolving the areas containing an area, e.g. the country of a city.
+//!
+//! Artists, labels and places usually reference the most specific area
+//! known, like a city or a district, while most applications just want to
+//! show the country. MusicBrainz models the containment with `part of`
+//! relationships between areas, which are followed here one request per
+//! area, respecting the rate limit of the client.
+//!
+//! As the same areas are resolved over and over again during imports, e.g.
+//! `Tokyo` for every artist from there, the client caches the fetched areas
+//! until `Client::clear_area_cache` is called.
+
+use crate::client::{check_response_error, Client};
+use crate::entities::{Area, AreaResponse, AreaType, Include, Mbid, Resource};
+use crate::error::Error;
+
+use xpath_reader::{FromXml, Reader};
+
+/// Maximal number of areas in a chain, guarding against cycles in the data.
+const MAX_DEPTH: usize = 10;
+
+/// An area followed by the areas containing it, e.g. `Shinjuku`, `Tokyo`,
+/// `Japan`.
+#[derive(Clone, Debug)]
+pub struct AreaChain {
+    areas: Vec<Area>,
+}
+
+impl AreaChain {
+    /// The area the chain was resolved for.
+    pub fn area(&self) -> &Area {
+        &self.areas[0]
+    }
+
+    /// All areas of the chain, starting with the area itself and ending with
+    /// the outermost area which was resolved.
+    pub fn areas(&self) -> &[Area] {
+        self.areas.as_slice()
+    }
+
+    /// The country containing the area, or the area itself if it is a
+    /// country.
+    pub fn country(&self) -> Option<&Area> {
+        self.areas
+            .iter()
+            .find(|a| a.area_type() == Some(AreaType::Country))
+    }
+}
+
+/// An area together with the MBID of the area directly containing it.
+struct AreaWithParent {
+    area: AreaResponse,
+    parent: Option<Mbid>,
+}
+
+impl FromXml for AreaWithParent {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        Ok(AreaWithParent {
+            area: AreaResponse::from_xml(reader)?,
+            parent: reader.read(
+                "//mb:metadata/mb:area/mb:relation-list[@target-type='area']/\
+                 mb:relation[@type='part of'][mb:direction='backward']/mb:area/@id",
+            )?,
+        })
+    }
+}
+
+/// Resolve the chain of areas containing the area with the MBID `mbid`, e.g.
+/// the one of an artist's `area()`.
+///
+/// The `part of` relationships are followed until a country or an area
+/// without a parent is reached, so the chain of a city usually ends with its
+/// country.
+pub fn fetch_chain(client: &mut Client, mbid: &Mbid) -> Result<AreaChain, Error> {
+    let mut areas: Vec<Area> = Vec::new();
+    let mut next = Some(mbid.clone());
+
+    while let Some(mbid) = next.take() {
+        let (area, parent) = fetch_parent(client, &mbid)?;
+        let done = area.area_type() == Some(AreaType::Country) || areas.len() + 1 >= MAX_DEPTH;
+        areas.push(area);
+        if !done {
+            next = parent.filter(|p| areas.iter().all(|a| a.mbid() != p));
+        }
+    }
+
+    Ok(AreaChain { areas })
+}
+
+/// Fetch an area and the MBID of the area directly containing it, unless it
+/// is cached already.
+fn fetch_parent(client: &mut Client, mbid: &Mbid) -> Result<(Area, Option<Mbid>), Error> {
+    if let Some(cached) = client.area_parents.get(mbid) {
+        return Ok(cached.clone());
+    }
+
+    let url = format!(
+        "https://musicbrainz.org/ws/2/area/{}?inc={}",
+        mbid,
+        Include::AreaRels
+    );
+    let body = client.get_body(url.parse()?)?;
+
+    let context = crate::util::musicbrainz_context();
+    let reader = Reader::from_str(body.as_str(), Some(&context))?;
+    check_response_error(&reader)?;
+    let response = AreaWithParent::from_xml(&reader)?;
+
+    let entry = (Area::from_response(response.area, ()), response.parent);
+    client.area_parents.insert(mbid.clone(), entry.clone());
+    Ok(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::util::test_utils::{response, FnTransport};
+
+    const SHINJUKU: &'static str = "ee9e9c42-4a58-4fb3-9a44-7fbb4fc82f2b";
+    const TOKYO: &'static str = "6d3c5c4f-7b2c-4a9e-a7f4-8f5f4b1d0c3e";
+    const JAPAN: &'static str = "2db42837-c832-3c27-b4a3-08198f75693c";
+
+    /// Answers requests for the areas `Shinjuku`, `Tokyo` and `Japan`.
+    fn area_response(request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let mbid = request.url.path().rsplit('/').next().unwrap();
+        let area = |name: &str, area_type: &str, parent: Option<(&str, &str)>| {
+            let relations = match parent {
+                Some((id, name)) => format!(r#"<relation-list target-type="area"><relation type="part of" type-id="de7cc874-8b1b-3a05-8272-f3834c968fb7"><target>{id}</target><direction>backward</direction><area id="{id}"><name>{name}</name><sort-name>{name}</sort-name></area></relation></relation-list>"#, id = id, name = name),
+                None => String::new(),
+            };
+            format!(r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area type="{}" id="{}"><name>{}</name><sort-name>{}</sort-name>{}</area></metadata>"#, area_type, mbid, name, name, relations)
+        };
+        let body = match mbid {
+            SHINJUKU => area("Shinjuku", "District", Some((TOKYO, "Tokyo"))),
+            TOKYO => area("Tokyo", "Subdivision", Some((JAPAN, "Japan"))),
+            JAPAN => area("Japan", "Country", None),
+            _ => panic!("unexpected request for {}", mbid),
+        };
+        Ok(response(200, body))
+    }
+
+    fn area_client() -> Client {
+        Client::with_transport(
+            crate::util::test_utils::config()
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            FnTransport(area_response),
+        )
+    }
+
+    #[test]
+    fn chain_to_country() {
+        let mut client = area_client();
+        let chain = fetch_chain(&mut client, &SHINJUKU.parse().unwrap()).unwrap();
+
+        let names: Vec<&str> = chain.areas().iter().map(|a| a.name().as_str()).collect();
+        assert_eq!(names, vec!["Shinjuku", "Tokyo", "Japan"]);
+        assert_eq!(chain.area().name(), "Shinjuku");
+        assert_eq!(chain.country().unwrap().mbid(), &JAPAN.parse().unwrap());
+
+        let chain = fetch_chain(&mut client, &JAPAN.parse().unwrap()).unwrap();
+        assert_eq!(chain.areas().len(), 1);
+        assert_eq!(chain.country().unwrap().name(), "Japan");
+    }
+
+    #[test]
+    fn cached_areas() {
+        let mut client = area_client();
+        fetch_chain(&mut client, &SHINJUKU.parse().unwrap()).unwrap();
+        assert_eq!(client.stats().requests, 3);
+
+        // All areas of the chain are cached.
+        fetch_chain(&mut client, &SHINJUKU.parse().unwrap()).unwrap();
+        let chain = fetch_chain(&mut client, &TOKYO.parse().unwrap()).unwrap();
+        assert_eq!(chain.areas().len(), 2);
+        assert_eq!(client.stats().requests, 3);
+
+        client.clear_area_cache();
+        fetch_chain(&mut client, &TOKYO.parse().unwrap()).unwrap();
+        assert_eq!(client.stats().requests, 5);
+    }
+}
@@ -0,0 +1,81 @@
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A cached response body along with its `ETag`.
+#[derive(Clone, Debug)]
+pub(crate) struct CacheEntry {
+    pub etag: String,
+    pub body: String,
+}
+
+/// Bounded cache of response bodies by url, used to perform conditional
+/// requests.
+pub(crate) type EtagCache = BoundedCache<String, CacheEntry>;
+
+/// Cache holding at most `capacity` entries.
+///
+/// If the cache is full the oldest entry is evicted.
+#[derive(Clone, Debug)]
+pub(crate) struct BoundedCache<K, V> {
+    capacity: usize,
+    entries: HashMap<K, V>,
+    /// Keys of the entries, oldest first.
+    order: VecDeque<K>,
+}
+
+impl<K: Clone + Eq + Hash, V> BoundedCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        BoundedCache {
+            capacity,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.entries.get(key)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.contains_key(&key) {
+            self.order.retain(|k| k != &key);
+        } else if self.entries.len() >= self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.order.push_back(key.clone());
+        self.entries.insert(key, value);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eviction() {
+        let mut cache = BoundedCache::new(2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        cache.insert("a", 3);
+        cache.insert("c", 4);
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a"), Some(&3));
+        assert_eq!(cache.get("c"), Some(&4));
+    }
+}
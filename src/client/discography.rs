@@ -0,0 +1,226 @@
+//! The discography of an artist, grouped by the type of its release groups.
+//!
+//! This combines browsing the release groups and, if requested, the releases
+//! of an artist, so an artist page can be shown with as few requests as
+//! possible. All pages are fetched internally, respecting the rate limit of
+//! the client.
+
+use crate::browse::browse_entities::Release;
+use crate::client::Client;
+use crate::entities::{Mbid, ReleaseGroupPrimaryType, ReleaseGroupRef};
+use crate::error::Error;
+use crate::progress::Progress;
+
+/// Specifies which parts of the discography are fetched.
+#[derive(Clone, Debug)]
+pub struct DiscographyOptions {
+    /// Only release groups with one of these primary types are included.
+    pub types: Vec<ReleaseGroupPrimaryType>,
+
+    /// Include release groups with secondary types, e.g. compilations, live
+    /// albums or remixes.
+    pub secondary_types: bool,
+
+    /// Also fetch the releases of every release group.
+    ///
+    /// This requires additional requests, one per 100 releases of the artist.
+    pub releases: bool,
+}
+
+impl Default for DiscographyOptions {
+    fn default() -> Self {
+        DiscographyOptions {
+            types: vec![
+                ReleaseGroupPrimaryType::Album,
+                ReleaseGroupPrimaryType::Single,
+                ReleaseGroupPrimaryType::EP,
+            ],
+            secondary_types: false,
+            releases: false,
+        }
+    }
+}
+
+/// A release group of a `Discography`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiscographyEntry {
+    /// The release group itself.
+    pub release_group: ReleaseGroupRef,
+
+    /// The year of the first release of the release group.
+    pub year: Option<u16>,
+
+    /// The releases of the release group, only fetched if requested.
+    pub releases: Vec<Release>,
+}
+
+/// The discography of an artist, as returned by `fetch`.
+///
+/// Every list is ordered by year, release groups without a known year come
+/// last.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Discography {
+    pub albums: Vec<DiscographyEntry>,
+    pub singles: Vec<DiscographyEntry>,
+    pub eps: Vec<DiscographyEntry>,
+
+    /// Release groups of all other primary types.
+    pub other: Vec<DiscographyEntry>,
+}
+
+/// Fetch the discography of the artist with the MBID `artist`.
+pub fn fetch(
+    client: &mut Client,
+    artist: &Mbid,
+    options: DiscographyOptions,
+) -> Result<Discography, Error> {
+    fetch_with_progress(client, artist, options, |_, _| {})
+}
+
+/// Fetch the discography like `fetch`, reporting the number of fetched
+/// release groups and releases to `progress` after every page.
+///
+/// The releases are only counted once all release groups were fetched, so the
+/// total grows when the first page of releases was fetched.
+pub fn fetch_with_progress<P: Progress>(
+    client: &mut Client,
+    artist: &Mbid,
+    options: DiscographyOptions,
+    mut progress: P,
+) -> Result<Discography, Error> {
+    let mut groups = 0;
+    let release_groups = client
+        .browse_release_groups_by_artist(artist)
+        .release_types(&options.types)
+        .progress(|done, total| {
+            groups = total;
+            progress.on_progress(done, total)
+        })
+        .all()?;
+    let releases = if options.releases {
+        client
+            .browse_releases_by_artist(artist)
+            .with_release_groups()
+            .progress(|done, total| progress.on_progress(groups + done, groups + total))
+            .all()?
+    } else {
+        Vec::new()
+    };
+
+    Ok(Discography::new(release_groups, releases, &options))
+}
+
+impl Discography {
+    /// Assemble a discography from the browsed release groups and releases.
+    fn new(
+        release_groups: Vec<ReleaseGroupRef>,
+        releases: Vec<Release>,
+        options: &DiscographyOptions,
+    ) -> Self {
+        let mut discography = Discography::default();
+
+        for release_group in release_groups {
+            let release_type = &release_group.release_type;
+            if !options.secondary_types && !release_type.secondary.is_empty() {
+                continue;
+            }
+            let list = match release_type.primary {
+                Some(ReleaseGroupPrimaryType::Album) => &mut discography.albums,
+                Some(ReleaseGroupPrimaryType::Single) => &mut discography.singles,
+                Some(ReleaseGroupPrimaryType::EP) => &mut discography.eps,
+                _ => &mut discography.other,
+            };
+            list.push(DiscographyEntry {
+                year: release_group
+                    .first_release_date
+                    .as_ref()
+                    .and_then(|d| d.year()),
+                releases: releases
+                    .iter()
+                    .filter(|r| {
+                        r.release_group.as_ref().map(|g| &g.mbid) == Some(&release_group.mbid)
+                    })
+                    .cloned()
+                    .collect(),
+                release_group: release_group,
+            });
+        }
+
+        for list in vec![
+            &mut discography.albums,
+            &mut discography.singles,
+            &mut discography.eps,
+            &mut discography.other,
+        ] {
+            // Sorting is stable, so the order of the server is kept within a
+            // year.
+            list.sort_by_key(|e| (e.year.is_none(), e.year));
+        }
+        discography
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browse::parse_page;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::util::test_utils::{response, FnTransport};
+
+    const RELEASE_GROUPS: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-group-list count="4" offset="0"><release-group type="Album" id="b1392450-e666-3926-a536-22c65f834433"><title>OK Computer</title><first-release-date>1997-05-21</first-release-date><primary-type>Album</primary-type></release-group><release-group type="Album" id="1b022e01-4da6-387b-8658-8678046e4cef"><title>Pablo Honey</title><first-release-date>1993-02-22</first-release-date><primary-type>Album</primary-type></release-group><release-group type="Single" id="a0b0e1f4-9e3c-4e8e-8cd0-0e6e6e4b5c1a"><title>Creep</title><first-release-date>1992-09-21</first-release-date><primary-type>Single</primary-type></release-group><release-group type="Live" id="f8e6e6a4-3a4c-3b5c-9a4e-6a5c1b0f8a2d"><title>I Might Be Wrong</title><first-release-date>2001-11-12</first-release-date><primary-type>Album</primary-type><secondary-type-list><secondary-type>Live</secondary-type></secondary-type-list></release-group></release-group-list></metadata>"#;
+
+    const RELEASES: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-list count="1" offset="0"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><status>Official</status><date>1992-09-21</date><country>GB</country><release-group type="Single" id="a0b0e1f4-9e3c-4e8e-8cd0-0e6e6e4b5c1a"><title>Creep</title><primary-type>Single</primary-type></release-group></release></release-list></metadata>"#;
+
+    #[test]
+    fn assemble() {
+        let release_groups = parse_page(RELEASE_GROUPS).unwrap().entities;
+        let releases = parse_page(RELEASES).unwrap().entities;
+        let discography = Discography::new(release_groups, releases, &DiscographyOptions::default());
+
+        let albums: Vec<&str> = discography
+            .albums
+            .iter()
+            .map(|e| e.release_group.title.as_str())
+            .collect();
+        assert_eq!(albums, vec!["Pablo Honey", "OK Computer"]);
+        assert_eq!(discography.albums[0].year, Some(1993));
+        assert_eq!(discography.singles.len(), 1);
+        assert_eq!(discography.singles[0].releases.len(), 1);
+        assert_eq!(
+            discography.singles[0].releases[0].title,
+            "Creep".to_string()
+        );
+        assert!(discography.eps.is_empty());
+        assert!(discography.other.is_empty());
+    }
+
+    /// Answers the browse of the release groups and of the releases.
+    fn discography_response(request: &HttpRequest) -> Result<HttpResponse, Error> {
+        if request.url.path() == "/ws/2/release-group" {
+            Ok(response(200, RELEASE_GROUPS))
+        } else {
+            assert_eq!(request.url.path(), "/ws/2/release");
+            Ok(response(200, RELEASES))
+        }
+    }
+
+    #[test]
+    fn fetch_progress() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let mut client = Client::with_transport(config, FnTransport(discography_response));
+        let artist = "a74b1b7f-71a5-4011-9441-d0b5e4122711".parse().unwrap();
+        let options = DiscographyOptions {
+            releases: true,
+            ..DiscographyOptions::default()
+        };
+
+        let mut reports = Vec::new();
+        let discography = fetch_with_progress(&mut client, &artist, options, |done, total| {
+            reports.push((done, total))
+        })
+        .unwrap();
+        assert_eq!(discography.albums.len(), 2);
+        assert_eq!(discography.singles[0].releases.len(), 1);
+        assert_eq!(reports, vec![(4, 4), (5, 5)]);
+    }
+}
@@ -1,4 +1,6 @@
+use regex::Regex;
 use xpath_reader::Reader;
+use crate::client::HttpResponse;
 use crate::error::{Error, ErrorKind};
 
 /// Checks if there is an error in the document provided by the reader and
@@ -21,6 +23,69 @@ pub fn check_response_error<'d>(reader: &'d Reader<'d>) -> Result<(), Error> {
     }
 }
 
+/// Builds the error for a response with an error status like 404.
+///
+/// Depending on the endpoint and the status, MusicBrainz returns XML or JSON
+/// error documents, HTML pages (e.g. from the proxy in front of the server)
+/// or plain text, so the message is extracted from any of these.
+///
+/// The error carries the url, the status and the headers describing the
+/// failure, e.g. `Retry-After`.
+pub(crate) fn status_error(url: &str, response: &HttpResponse) -> Error {
+    let status = response.status;
+    let kind = match status {
+        400 => ErrorKind::BadRequest,
+        404 => ErrorKind::NotFound,
+        429 | 503 => ErrorKind::RateLimited,
+        _ => ErrorKind::ServerError,
+    };
+    let message = match error_message(&String::from_utf8_lossy(&response.body)) {
+        Some(message) => format!("MusicBrainz returned {}: {}", status, message),
+        None => format!("MusicBrainz returned {}.", status),
+    };
+    Error::new(message, kind).with_response(url, response)
+}
+
+/// Extracts the message of an error document in any of the formats returned
+/// by MusicBrainz.
+fn error_message(body: &str) -> Option<String> {
+    let body = body.trim();
+    if body.starts_with('{') {
+        // {"error": "Not Found", "help": "..."}
+        let error = Regex::new(r#""error"\s*:\s*"((?:[^"\\]|\\.)*)""#).unwrap();
+        let escaped = error.captures(body)?.get(1)?.as_str();
+        let mut message = String::new();
+        let mut chars = escaped.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                message.extend(chars.next());
+            } else {
+                message.push(c);
+            }
+        }
+        Some(message)
+    } else if body.starts_with('<') {
+        if let Ok(reader) = Reader::from_str(body, None) {
+            let texts: Vec<String> = reader.read("//error/text").unwrap_or_default();
+            if !texts.is_empty() {
+                return Some(texts.join("\n"));
+            }
+        }
+        // Not an error document, but possibly an HTML page with a title.
+        let title = Regex::new(r"(?is)<title>(.*?)</title>").unwrap();
+        let title = title.captures(body)?.get(1)?.as_str().trim();
+        if title.is_empty() {
+            None
+        } else {
+            Some(title.to_string())
+        }
+    } else if body.is_empty() {
+        None
+    } else {
+        Some(body.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +111,41 @@ mod tests {
             "[server error]: Your requests are exceeding the allowable rate limit. Please see http://wiki.musicbrainz.org/XMLWebService for more information.\nFor usage, please see: http://musicbrainz.org/development/mmd"));
     }
 
+    #[test]
+    fn status_errors() {
+        let url = "https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c?inc=foo";
+        let error = |status: u16, body: &str| {
+            let response = HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: body.as_bytes().to_vec(),
+            };
+            status_error(url, &response)
+        };
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><error><text>Not Found</text></error>"#;
+        let err = error(404, xml);
+        assert!(err.is_not_found());
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(err.url(), Some(url));
+        assert!(err.to_string().starts_with("[not found]: MusicBrainz returned 404: Not Found\n"));
+
+        let json = r#"{"error": "\"foo\" is not a valid inc parameter.", "help": "..."}"#;
+        let err = error(400, json);
+        assert!(err.is_bad_request());
+        assert!(err.to_string().contains("returned 400: \"foo\" is not a valid inc parameter.\n"));
+
+        let html = "<html><head><title>503 Service Temporarily Unavailable</title></head></html>";
+        let err = error(503, html);
+        assert!(err.is_rate_limited());
+        assert!(err.to_string().contains("returned 503: 503 Service Temporarily Unavailable\n"));
+
+        let err = error(502, "");
+        assert!(err.is_server_error());
+        assert_eq!(err.status(), Some(502));
+        assert!(err.to_string().contains("MusicBrainz returned 502.\n"));
+    }
+
     #[test]
     fn ok() {
         let context = crate::util::musicbrainz_context();
@@ -0,0 +1,206 @@
+//! High level functions performing common tasks with as few requests as
+//! possible and returning the results in a flat, ready to display form.
+
+use crate::client::Client;
+use crate::entities::{
+    ArtistCredit, ArtistRef, Country, LabelInfo, Mbid, PartialDate, Release, ReleaseGroupRef, ReleaseOptions,
+    ReleaseStatus, TrackLength,
+};
+use crate::error::Error;
+
+/// A release with all its tracks, as returned by `fetch_full_release`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FullRelease {
+    /// MBID of the release.
+    pub mbid: Mbid,
+
+    /// The title of the release.
+    pub title: String,
+
+    /// The artists credited on the release, formatted for display.
+    pub artist: String,
+
+    /// The artists credited on the release.
+    pub artists: Vec<ArtistRef>,
+
+    /// The date the release was issued.
+    pub date: Option<PartialDate>,
+
+    /// The country the release was issued in.
+    pub country: Option<Country>,
+
+    /// Release status of the release.
+    pub status: Option<ReleaseStatus>,
+
+    /// Barcode of the release, if it has one.
+    pub barcode: Option<String>,
+
+    /// The labels which issued the release.
+    pub labels: Vec<LabelInfo>,
+
+    /// The release group the release belongs to.
+    pub release_group: Option<ReleaseGroupRef>,
+
+    /// The number of mediums of the release.
+    pub medium_count: u16,
+
+    /// The tracks of all mediums, ordered by medium and position.
+    pub tracks: Vec<FullTrack>,
+}
+
+/// A track of a `FullRelease`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FullTrack {
+    /// MBID of the track.
+    pub mbid: Mbid,
+
+    /// MBID of the recording used for the track.
+    pub recording: Mbid,
+
+    /// Position of the medium containing the track.
+    pub medium: u16,
+
+    /// Format of the medium containing the track.
+    pub medium_format: Option<String>,
+
+    /// Position of the track on its medium.
+    pub position: u16,
+
+    /// The track number as printed on the release.
+    pub number: String,
+
+    /// The title of the track.
+    pub title: String,
+
+    /// The artists credited for the track, formatted for display.
+    ///
+    /// Tracks without an artist credit of their own in the response get the
+    /// credit of the release.
+    pub artist: String,
+
+    /// The length of the track, falling back to the length of the recording.
+    pub length: Option<TrackLength>,
+}
+
+/// Fetch a release with its artists, labels, release group and all tracks in
+/// one request.
+pub fn fetch_full_release(client: &mut Client, mbid: &Mbid) -> Result<FullRelease, Error> {
+    let release: Release = client.get_by_mbid(mbid, ReleaseOptions::everything())?;
+    Ok(FullRelease::from_release(&release))
+}
+
+impl FullRelease {
+    /// Flatten an already fetched release.
+    ///
+    /// Data which wasn't requested when fetching the release is left empty.
+    pub fn from_release(release: &Release) -> Self {
+        let artists = release.artists().into_option().unwrap_or(&[]).to_vec();
+        let artist = credit_string(release.artist_credit().into_option().unwrap_or(&[]));
+        let mediums = release.mediums().into_option().unwrap_or(&[]);
+
+        let tracks = mediums
+            .iter()
+            .flat_map(|medium| {
+                let artist = &artist;
+                medium.tracks().iter().map(move |track| FullTrack {
+                    mbid: track.mbid.clone(),
+                    recording: track.recording.mbid.clone(),
+                    medium: medium.position(),
+                    medium_format: medium.format().cloned(),
+                    position: track.position,
+                    number: track.number.clone(),
+                    title: track.title.clone(),
+                    artist: if track.artist_credit.is_empty() {
+                        artist.clone()
+                    } else {
+                        credit_string(&track.artist_credit)
+                    },
+                    length: track.length.or(track.recording.length),
+                })
+            })
+            .collect();
+
+        FullRelease {
+            mbid: release.mbid().clone(),
+            title: release.title().clone(),
+            artist: artist,
+            artists: artists,
+            date: release.date().cloned(),
+            country: release.country().cloned(),
+            status: release.status(),
+            barcode: release.barcode().cloned(),
+            labels: release.labels().into_option().unwrap_or(&[]).to_vec(),
+            release_group: release.release_group().into_option().cloned(),
+            medium_count: release
+                .medium_count()
+                .into_option()
+                .unwrap_or(mediums.len() as u16),
+            tracks: tracks,
+        }
+    }
+}
+
+/// Format an artist credit for display, as printed on the release, e.g.
+/// `A feat. B`.
+fn credit_string(credits: &[ArtistCredit]) -> String {
+    credits
+        .iter()
+        .flat_map(|c| vec![c.name.as_str(), c.join_phrase.as_str()])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_release() {
+        let mbid: Mbid = "ed118c5f-d940-4b52-a37b-b1a205374abe".parse().unwrap();
+        let options = ReleaseOptions {
+            annotation: true,
+            artists: true,
+            recordings: true,
+            labels: true,
+            ..ReleaseOptions::minimal()
+        };
+        let release: Release = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
+        let full = FullRelease::from_release(&release);
+
+        assert_eq!(full.title, "Creep".to_string());
+        assert_eq!(full.artist, "Radiohead".to_string());
+        assert_eq!(full.labels.len(), 2);
+        assert_eq!(full.release_group, None);
+        assert_eq!(full.medium_count, 1);
+        assert_eq!(full.tracks.len(), 4);
+        assert_eq!(
+            full.tracks[2],
+            FullTrack {
+                mbid: "6acd9fa0-e9a5-39b3-a0b4-70cd9e8bae01".parse().unwrap(),
+                recording: "10b05f6e-e670-48c2-b269-6b442fde6736".parse().unwrap(),
+                medium: 1,
+                medium_format: Some("CD".to_string()),
+                position: 3,
+                number: "3".to_string(),
+                title: "Inside My Head".to_string(),
+                artist: "Radiohead".to_string(),
+                length: Some(TrackLength::from_millis(191826)),
+            }
+        );
+    }
+
+    #[test]
+    fn credited_track_artists() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="b8b5a7d6-4bb2-4d8b-9a2f-6b5c2d4e1f3a"><title>Kid A Mnesia</title><artist-credit><name-credit><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit><medium-list count="1"><medium><position>1</position><track-list count="2" offset="0"><track id="6acd9fa0-e9a5-39b3-a0b4-70cd9e8bae01"><position>1</position><number>1</number><title>Everything in Its Right Place</title><recording id="10b05f6e-e670-48c2-b269-6b442fde6736"><title>Everything in Its Right Place</title></recording></track><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>2</position><number>2</number><title>Pulk/Pull</title><artist-credit><name-credit joinphrase=" feat. "><name>On a Friday</name><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit><name-credit><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></name-credit></artist-credit><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Pulk/Pull</title></recording></track></track-list></medium></medium-list></release></metadata>"#;
+        let options = ReleaseOptions {
+            artists: true,
+            recordings: true,
+            ..ReleaseOptions::minimal()
+        };
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+        let full = FullRelease::from_release(&release);
+
+        assert_eq!(full.artist, "Radiohead".to_string());
+        assert_eq!(full.tracks[0].artist, "Radiohead".to_string());
+        assert_eq!(full.tracks[1].artist, "On a Friday feat. Thom Yorke".to_string());
+    }
+}
@@ -0,0 +1,171 @@
+use super::HttpResponse;
+
+use std::time::Duration;
+
+/// Metadata about a response of the API.
+///
+/// Sync tools can use it to decide whether an entity has to be fetched
+/// again, e.g. by passing the `etag` to `Client::get_by_mbid_if_none_match`.
+///
+/// # Freshness
+///
+/// The API doesn't provide the number of edits of an entity or the time it
+/// was last edited, so the version of an entity can only be told by its
+/// response:
+///
+/// - `etag` changes whenever the body of the response changes. It is only
+///   comparable between responses to the same url, i.e. fetched with the
+///   same options.
+/// - `last_modified` is only set if the server sends the header, which it
+///   currently doesn't for lookups.
+/// - `created` is the time the document was generated, which is only
+///   included in search results.
+///
+/// These fields are kept as they are, but the server might stop sending any
+/// of them, so all of them are optional.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ResponseMeta {
+    /// The HTTP status code of the response.
+    pub status: u16,
+
+    /// The url which was requested, after replacing the host if the client
+    /// uses a different server.
+    pub url: String,
+
+    /// How long the request took in total, including waiting for the rate
+    /// limit and retries.
+    pub duration: Duration,
+
+    /// How many times the request was retried because the server was
+    /// unavailable.
+    pub retries: u64,
+
+    /// The number of bytes of the response body.
+    pub size: u64,
+
+    /// The `ETag` header of the response.
+    pub etag: Option<String>,
+
+    /// The `Last-Modified` header of the response.
+    pub last_modified: Option<String>,
+
+    /// The time the response was created at as specified by the `created`
+    /// attribute of the document, which is included in search results.
+    pub created: Option<String>,
+
+    /// Problems noticed while parsing the response which didn't prevent it
+    /// from being parsed, e.g. elements which were ignored.
+    #[cfg(feature = "diagnostics")]
+    pub warnings: Vec<String>,
+}
+
+impl ResponseMeta {
+    /// Collect the metadata of `response`, which was received for `url`
+    /// `duration` after the request was started and after `retries` retries.
+    pub(crate) fn new(
+        response: &HttpResponse,
+        url: String,
+        duration: Duration,
+        retries: u64,
+    ) -> Self {
+        ResponseMeta {
+            status: response.status,
+            url,
+            duration,
+            retries,
+            size: response.body.len() as u64,
+            etag: response.header("ETag").map(String::from),
+            last_modified: response.header("Last-Modified").map(String::from),
+            created: read_created(&response.body),
+            #[cfg(feature = "diagnostics")]
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Whether this response and `other` contain the same version of the
+    /// requested data, according to their `ETag`s.
+    ///
+    /// Returns `None` if one of them has no `ETag`. Weak and strong `ETag`s
+    /// are considered equal if their values are.
+    ///
+    /// The `ETag` also depends on the requested data, so only responses to
+    /// requests with the same options can be compared. A mirror should
+    /// therefore fetch an entity with the same options every time, e.g. the
+    /// `for_mirror` options of the entity, which request the annotation and
+    /// genres a mirror keeps along with it.
+    pub fn same_version(&self, other: &ResponseMeta) -> Option<bool> {
+        let value = |etag: &str| etag.trim_start_matches("W/").to_string();
+        match (self.etag.as_ref(), other.etag.as_ref()) {
+            (Some(a), Some(b)) => Some(value(a) == value(b)),
+            _ => None,
+        }
+    }
+}
+
+/// Read the `created` attribute from the root element of `body`.
+///
+/// Only the start tag of the root element is inspected, so this doesn't
+/// require parsing the whole document a second time.
+fn read_created(body: &[u8]) -> Option<String> {
+    let find =
+        |haystack: &[u8], needle: &[u8]| haystack.windows(needle.len()).position(|w| w == needle);
+    let tag = &body[find(body, b"<metadata")?..];
+    let tag = &tag[..tag.iter().position(|&b| b == b'>')?];
+    let value = &tag[find(tag, b" created=\"")? + 10..];
+    let end = value.iter().position(|&b| b == b'"')?;
+    String::from_utf8(value[..end].to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response_meta(etag: Option<&str>, body: &str) -> ResponseMeta {
+        let response = HttpResponse {
+            status: 200,
+            headers: etag
+                .map(|e| vec![("ETag".to_string(), e.to_string())])
+                .unwrap_or_default(),
+            body: body.as_bytes().to_vec(),
+        };
+        ResponseMeta::new(
+            &response,
+            "https://musicbrainz.org/ws/2/area/".to_string(),
+            Duration::from_millis(20),
+            1,
+        )
+    }
+
+    #[test]
+    fn created() {
+        let meta = response_meta(
+            None,
+            r#"<?xml version="1.0"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-group-list/></metadata>"#,
+        );
+        assert_eq!(meta.created, Some("2017-05-06T09:45:01.432Z".to_string()));
+        assert_eq!(meta.size, 141);
+        assert_eq!(meta.retries, 1);
+
+        assert_eq!(
+            read_created(br#"<?xml version="1.0"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area created="x"/></metadata>"#),
+            None
+        );
+    }
+
+    #[test]
+    fn same_version() {
+        let meta = |etag: Option<&str>| response_meta(etag, "");
+
+        let old = meta(Some("W/\"370dc96072bf4f2e4ff3c406a238acde\""));
+        assert_eq!(
+            old.same_version(&meta(Some("\"370dc96072bf4f2e4ff3c406a238acde\""))),
+            Some(true)
+        );
+        assert_eq!(
+            old.same_version(&meta(Some("W/\"1f3870be274f6c49b3e31a0c6728957f\""))),
+            Some(false)
+        );
+        assert_eq!(old.same_version(&meta(None)), None);
+    }
+}
@@ -1,34 +1,83 @@
 //! Contains the types and functions to communicate with the MusicBrainz API.
 
 use crate::error::{Error, ErrorKind};
-use crate::entities::{Mbid, ResourceOld, Resource};
+use crate::entities::{AnyEntity, Area, Entity, EntityKind, Include, Mbid, Relation, Resource};
 
-use reqwest_mock::Client as MockClient;
+#[cfg(feature = "default-transport")]
 use reqwest_mock::GenericClient as HttpClient;
-use reqwest_mock::{StatusCode, Url};
-use reqwest_mock::header::UserAgent;
+use url::Url;
 use xpath_reader::reader::{FromXml, Reader};
 
+use std::cmp::{max, min};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 use std::thread::sleep;
 
-use crate::search::{ReleaseGroupSearchBuilder, SearchBuilder};
+use crate::browse::browse_entities;
+use crate::browse::Browse;
+use crate::entities::{EventRef, ReleaseGroupRef};
+use crate::paging::{Limit, Offset};
+use crate::search::fields::release::{CatalogNumber, LabelId};
+use crate::search::search_entities;
+use crate::search::{AreaSearchBuilder, ArtistSearchBuilder, EventSearchBuilder, LabelSearchBuilder,
+                    PlaceSearchBuilder, RecordingSearchBuilder, ReleaseGroupSearchBuilder,
+                    ReleaseQuery, ReleaseSearchBuilder, SearchBuilder, SearchEntry, SearchQuery,
+                    SearchResult, SeriesSearchBuilder};
 
 mod error;
 pub(crate) use self::error::check_response_error;
+use self::error::status_error;
 
-/// Helper extracting the number of milliseconds from a `Duration`.
-fn as_millis(duration: &Duration) -> u64 {
-    ((duration.as_secs() as f64) + (duration.subsec_nanos() as f64) * 1e6) as u64
-}
+mod transport;
+pub use self::transport::{HttpRequest, HttpResponse, HttpTransport};
+#[cfg(feature = "default-transport")]
+pub use self::transport::ReqwestTransport;
 
-/// Returns an `Instant` at least 1000 seconds ago.
-fn past_instant() -> Instant {
-    Instant::now() - Duration::new(1000, 0)
-}
+mod cache;
+use self::cache::{BoundedCache, CacheEntry, EtagCache};
+
+pub mod areas;
+pub mod discography;
+pub mod helpers;
+
+mod meta;
+pub use self::meta::ResponseMeta;
+
+mod ping;
+pub use self::ping::Ping;
+
+mod pool;
+pub use self::pool::ClientPool;
+use self::pool::RateLimiter;
+
+mod stats;
+pub use self::stats::ClientStats;
+
+mod tracks;
+pub use self::tracks::TrackLocation;
+
+mod user_agent;
+pub use self::user_agent::UserAgent;
+
+/// Interval in which waiting clients check whether they were cancelled.
+const CANCEL_POLL_INTERVAL: u64 = 50;
+
+/// Maximal number of areas cached by `areas::fetch_chain`.
+const AREA_CACHE_CAPACITY: usize = 1000;
+
+/// Host of the MusicBrainz server all urls are built for.
+const MUSICBRAINZ_HOST: &'static str = "musicbrainz.org";
+
+/// Host of the MusicBrainz test server, whose data is reset regularly.
+const TEST_SERVER_HOST: &'static str = "test.musicbrainz.org";
 
 /// Configuration for the client.
+///
+/// Created with `ClientConfig::builder`, which validates the configuration.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub struct ClientConfig {
     /// The user-agent to be sent with every request to the API.
     ///
@@ -42,6 +91,9 @@ pub struct ClientConfig {
     ///
     /// For more information see:
     /// https://musicbrainz.org/doc/XML_Web_Service/Rate_Limiting
+    ///
+    /// Use `UserAgent` to create a user agent in this format, which can be
+    /// converted into a `String` with `into()`.
     pub user_agent: String,
 
     /// How many times to retry requests where MusicBrainz returned 503 because
@@ -50,6 +102,131 @@ pub struct ClientConfig {
 
     /// Specifies amounts of time to wait between certain actions.
     pub waits: ClientWaits,
+
+    /// Skip parts of responses which can't be parsed, e.g. a single broken
+    /// track of a release, instead of failing the whole request.
+    ///
+    /// The skipped parts are listed in the `parse_warnings` of the entity.
+    pub lenient: bool,
+
+    /// Maximal size of a response body in bytes, larger responses fail with
+    /// an error for which `Error::is_response_too_large` is true.
+    ///
+    /// The limit is passed on to the `HttpTransport`, so transports which
+    /// support it, like the default one, can stop reading the body early.
+    pub max_response_size: Option<u64>,
+}
+
+impl ClientConfig {
+    /// Returns a builder for a configuration with sensible defaults.
+    ///
+    /// Only the user agent has to be specified.
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder {
+            user_agent: None,
+            max_retries: 5,
+            waits: ClientWaits::default(),
+            lenient: false,
+            max_response_size: None,
+        }
+    }
+}
+
+/// Builder for a `ClientConfig`, created by `ClientConfig::builder`.
+#[derive(Clone, Debug)]
+pub struct ClientConfigBuilder {
+    user_agent: Option<String>,
+    max_retries: u8,
+    waits: ClientWaits,
+    lenient: bool,
+    max_response_size: Option<u64>,
+}
+
+impl ClientConfigBuilder {
+    /// The user-agent sent with every request, either a `UserAgent` or a
+    /// string in the same format.
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// How many times requests are attempted if MusicBrainz is unavailable,
+    /// 5 by default.
+    pub fn max_retries(mut self, max_retries: u8) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Initial wait time in milliseconds for the exponential backoff after
+    /// MusicBrainz was unavailable, 400 by default.
+    pub fn backoff_init(mut self, millis: u64) -> Self {
+        self.waits.backoff_init = millis;
+        self
+    }
+
+    /// Minimal time in milliseconds between requests, so tests don't have to
+    /// wait for the rate limit.
+    #[cfg(test)]
+    pub(crate) fn request_interval(mut self, millis: u64) -> Self {
+        self.waits.requests = millis;
+        self
+    }
+
+    /// Maximal time in milliseconds a single HTTP request may take.
+    ///
+    /// Replaying clients don't support it and fail every request if it's
+    /// set.
+    pub fn request_timeout(mut self, millis: u64) -> Self {
+        self.waits.request_timeout = Some(millis);
+        self
+    }
+
+    /// Maximal time in milliseconds a request to the API may take in total,
+    /// including waits and retries.
+    pub fn total_timeout(mut self, millis: u64) -> Self {
+        self.waits.total_timeout = Some(millis);
+        self
+    }
+
+    /// Skip parts of responses which can't be parsed instead of failing the
+    /// request, false by default.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Maximal size of a response body in bytes, unlimited by default.
+    pub fn max_response_size(mut self, bytes: u64) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Validate the configuration, returning a usage error if it's invalid.
+    pub fn build(self) -> Result<ClientConfig, Error> {
+        let invalid = |msg: &str| Err(Error::new(msg, ErrorKind::UsageError));
+
+        let user_agent = match self.user_agent {
+            Some(ref ua) if !ua.trim().is_empty() => ua.clone(),
+            _ => return invalid("A user agent is required."),
+        };
+        if self.max_retries == 0 {
+            return invalid("At least one attempt has to be allowed (max_retries > 0).");
+        }
+        if self.waits.request_timeout == Some(0) || self.waits.total_timeout == Some(0) {
+            return invalid("Timeouts have to be longer than 0 ms.");
+        }
+        if self.max_response_size == Some(0) {
+            return invalid("The maximal response size has to be at least 1 byte.");
+        }
+
+        Ok(ClientConfig {
+            user_agent: user_agent,
+            max_retries: self.max_retries,
+            waits: self.waits,
+            lenient: self.lenient,
+            max_response_size: self.max_response_size,
+        })
+    }
 }
 
 /// Specification of the wait time between requests.
@@ -65,6 +242,16 @@ pub struct ClientWaits {
     //       to make abuse of the main servers harder.
     /// Minimal time between requests
     requests: u64,
+
+    /// Maximal time a single HTTP request may take.
+    ///
+    /// This is passed on to the `HttpTransport`, note that the replaying
+    /// transport does not support it and rejects requests with a timeout.
+    pub request_timeout: Option<u64>,
+
+    /// Maximal time a request to the API may take in total, including waits
+    /// because of rate limiting and retries.
+    pub total_timeout: Option<u64>,
 }
 
 impl Default for ClientWaits {
@@ -72,24 +259,85 @@ impl Default for ClientWaits {
         ClientWaits {
             backoff_init: 400,
             requests: 1000,
+            request_timeout: None,
+            total_timeout: None,
         }
     }
 }
 
+/// Allows cancelling the requests of a `Client`, possibly from another
+/// thread.
+///
+/// Cancelling a token makes the pending and all future requests of the
+/// clients using it fail, until the token is reset.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancel all requests made with this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the token was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Allow making requests again after the token was cancelled.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::SeqCst);
+    }
+}
+
 /// The main struct to be used to communicate with the MusicBrainz API.
 ///
 /// Please create only one instance and use it troughout your application
 /// as it will ensure appropriate wait times between requests to prevent
-/// being blocked for making to many requests.
+/// being blocked for making to many requests. If requests have to be made
+/// from multiple threads use a `ClientPool` instead.
 pub struct Client {
-    http_client: HttpClient,
+    transport: Box<dyn HttpTransport + Send>,
     config: ClientConfig,
 
-    /// The time the last request was made.
     /// According to the documentation we have to wait at least one second
-    /// between any two requests
-    /// to the MusicBrainz API.
-    last_request: Instant,
+    /// between any two requests to the MusicBrainz API.
+    rate_limit: RateLimiter,
+
+    /// If set, every response is recorded again, even if a recording for the
+    /// request already exists.
+    force_record: bool,
+
+    /// Token used to cancel requests.
+    cancellation: CancellationToken,
+
+    /// Bodies of previous responses used for conditional requests, if
+    /// enabled.
+    etag_cache: Option<EtagCache>,
+
+    /// Maximal number of bytes of a response body to attach to parse errors.
+    raw_body_limit: Option<usize>,
+
+    /// Called with the url and body of every response which failed to parse.
+    parse_error_sink: Option<Box<dyn FnMut(&str, &str) + Send>>,
+
+    /// Counters about the performed requests.
+    stats: ClientStats,
+
+    /// Areas fetched by `areas::fetch_chain`, along with the MBID of their
+    /// parent area.
+    area_parents: BoundedCache<Mbid, (Area, Option<Mbid>)>,
+
+    /// Host requests to `musicbrainz.org` are sent to instead, e.g. the one
+    /// of the test server.
+    host: Option<&'static str>,
 }
 
 /// A request to be performed on the client.
@@ -104,12 +352,31 @@ pub struct Request {
 
 impl Client {
     /// Create a new `Client` instance.
+    #[cfg(feature = "default-transport")]
     pub fn new(config: ClientConfig) -> Self {
-        Client {
-            config: config,
-            http_client: HttpClient::direct(),
-            last_request: past_instant(),
-        }
+        Client::with_transport(config, ReqwestTransport::new())
+    }
+
+    /// Create a new `Client` instance sending `user_agent` with every request
+    /// and using the defaults of `ClientConfig::builder` otherwise.
+    ///
+    /// Returns a usage error if the user agent is empty.
+    #[cfg(feature = "default-transport")]
+    pub fn with_user_agent(user_agent: &str) -> Result<Self, Error> {
+        let config = ClientConfig::builder().user_agent(user_agent).build()?;
+        Ok(Client::new(config))
+    }
+
+    /// Like `with_user_agent`, but all requests are sent to
+    /// `test.musicbrainz.org`.
+    ///
+    /// The data of the test server is reset regularly, so it can be used to
+    /// try out the crate without affecting the main server.
+    #[cfg(feature = "default-transport")]
+    pub fn musicbrainz_test_server(user_agent: &str) -> Result<Self, Error> {
+        let mut client = Client::with_user_agent(user_agent)?;
+        client.host = Some(TEST_SERVER_HOST);
+        Ok(client)
     }
 
     /// Create a new `Client` instance with the specified `HttpClient`.
@@ -117,23 +384,201 @@ impl Client {
     /// This is useful for testing purposes where you can inject a different
     /// `HttpClient`, i. e. one replaying requests to save API calls or one
     /// providing explicit stubbing.
+    #[cfg(feature = "default-transport")]
     pub fn with_http_client(config: ClientConfig, client: HttpClient) -> Self {
+        Client::with_transport(config, client)
+    }
+
+    /// Create a new `Client` instance performing its requests with the
+    /// specified `HttpTransport`.
+    ///
+    /// This allows using a different HTTP stack than the default one or
+    /// stubbing responses on the transport level.
+    pub fn with_transport<T>(config: ClientConfig, transport: T) -> Self
+    where
+        T: HttpTransport + Send + 'static,
+    {
         Client {
             config: config,
-            http_client: client,
-            last_request: past_instant(),
+            transport: Box::new(transport),
+            rate_limit: RateLimiter::new(),
+            force_record: false,
+            cancellation: CancellationToken::new(),
+            etag_cache: None,
+            raw_body_limit: None,
+            parse_error_sink: None,
+            stats: ClientStats::default(),
+            area_parents: BoundedCache::new(AREA_CACHE_CAPACITY),
+            host: None,
+        }
+    }
+
+    /// Create a new `Client` instance replaying responses recorded in `dir`.
+    ///
+    /// Requests for which no recording exists yet are performed against the
+    /// API and their responses are stored in `dir`, so subsequent runs (e.g.
+    /// the test suite of an application using this crate) don't have to
+    /// access the network anymore.
+    #[cfg(feature = "default-transport")]
+    pub fn replay_from<P: Into<PathBuf>>(config: ClientConfig, dir: P) -> Self {
+        Client::with_http_client(config, HttpClient::replay_dir(dir.into()))
+    }
+
+    /// Create a new `Client` instance recording all responses to `dir`.
+    ///
+    /// In contrast to `replay_from` every request is performed against the
+    /// API and existing recordings are overwritten, which is useful to
+    /// refresh outdated fixtures.
+    #[cfg(feature = "default-transport")]
+    pub fn record_to<P: Into<PathBuf>>(config: ClientConfig, dir: P) -> Self {
+        let mut client = Client::replay_from(config, dir);
+        client.force_record = true;
+        client
+    }
+
+    /// Returns the token which can be used to cancel requests of this client.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Replace the token used to cancel requests of this client.
+    ///
+    /// This allows cancelling the requests of multiple clients at once.
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    /// Cache the bodies of up to `capacity` responses along with their
+    /// `ETag`.
+    ///
+    /// Repeated requests of the same url are then performed as conditional
+    /// requests and if the resource didn't change, the server answers with
+    /// 304 (Not Modified) and the cached body is used instead. This reduces
+    /// server load and latency for applications fetching the same entities
+    /// repeatedly.
+    pub fn enable_etag_cache(&mut self, capacity: usize) {
+        self.etag_cache = Some(EtagCache::new(capacity));
+    }
+
+    /// Remove all responses from the `ETag` cache.
+    pub fn clear_etag_cache(&mut self) {
+        if let Some(ref mut cache) = self.etag_cache {
+            cache.clear();
+        }
+    }
+
+    /// Remove all areas from the cache used when resolving the areas
+    /// containing an area, see `areas::fetch_chain`.
+    ///
+    /// The cache holds at most 1000 areas, evicting the oldest ones first.
+    pub fn clear_area_cache(&mut self) {
+        self.area_parents.clear();
+    }
+
+    /// Attach the body of responses which failed to parse to the returned
+    /// error, truncated to at most `limit` bytes.
+    ///
+    /// This makes bug reports about entities which can't be parsed
+    /// actionable, see `Error::raw_body`.
+    pub fn retain_raw_bodies(&mut self, limit: usize) {
+        self.raw_body_limit = Some(limit);
+    }
+
+    /// Call `sink` with the url and the full body of every response which
+    /// failed to parse, e.g. to write them to a log.
+    pub fn set_parse_error_sink<F>(&mut self, sink: F)
+    where
+        F: FnMut(&str, &str) + Send + 'static,
+    {
+        self.parse_error_sink = Some(Box::new(sink));
+    }
+
+    /// Passes the body of a response which failed to parse to the sink and
+    /// attaches it to the error, as configured.
+    pub(crate) fn parse_failed(&mut self, url: &str, body: &str, err: Error) -> Error {
+        if !err.is_parse_error() {
+            return err;
+        }
+        let err = err.with_url(url);
+        if let Some(ref mut sink) = self.parse_error_sink {
+            sink(url, body);
+        }
+        match self.raw_body_limit {
+            Some(limit) => err.with_raw_body(body, limit),
+            None => err,
+        }
+    }
+
+    /// Counters about the requests performed by this client so far.
+    pub fn stats(&self) -> &ClientStats {
+        &self.stats
+    }
+
+    /// Reset all counters returned by `stats` to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = ClientStats::default();
+    }
+
+    /// Check whether the API is reachable, measuring the latency of a minimal
+    /// request.
+    ///
+    /// The request respects the rate limit, but isn't retried if the server
+    /// is unavailable. Failing to reach the server is reported by the
+    /// returned `Ping`, an error is only returned if the client was
+    /// cancelled.
+    pub fn ping(&mut self) -> Result<Ping, Error> {
+        ping::ping(self)
+    }
+
+    /// Time until the next request can be performed without waiting for the
+    /// rate limit.
+    ///
+    /// For clients of a `ClientPool` this takes the requests of all clients
+    /// into account.
+    pub fn rate_limit_budget(&self) -> Duration {
+        let next_slot = self.rate_limit.next_slot();
+        let now = Instant::now();
+        if next_slot > now {
+            next_slot - now
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+
+    fn check_cancelled(&self) -> Result<(), Error> {
+        if self.cancellation.is_cancelled() {
+            Err(Error::new("The request was cancelled.", ErrorKind::Cancelled))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleeps for the specified duration, returning early with an error if the
+    /// client is cancelled in the meantime.
+    fn sleep_cancellable(&self, duration: Duration) -> Result<(), Error> {
+        let end = Instant::now() + duration;
+        loop {
+            self.check_cancelled()?;
+            let now = Instant::now();
+            if now >= end {
+                return Ok(());
+            }
+            sleep(min(end - now, Duration::from_millis(CANCEL_POLL_INTERVAL)));
         }
     }
 
     /// Waits until we are allowed to make the next request to the MusicBrainz
     /// API.
-    fn wait_if_needed(&mut self) {
+    fn wait_if_needed(&mut self) -> Result<(), Error> {
+        let slot = self
+            .rate_limit
+            .reserve(Duration::from_millis(self.config.waits.requests));
         let now = Instant::now();
-        let elapsed = now.duration_since(self.last_request);
-        if as_millis(&elapsed) < self.config.waits.requests {
-            sleep(Duration::from_millis(self.config.waits.requests) - elapsed);
+        if slot > now {
+            self.stats.rate_limit_wait += slot - now;
+            self.sleep_cancellable(slot - now)?;
         }
-        self.last_request = now;
+        Ok(())
     }
 
     pub fn get_by_mbid<Res, Resp, Opt>(&mut self, mbid: &Mbid, options: Opt) -> Result<Res, Error>
@@ -144,75 +589,598 @@ impl Client {
         let request = Res::request(&options);
         let url = request.get_by_mbid_url(mbid);
         let response_body = self.get_body(url.parse()?)?;
-        let context = crate::util::musicbrainz_context();
-        let reader = Reader::from_str(response_body.as_str(), Some(&context))?;
-        check_response_error(&reader)?;
+        parse_response(response_body.as_str(), options, self.config.lenient)
+            .map_err(|e| self.parse_failed(&url, &response_body, e))
+    }
+
+    /// Like `get_by_mbid`, but also requests the relationships listed in
+    /// `rels`, e.g. `Include::UrlRels`, and returns them along with the
+    /// entity.
+    ///
+    /// Returns a usage error if `rels` contains an include which doesn't
+    /// request relationships.
+    pub fn get_by_mbid_with_rels<Res>(
+        &mut self,
+        mbid: &Mbid,
+        options: Res::Options,
+        rels: &[Include],
+    ) -> Result<Entity<Res>, Error>
+    where
+        Res: Resource,
+    {
+        if let Some(include) = rels.iter().find(|i| !i.is_relationship()) {
+            return Err(Error::new(
+                format!("The include '{}' doesn't request relationships.", include),
+                ErrorKind::UsageError,
+            ));
+        }
+
+        let mut request = Res::request(&options);
+        for rel in rels {
+            // The options might request some relationships already.
+            if !request.include.split('+').any(|i| i == rel.name()) {
+                if !request.include.is_empty() {
+                    request.include.push('+');
+                }
+                request.include.push_str(rel.name());
+            }
+        }
+        let url = request.get_by_mbid_url(mbid);
+        let response_body = self.get_body(url.parse()?)?;
 
-        let response = Resp::from_xml(&reader)?;
+        let lenient = self.config.lenient;
+        let parse = |body: &str| -> Result<Entity<Res>, Error> {
+            let data = parse_response(body, options, lenient)?;
+            let context = crate::util::musicbrainz_context();
+            let reader = Reader::from_str(body, Some(&context))?;
+            let rels: Vec<Relation> =
+                reader.read("//mb:metadata/*/mb:relation-list/mb:relation")?;
+            Ok(Entity { data, rels })
+        };
+        parse(&response_body).map_err(|e| self.parse_failed(&url, &response_body, e))
+    }
 
-        Ok(Res::from_response(response, options))
+    /// Fetch the entity a link to the MusicBrainz website points to, e.g.
+    /// `https://musicbrainz.org/release/<mbid>`.
+    ///
+    /// Returns a usage error if the link points to a different type of entity
+    /// than the one requested.
+    pub fn get_by_url<Res>(&mut self, url: &str, options: Res::Options) -> Result<Res, Error>
+    where
+        Res: Resource,
+    {
+        let (kind, mbid) = crate::entities::parse_mb_url(url)?;
+        if kind != Res::NAME {
+            return Err(Error::new(
+                format!("Link to a {} can't be fetched as {}.", kind, Res::NAME),
+                ErrorKind::UsageError,
+            ));
+        }
+        self.get_by_mbid(&mbid, options)
+    }
+
+    /// Fetch an entity whose type is only known at runtime.
+    ///
+    /// The entity is requested with its minimal options.
+    pub fn get_dynamic(&mut self, kind: EntityKind, mbid: &Mbid) -> Result<AnyEntity, Error> {
+        crate::entities::get_dynamic(self, kind, mbid)
+    }
+
+    /// Fetch the entity a link to the MusicBrainz website points to, whatever
+    /// its type is.
+    pub fn get_dynamic_by_url(&mut self, url: &str) -> Result<AnyEntity, Error> {
+        let (kind, mbid) = crate::entities::parse_mb_url(url)?;
+        self.get_dynamic(kind.parse()?, &mbid)
+    }
+
+    /// Find the release containing the track with the MBID `mbid`, along with
+    /// the position of the track on it.
+    ///
+    /// There is no lookup for tracks in the API, so this takes a search for
+    /// the track and a request for the release. Returns an error of kind
+    /// not found if the search doesn't know the track.
+    pub fn get_track(&mut self, mbid: &Mbid) -> Result<TrackLocation, Error> {
+        tracks::get_track(self, mbid)
+    }
+
+    /// Like `get_by_mbid`, but also returns metadata about the response, e.g.
+    /// how long the request took and how often it was retried.
+    pub fn get_by_mbid_with_meta<Res>(
+        &mut self,
+        mbid: &Mbid,
+        options: Res::Options,
+    ) -> Result<(Res, ResponseMeta), Error>
+    where
+        Res: Resource,
+    {
+        let url = Res::request(&options).get_by_mbid_url(mbid);
+        let (body, mut meta) = self
+            .get_body_with_meta(url.parse()?, Vec::new())?
+            .ok_or_else(|| Error::new("Unexpected 304 (Not Modified).", ErrorKind::Communication))?;
+        let entity = parse_response(body.as_str(), options, self.config.lenient)
+            .map_err(|e| self.parse_failed(&url, &body, e))?;
+        add_warnings::<Res>(&mut meta, &body);
+        Ok((entity, meta))
+    }
+
+    /// Fetch the specified resource only if it changed since the version
+    /// identified by `etag` was fetched.
+    ///
+    /// Returns `None` if the resource didn't change.
+    pub fn get_by_mbid_if_none_match<Res>(
+        &mut self,
+        mbid: &Mbid,
+        options: Res::Options,
+        etag: &str,
+    ) -> Result<Option<(Res, ResponseMeta)>, Error>
+    where
+        Res: Resource,
+    {
+        let url = Res::request(&options).get_by_mbid_url(mbid);
+        let headers = vec![("If-None-Match".to_string(), etag.to_string())];
+        match self.get_body_with_meta(url.parse()?, headers)? {
+            Some((body, mut meta)) => {
+                let entity = parse_response(body.as_str(), options, self.config.lenient)
+                    .map_err(|e| self.parse_failed(&url, &body, e))?;
+                add_warnings::<Res>(&mut meta, &body);
+                Ok(Some((entity, meta)))
+            }
+            None => Ok(None),
+        }
     }
 
     /// Fetch the specified resource from the server and parse it.
+    ///
+    /// Requests the default options of the resource, which for the entities
+    /// that had no options before is the data which was always requested.
+    #[deprecated(note = "use `get_by_mbid`, passing the options to request")]
     pub fn get_by_mbid_old<Res>(&mut self, mbid: &Mbid) -> Result<Res, Error>
     where
-        Res: ResourceOld + FromXml,
+        Res: Resource,
+        Res::Options: Default,
     {
-        let url = Res::get_url(mbid);
-        let response_body = self.get_body(url.parse()?)?;
+        self.get_by_mbid(mbid, Res::Options::default())
+    }
 
-        // Parse the response.
-        let context = crate::util::musicbrainz_context();
-        let reader = Reader::from_str(&response_body[..], Some(&context))?;
-        check_response_error(&reader)?;
-        Ok(Res::from_xml(&reader)?)
+    /// Perform a `GET` request of an arbitrary url and return the body of the
+    /// response without parsing it.
+    ///
+    /// The request is subject to the same rate limiting, retries and caching
+    /// as all other requests, so this can be used for endpoints the crate
+    /// doesn't support (yet), e.g. with `fmt=json`.
+    pub fn get_raw(&mut self, url: &str) -> Result<String, Error> {
+        let url = Url::parse(url).map_err(|e| {
+            Error::new(format!("Invalid url '{}': {}", url, e), ErrorKind::UsageError)
+        })?;
+        self.get_body(url)
     }
 
     pub(crate) fn get_body(&mut self, url: Url) -> Result<String, Error> {
-        self.wait_if_needed();
+        let cached_etag = self
+            .etag_cache
+            .as_ref()
+            .and_then(|c| c.get(url.as_str()))
+            .map(|e| e.etag.clone());
+        let headers = match cached_etag {
+            Some(etag) => vec![("If-None-Match".to_string(), etag)],
+            None => Vec::new(),
+        };
+
+        let response = self.send(url.clone(), headers, None)?;
+        if response.status == 304 {
+            return self
+                .etag_cache
+                .as_ref()
+                .and_then(|c| c.get(url.as_str()))
+                .map(|e| e.body.clone())
+                .ok_or_else(|| {
+                    Error::new("Unexpected 304 (Not Modified).", ErrorKind::Communication)
+                });
+        }
+        if response.status >= 400 {
+            return Err(status_error(self.server_url(url).as_str(), &response));
+        }
+
+        let etag = response.header("ETag").map(String::from);
+        let body = response.into_body_utf8()?;
+        if let (Some(cache), Some(etag)) = (self.etag_cache.as_mut(), etag) {
+            cache.insert(
+                url.into_string(),
+                CacheEntry {
+                    etag,
+                    body: body.clone(),
+                },
+            );
+        }
+        Ok(body)
+    }
+
+    /// Perform a `GET` request returning the body along with metadata, or
+    /// `None` if the server answered with 304 (Not Modified).
+    pub(crate) fn get_body_with_meta(
+        &mut self,
+        url: Url,
+        headers: Vec<(String, String)>,
+    ) -> Result<Option<(String, ResponseMeta)>, Error> {
+        let start = Instant::now();
+        let retries = self.stats.retries;
+        let response = self.send(url.clone(), headers, None)?;
+        if response.status == 304 {
+            return Ok(None);
+        }
+        let url = self.server_url(url);
+        if response.status >= 400 {
+            return Err(status_error(url.as_str(), &response));
+        }
+        let meta = ResponseMeta::new(
+            &response,
+            url.into_string(),
+            start.elapsed(),
+            self.stats.retries - retries,
+        );
+        Ok(Some((response.into_body_utf8()?, meta)))
+    }
+
+    /// Replace the host of `url` if the client uses a different server than
+    /// `musicbrainz.org`.
+    fn server_url(&self, mut url: Url) -> Url {
+        if let Some(host) = self.host {
+            if url.host_str() == Some(MUSICBRAINZ_HOST) {
+                // Only valid hosts are ever stored.
+                url.set_host(Some(host)).unwrap();
+            }
+        }
+        url
+    }
+
+    /// Perform a request, a `POST` request if `body` is provided and a `GET`
+    /// request otherwise, respecting the rate limit and retrying it if the
+    /// server is unavailable.
+    pub(crate) fn send(
+        &mut self,
+        url: Url,
+        headers: Vec<(String, String)>,
+        body: Option<&[u8]>,
+    ) -> Result<HttpResponse, Error> {
+        let attempts = self.config.max_retries;
+        self.send_attempts(url, headers, body, attempts)
+    }
+
+    /// Perform a request like `send`, but with at most `max_attempts`
+    /// attempts instead of the configured number.
+    pub(crate) fn send_attempts(
+        &mut self,
+        url: Url,
+        headers: Vec<(String, String)>,
+        body: Option<&[u8]>,
+        max_attempts: u8,
+    ) -> Result<HttpResponse, Error> {
+        let url = self.server_url(url);
+        let deadline = self
+            .config
+            .waits
+            .total_timeout
+            .map(|t| Instant::now() + Duration::from_millis(t));
+        let check_deadline = || match deadline {
+            Some(d) if Instant::now() >= d => Err(Error::new(
+                format!("Request to {} timed out.", url),
+                ErrorKind::Timeout,
+            )),
+            _ => Ok(()),
+        };
+
+        self.wait_if_needed()?;
 
         let mut attempts = 0;
         let mut backoff = self.config.waits.backoff_init;
+        let mut unavailable = None;
 
-        while attempts < self.config.max_retries {
-            let response = self
-                .http_client
-                .get(url.clone())
-                .header(UserAgent::new(self.config.user_agent.clone()))
-                .send()?;
-            if response.status == StatusCode::ServiceUnavailable {
-                sleep(Duration::from_millis(backoff));
+        while attempts < max_attempts {
+            self.check_cancelled()?;
+            check_deadline()?;
+            if self.force_record {
+                self.transport.force_record_next();
+            }
+            let mut request = HttpRequest {
+                url: url.clone(),
+                headers: vec![("User-Agent".to_string(), self.config.user_agent.clone())],
+                timeout: self.config.waits.request_timeout.map(Duration::from_millis),
+                max_body_size: self.config.max_response_size,
+            };
+            request.headers.extend(headers.iter().cloned());
+            self.stats.requests += 1;
+            let response = match body {
+                Some(body) => self.transport.post(&request, body)?,
+                None => self.transport.get(&request)?,
+            };
+            self.stats.bytes_downloaded += response.body.len() as u64;
+            check_response_size(url.as_str(), &response, self.config.max_response_size)?;
+            if response.status == 503 {
                 attempts += 1;
+                if attempts == max_attempts {
+                    unavailable = Some(response);
+                    break;
+                }
+                let mut wait = Duration::from_millis(backoff);
+                if let Some(d) = deadline {
+                    // Don't wait past the deadline, the next attempt fails
+                    // with a timeout then.
+                    let now = Instant::now();
+                    let remaining = if d > now {
+                        d - now
+                    } else {
+                        Duration::from_secs(0)
+                    };
+                    wait = min(wait, remaining);
+                }
+                self.stats.retries += 1;
+                self.stats.backoff_wait += wait;
+                self.sleep_cancellable(wait)?;
                 backoff *= 2;
                 // If we are in testing we want to avoid always failing.
-                self.http_client.force_record_next();
+                self.transport.force_record_next();
             } else {
-                let response_body = response.body_to_utf8()?;
-                return Ok(response_body);
+                return Ok(response);
             }
         }
-        Err(Error::new(
+        let err = Error::new(
             "MusicBrainz returned 503 (ServiceUnavailable) too many times.",
-            ErrorKind::Communication,
-        ))
+            ErrorKind::RateLimited,
+        );
+        Err(match unavailable {
+            Some(response) => err.with_response(url.as_str(), &response),
+            None => err.with_url(url.as_str()),
+        })
+    }
+
+    /// List the events taking place at a place, e.g. all concerts at a venue.
+    pub fn browse_events_by_place<'cl>(&'cl mut self, place: &Mbid) -> Browse<'cl, EventRef> {
+        Browse::new(self, "place", place)
+    }
+
+    /// List the events an artist performed at.
+    pub fn browse_events_by_artist<'cl>(&'cl mut self, artist: &Mbid) -> Browse<'cl, EventRef> {
+        Browse::new(self, "artist", artist)
+    }
+
+    /// List the release groups of an artist.
+    pub fn browse_release_groups_by_artist<'cl>(
+        &'cl mut self,
+        artist: &Mbid,
+    ) -> Browse<'cl, ReleaseGroupRef> {
+        Browse::new(self, "artist", artist)
+    }
+
+    /// List the releases of an artist.
+    pub fn browse_releases_by_artist<'cl>(
+        &'cl mut self,
+        artist: &Mbid,
+    ) -> Browse<'cl, browse_entities::Release> {
+        Browse::new(self, "artist", artist)
+    }
+
+    /// List the releases issued by a label.
+    pub fn browse_releases_by_label<'cl>(
+        &'cl mut self,
+        label: &Mbid,
+    ) -> Browse<'cl, browse_entities::Release> {
+        Browse::new(self, "label", label)
     }
-    /*
+
+    /// List the recordings of a work, e.g. all performances of a composition
+    /// or all covers of a song.
+    pub fn browse_recordings_by_work<'cl>(
+        &'cl mut self,
+        work: &Mbid,
+    ) -> Browse<'cl, browse_entities::Recording> {
+        Browse::new(self, "work", work)
+    }
+
     /// Returns a search builder to search for an area.
     pub fn search_area<'cl>(&'cl mut self) -> AreaSearchBuilder<'cl> {
         AreaSearchBuilder::new(self)
     }
 
-    /// Returns a search biulder to search for an artist.
+    /// Returns a search builder to search for an artist.
     pub fn search_artist<'cl>(&'cl mut self) -> ArtistSearchBuilder<'cl> {
         ArtistSearchBuilder::new(self)
-    }*/
+    }
+
+    /// Returns a search builder to search for an event.
+    pub fn search_event<'cl>(&'cl mut self) -> EventSearchBuilder<'cl> {
+        EventSearchBuilder::new(self)
+    }
+
+    /// Returns a search builder to search for a label.
+    pub fn search_label<'cl>(&'cl mut self) -> LabelSearchBuilder<'cl> {
+        LabelSearchBuilder::new(self)
+    }
+
+    /// Returns a search builder to search for a place.
+    pub fn search_place<'cl>(&'cl mut self) -> PlaceSearchBuilder<'cl> {
+        PlaceSearchBuilder::new(self)
+    }
+
+    /// Returns a search builder to search for a recording.
+    pub fn search_recording<'cl>(&'cl mut self) -> RecordingSearchBuilder<'cl> {
+        RecordingSearchBuilder::new(self)
+    }
+
+    /// Returns a search builder to search for a release.
+    pub fn search_release<'cl>(&'cl mut self) -> ReleaseSearchBuilder<'cl> {
+        ReleaseSearchBuilder::new(self)
+    }
 
     /// Returns a search builder to search for a release group.
     pub fn search_release_group<'cl>(&'cl mut self) -> ReleaseGroupSearchBuilder<'cl> {
         ReleaseGroupSearchBuilder::new(self)
     }
+
+    /// Returns a search builder to search for a series.
+    pub fn search_series<'cl>(&'cl mut self) -> SeriesSearchBuilder<'cl> {
+        SeriesSearchBuilder::new(self)
+    }
+
+    /// Find the releases `label` issued with the catalog number `catno`.
+    ///
+    /// The search index matches catalog numbers loosely, so the results are
+    /// filtered to releases listing the label with the same catalog number,
+    /// ignoring case, spaces and punctuation, i.e. `CDP 7 46208 2` also finds
+    /// a release listed with `CDP-7-46208-2`.
+    ///
+    /// All pages of search results are fetched, until the server returns a
+    /// page with less than `Limit::MAX` entries.
+    pub fn find_release_by_catalog_number(
+        &mut self,
+        label: &Mbid,
+        catno: &str,
+    ) -> SearchResult<search_entities::Release> {
+        let normalized = normalize_catalog_number(catno);
+        let mut entries = Vec::new();
+        let mut offset = Offset::new(0);
+
+        loop {
+            let query = ReleaseQuery::new()
+                .add(LabelId(label.clone()))
+                .add(CatalogNumber(catno.to_string()))
+                .limit(Limit::MAX)
+                .offset(offset);
+            let page = self.run(&query)?;
+            let len = page.len();
+
+            entries.extend(page.into_iter().filter(|entry| {
+                entry.entity.labels.iter().any(|info| {
+                    info.label.as_ref() == Some(label)
+                        && info
+                            .catalog_number
+                            .as_ref()
+                            .map_or(false, |c| normalize_catalog_number(c) == normalized)
+                })
+            }));
+
+            if len < usize::from(Limit::MAX.get()) {
+                return Ok(entries);
+            }
+            offset = offset.advance(len as u32);
+        }
+    }
+
+    /// Perform a search which was built without a client, e.g. a
+    /// `ReleaseGroupQuery`.
+    pub fn run<Q: SearchQuery>(&mut self, query: &Q) -> SearchResult<Q::Entity> {
+        self.run_with_meta(query).map(|(results, _)| results)
+    }
+
+    /// Perform a search like `run`, also returning metadata about the
+    /// response.
+    pub fn run_with_meta<Q: SearchQuery>(
+        &mut self,
+        query: &Q,
+    ) -> Result<(Vec<SearchEntry<Q::Entity>>, ResponseMeta), Error> {
+        let url = query.search_url()?;
+
+        // Perform the request.
+        let (response_body, meta) = self
+            .get_body_with_meta(url.clone(), Vec::new())?
+            .ok_or_else(|| {
+                Error::new("Unexpected 304 (Not Modified).", ErrorKind::Communication)
+            })?;
+        let entries = query
+            .parse_results(response_body.as_str())
+            .map_err(|e| self.parse_failed(url.as_str(), &response_body, e))?;
+        Ok((entries, meta))
+    }
+}
+
+/// Lowercase `catno` and drop everything but letters and digits, so catalog
+/// numbers can be compared regardless of their formatting.
+fn normalize_catalog_number(catno: &str) -> String {
+    catno
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Returns an error if the body of `response` is larger than `max_size`,
+/// either according to its `Content-Length` or its actual length.
+fn check_response_size(
+    url: &str,
+    response: &HttpResponse,
+    max_size: Option<u64>,
+) -> Result<(), Error> {
+    let max_size = match max_size {
+        Some(max_size) => max_size,
+        None => return Ok(()),
+    };
+    let declared = response
+        .header("Content-Length")
+        .and_then(|len| len.trim().parse::<u64>().ok());
+    let size = max(declared.unwrap_or(0), response.body.len() as u64);
+    if size > max_size {
+        let err = Error::new(
+            format!(
+                "The response has {} bytes, more than the maximum of {} bytes.",
+                size, max_size
+            ),
+            ErrorKind::ResponseTooLarge,
+        );
+        Err(err.with_response(url, response))
+    } else {
+        Ok(())
+    }
+}
+
+/// Parses the body of a response to a lookup of `Res`.
+///
+/// Unless `lenient` is set, skipped parts of the response fail the parse.
+pub(crate) fn parse_response<Res>(
+    body: &str,
+    options: Res::Options,
+    lenient: bool,
+) -> Result<Res, Error>
+where
+    Res: Resource,
+{
+    let entity = parse_entity::<Res>(body, options)?;
+    match entity.parse_warnings().first() {
+        Some(warning) if !lenient => Err(Error::parse_error(warning.to_string())),
+        _ => Ok(entity),
+    }
+}
+
+fn parse_entity<Res>(body: &str, options: Res::Options) -> Result<Res, Error>
+where
+    Res: Resource,
+{
+    #[cfg(feature = "streaming")]
+    {
+        if let Some(response) = Res::parse_streaming(body, &options) {
+            return Ok(Res::from_response(response?, options));
+        }
+    }
+
+    let context = crate::util::musicbrainz_context();
+    let reader = Reader::from_str(body, Some(&context))?;
+    check_response_error(&reader)?;
+
+    let response = Res::Response::from_xml(&reader)?;
+
+    Ok(Res::from_response(response, options))
+}
+
+/// Records the unparsed elements of a successfully parsed response of `Res`.
+#[cfg(feature = "diagnostics")]
+fn add_warnings<Res: Resource>(meta: &mut ResponseMeta, body: &str) {
+    if let Some(parsed) = Res::PARSED_ELEMENTS {
+        meta.warnings
+            .extend(crate::diagnostics::unparsed_elements(body, Res::NAME, parsed));
+    }
 }
 
+#[cfg(not(feature = "diagnostics"))]
+fn add_warnings<Res: Resource>(_: &mut ResponseMeta, _: &str) {}
+
 impl Request {
     /// Returns the url where one can get a resource in the valid format for
     /// parsing from.
@@ -227,18 +1195,505 @@ impl Request {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::Area;
+    use crate::util::test_utils::{response, stub_transport, FnTransport};
+
+    const AREA_XML: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area type="Country" type-id="06dd0ae4-8c74-30bb-b43d-95dcedf961de" id="2db42837-c832-3c27-b4a3-08198f75693c"><name>Japan</name><sort-name>Japan</sort-name><iso-3166-1-code-list><iso-3166-1-code>JP</iso-3166-1-code></iso-3166-1-code-list></area></metadata>"#;
+
+    /// Implements conditional requests for a single resource.
+    fn etag_response(request: &HttpRequest, etag: &str) -> Result<HttpResponse, Error> {
+        let if_none_match = request
+            .headers
+            .iter()
+            .find(|&&(ref name, _)| name == "If-None-Match")
+            .map(|&(_, ref value)| value.as_str());
+        let status = if if_none_match == Some(etag) { 304 } else { 200 };
+        let mut answer = response(status, if status == 200 { AREA_XML } else { "" });
+        answer.headers = vec![
+            ("etag".to_string(), etag.to_string()),
+            ("Last-Modified".to_string(), "Sat, 06 May 2017 09:45:01 GMT".to_string()),
+        ];
+        Ok(answer)
+    }
 
     fn get_client(testname: &str) -> Client {
-        Client::with_http_client(
-            ClientConfig {
-                user_agent: "MusicBrainz-Rust/Testing".to_string(),
-                max_retries: 5,
-                waits: ClientWaits::default(),
-            },
-            HttpClient::replay_file(format!("replay/test_client/search/{}.json", testname)),
+        Client::with_transport(
+            crate::util::test_utils::config().build().unwrap(),
+            reqwest_mock::GenericClient::replay_file(format!("replay/test_client/search/{}.json", testname)),
         )
     }
 
+    fn stub_client(body: &'static str) -> Client {
+        Client::with_transport(
+            crate::util::test_utils::config().build().unwrap(),
+            stub_transport(body),
+        )
+    }
+
+    #[test]
+    fn config_builder() {
+        let config = ClientConfig::builder()
+            .user_agent(UserAgent::new("Tagger", "1.0", "me@tagger.rs").unwrap())
+            .max_retries(3)
+            .total_timeout(10_000)
+            .build()
+            .unwrap();
+        assert_eq!(config.user_agent, "Tagger/1.0 ( me@tagger.rs )".to_string());
+        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.waits.total_timeout, Some(10_000));
+        assert_eq!(config.waits.backoff_init, 400);
+
+        assert!(ClientConfig::builder().build().unwrap_err().is_usage_error());
+        assert!(ClientConfig::builder()
+            .user_agent("MusicBrainz-Rust/Testing")
+            .max_retries(0)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn max_response_size() {
+        let mbid: Mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let mut client = stub_client(AREA_XML);
+        client.config.max_response_size = Some(AREA_XML.len() as u64);
+        let _: Area = client.get_by_mbid(&mbid, ()).unwrap();
+
+        client.config.max_response_size = Some(100);
+        let err = client.get_by_mbid::<Area>(&mbid, ()).unwrap_err();
+        assert!(err.is_response_too_large());
+        assert_eq!(err.status(), Some(200));
+        assert!(ClientConfig::builder()
+            .user_agent("MusicBrainz-Rust/Testing")
+            .max_response_size(0)
+            .build()
+            .unwrap_err()
+            .is_usage_error());
+    }
+
+    #[test]
+    #[cfg(feature = "default-transport")]
+    fn test_server() {
+        use std::sync::Mutex;
+
+        let urls = Arc::new(Mutex::new(Vec::new()));
+        let mut client = Client::musicbrainz_test_server("MusicBrainz-Rust/Testing").unwrap();
+        let requested = urls.clone();
+        client.transport = Box::new(FnTransport(move |request: &HttpRequest| {
+            requested.lock().unwrap().push(request.url.to_string());
+            Ok(response(200, AREA_XML))
+        }));
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let _: Area = client.get_by_mbid(&mbid, ()).unwrap();
+
+        assert_eq!(
+            urls.lock().unwrap().as_slice(),
+            &["https://test.musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c?inc="
+                .to_string()]
+        );
+        assert!(Client::with_user_agent(" ").unwrap_err().is_usage_error());
+    }
+
+    #[test]
+    fn relationships() {
+        use crate::entities::{DatedRelation, RelationDirection, RelationTarget};
+
+        let mut client = stub_client(
+            r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area type="City" id="6d3c5c4f-7b2c-4a9e-a7f4-8f5f4b1d0c3e"><name>Tokyo</name><sort-name>Tokyo</sort-name><relation-list target-type="area"><relation type="part of" type-id="de7cc874-8b1b-3a05-8272-f3834c968fb7"><target>2db42837-c832-3c27-b4a3-08198f75693c</target><direction>backward</direction><area id="2db42837-c832-3c27-b4a3-08198f75693c"><name>Japan</name><sort-name>Japan</sort-name></area></relation></relation-list><relation-list target-type="url"><relation type="wikidata" type-id="85c5256f-aef1-484f-979a-42007218a1c7"><target id="8c4a0a3e-9f46-4b1c-b0b5-3c7c1e7c2bd8">https://www.wikidata.org/wiki/Q1490</target><begin>1943</begin><ended>true</ended></relation></relation-list><relation-list target-type="series"><relation type="part of"><target>d0e1c6a2-3f52-4c4e-9d4a-3a1f0b6c9f11</target><series id="d0e1c6a2-3f52-4c4e-9d4a-3a1f0b6c9f11"><name>Capitals</name></series></relation></relation-list></area></metadata>"#,
+        );
+        let mbid = "6d3c5c4f-7b2c-4a9e-a7f4-8f5f4b1d0c3e".parse().unwrap();
+
+        let entity: Entity<Area> = client
+            .get_by_mbid_with_rels(&mbid, (), &[Include::AreaRels, Include::UrlRels])
+            .unwrap();
+        assert_eq!(entity.data.name(), "Tokyo");
+        assert_eq!(entity.rels.len(), 3);
+
+        let parent = &entity.rels[0];
+        assert_eq!(parent.relation_type, "part of");
+        assert_eq!(parent.direction, RelationDirection::Backward);
+        match parent.target {
+            RelationTarget::Area(ref area) => assert_eq!(area.name, "Japan"),
+            ref target => panic!("unexpected target: {:?}", target),
+        }
+
+        let wikidata = &entity.rels[1];
+        assert_eq!(
+            wikidata.target,
+            RelationTarget::Url("https://www.wikidata.org/wiki/Q1490".to_string())
+        );
+        assert_eq!(wikidata.target.mbid(), None);
+        assert!(!wikidata.is_current());
+
+        assert_eq!(entity.rels_of_type("part of").count(), 2);
+        assert_eq!(
+            entity.rels[2].target.mbid(),
+            Some(&"d0e1c6a2-3f52-4c4e-9d4a-3a1f0b6c9f11".parse().unwrap())
+        );
+
+        let err = client
+            .get_by_mbid_with_rels::<Area>(&mbid, (), &[Include::Aliases])
+            .unwrap_err();
+        assert!(err.is_usage_error());
+    }
+
+    #[test]
+    fn custom_transport() {
+        let mut client = stub_client(AREA_XML);
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let area: Area = client.get_by_mbid(&mbid, ()).unwrap();
+
+        assert_eq!(area.name(), &"Japan".to_string());
+    }
+
+    #[test]
+    fn raw_body_of_parse_error() {
+        use std::sync::Mutex;
+
+        const BROKEN: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area id="broken"><name>Japan</name></area></metadata>"#;
+
+        let logged = Arc::new(Mutex::new(Vec::new()));
+        let sink = logged.clone();
+        let mut client = stub_client(BROKEN);
+        client.retain_raw_bodies(20);
+        client.set_parse_error_sink(move |url, body| {
+            sink.lock().unwrap().push((url.to_string(), body.to_string()));
+        });
+
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let err = client.get_by_mbid::<Area, _, _>(&mbid, ()).unwrap_err();
+        assert!(err.is_parse_error());
+        assert_eq!(err.raw_body(), Some(&BROKEN[..20]));
+
+        let logged = logged.lock().unwrap();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(
+            logged[0].0,
+            "https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c?inc="
+        );
+        assert_eq!(logged[0].1, BROKEN);
+    }
+
+    #[test]
+    fn get_raw() {
+        let mut client = stub_client(AREA_XML);
+        let body = client
+            .get_raw("https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c")
+            .unwrap();
+        assert_eq!(body, AREA_XML);
+
+        assert!(client.get_raw("not a url").unwrap_err().is_usage_error());
+    }
+
+    #[test]
+    fn get_by_url() {
+        let mut client = stub_client(AREA_XML);
+        let area: Area = client
+            .get_by_url("https://musicbrainz.org/area/2db42837-c832-3c27-b4a3-08198f75693c", ())
+            .unwrap();
+        assert_eq!(area.name(), &"Japan".to_string());
+
+        let res: Result<Area, _> = client.get_by_url(
+            "https://musicbrainz.org/artist/2db42837-c832-3c27-b4a3-08198f75693c",
+            (),
+        );
+        assert!(res.unwrap_err().is_usage_error());
+    }
+
+    #[test]
+    fn get_dynamic() {
+        let mut client = stub_client(AREA_XML);
+        let entity = client
+            .get_dynamic_by_url("https://musicbrainz.org/area/2db42837-c832-3c27-b4a3-08198f75693c")
+            .unwrap();
+
+        assert_eq!(entity.kind(), EntityKind::Area);
+        assert_eq!(
+            entity.mbid(),
+            &"2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn response_meta() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            FnTransport(|request: &HttpRequest| etag_response(request, "\"abc\"")),
+        );
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+
+        let (area, meta): (Area, _) = client.get_by_mbid_with_meta(&mbid, ()).unwrap();
+        assert_eq!(area.name(), &"Japan".to_string());
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.etag, Some("\"abc\"".to_string()));
+        assert_eq!(
+            meta.last_modified,
+            Some("Sat, 06 May 2017 09:45:01 GMT".to_string())
+        );
+
+        let unchanged: Option<(Area, _)> = client
+            .get_by_mbid_if_none_match(&mbid, (), "\"abc\"")
+            .unwrap();
+        assert!(unchanged.is_none());
+        let changed: Option<(Area, _)> = client
+            .get_by_mbid_if_none_match(&mbid, (), "\"old\"")
+            .unwrap();
+        assert!(changed.is_some());
+    }
+
+    #[test]
+    fn etag_cache() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            FnTransport(|request: &HttpRequest| etag_response(request, "\"abc\"")),
+        );
+        client.enable_etag_cache(10);
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+
+        let first: Area = client.get_by_mbid(&mbid, ()).unwrap();
+        // The transport answers with an empty 304 now.
+        let second: Area = client.get_by_mbid(&mbid, ()).unwrap();
+        assert_eq!(first.name(), second.name());
+
+        client.clear_etag_cache();
+        let res: Result<Area, _> = client.get_by_mbid_with_meta(&mbid, ()).map(|(a, _)| a);
+        assert!(res.is_ok());
+    }
+
+    /// Answers the first request with 503 (Service Unavailable).
+    fn unavailable_once() -> impl HttpTransport + Send {
+        let mut unavailable = true;
+        FnTransport(move |_: &HttpRequest| {
+            let status = if unavailable { 503 } else { 200 };
+            unavailable = false;
+            Ok(response(status, AREA_XML))
+        })
+    }
+
+    #[test]
+    fn stats() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .backoff_init(10)
+                .request_interval(20)
+                .build()
+                .unwrap(),
+            unavailable_once(),
+        );
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let (_, meta): (Area, _) = client.get_by_mbid_with_meta(&mbid, ()).unwrap();
+        assert_eq!(meta.retries, 1);
+        assert!(meta.duration >= Duration::from_millis(10));
+        let (_, meta): (Area, _) = client.get_by_mbid_with_meta(&mbid, ()).unwrap();
+        assert_eq!(meta.retries, 0);
+        assert_eq!(meta.size, AREA_XML.len() as u64);
+        assert_eq!(
+            meta.url,
+            "https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c?inc="
+                .to_string()
+        );
+
+        let stats = client.stats().clone();
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.retries, 1);
+        assert_eq!(stats.bytes_downloaded, 3 * AREA_XML.len() as u64);
+        assert_eq!(stats.backoff_wait, Duration::from_millis(10));
+        assert!(stats.rate_limit_wait > Duration::from_millis(0));
+        let estimate = stats.estimate_remaining(10, Duration::from_millis(20));
+        assert!(estimate >= Duration::from_millis(200));
+
+        client.reset_stats();
+        assert_eq!(client.stats(), &ClientStats::default());
+    }
+
+    #[test]
+    fn backoff_within_total_timeout() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .backoff_init(60_000)
+                .total_timeout(50)
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            unavailable_once(),
+        );
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let start = Instant::now();
+        let res: Result<Area, Error> = client.get_by_mbid(&mbid, ());
+
+        assert!(res.unwrap_err().is_timeout());
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(client.stats().backoff_wait <= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn cancelled_request() {
+        let mut client = stub_client(AREA_XML);
+        client.cancellation_token().cancel();
+        let mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+        let res: Result<Area, Error> = client.get_by_mbid(&mbid, ());
+
+        assert!(res.err().unwrap().is_cancelled());
+    }
+
+    #[test]
+    fn pool_shares_rate_limit() {
+        fn assert_send<T: Send>() {}
+        fn assert_sync<T: Sync>() {}
+        assert_send::<Client>();
+        assert_send::<ClientPool>();
+        assert_sync::<ClientPool>();
+
+        let pool = ClientPool::new(
+            crate::util::test_utils::config()
+                .request_interval(100)
+                .build()
+                .unwrap(),
+        );
+        let mbid: Mbid = "2db42837-c832-3c27-b4a3-08198f75693c".parse().unwrap();
+
+        let start = Instant::now();
+        let handles: Vec<_> = (0..3)
+            .map(|_| {
+                let mut client = pool.client_with_transport(stub_transport(AREA_XML));
+                let mbid = mbid.clone();
+                std::thread::spawn(move || {
+                    let _: Area = client.get_by_mbid(&mbid, ()).unwrap();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // The first request is performed immediately, the others have to wait.
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[test]
+    fn search_min_score() {
+        use crate::search::fields::release_group::ReleaseGroupName;
+        use crate::search::{ReleaseGroupQuery, SearchBuilder};
+
+        const XML: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-group-list count="2" offset="0"><release-group id="739de9cd-7e81-4bb0-9fdb-0feb7ea709c7" type="Single" ext:score="100"><title>霊魂消滅</title><primary-type>Single</primary-type><release-list count="0"></release-list></release-group><release-group id="23c74936-ad4f-45bb-8b6b-527d4aeaaad6" type="Album" ext:score="62"><title>A.I Complex</title><primary-type>Album</primary-type><release-list count="0"></release-list></release-group></release-group-list></metadata>"#;
+        let mut client = stub_client(XML);
+
+        let results = client
+            .search_release_group()
+            .add(ReleaseGroupName("霊魂消滅".to_owned()))
+            .min_score(80)
+            .search()
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].score, 100);
+
+        let best = client
+            .search_release_group()
+            .add(ReleaseGroupName("霊魂消滅".to_owned()))
+            .best_match(90)
+            .unwrap();
+        assert_eq!(best.unwrap().entity.title, "霊魂消滅".to_string());
+
+        // The same search as a standalone query, which can be performed again.
+        let query = ReleaseGroupQuery::new()
+            .add(ReleaseGroupName("霊魂消滅".to_owned()))
+            .min_score(80);
+        let retry = query.clone();
+        assert_eq!(client.run(&query).unwrap().len(), 1);
+        assert_eq!(client.run(&retry).unwrap()[0].score, 100);
+        assert_eq!(
+            client
+                .search_release_group()
+                .add(ReleaseGroupName("霊魂消滅".to_owned()))
+                .min_score(80)
+                .query(),
+            &query
+        );
+    }
+
+    #[test]
+    fn find_release_by_catalog_number() {
+        const XML: &'static str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-list count="3" offset="0"><release id="0b6b4ba0-d36f-47bd-b4ea-6a5b91842d29" ext:score="100"><title>OK Computer</title><label-info-list><label-info><catalog-number>NODATA 02</catalog-number><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name></label></label-info></label-info-list></release><release id="ed118c5f-d940-4b52-a37b-b1a205374abe" ext:score="90"><title>Creep</title><label-info-list><label-info><catalog-number>NODATA 01</catalog-number><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name></label></label-info></label-info-list></release><release id="9642c552-a5b3-4b7e-9168-aeb2a1a06f27" ext:score="80"><title>OK Computer</title><label-info-list><label-info><catalog-number>NODATA 02</catalog-number><label id="c029628b-6633-439e-bcee-ed02e8a338f7"><name>EMI</name></label></label-info></label-info-list></release></release-list></metadata>"#;
+        let mut client = stub_client(XML);
+        let parlophone: Mbid = "df7d1c7f-ef95-425f-8eef-445b3d7bcbd9".parse().unwrap();
+
+        let results = client
+            .find_release_by_catalog_number(&parlophone, "nodata-02")
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entity.title, "OK Computer".to_string());
+        assert_eq!(
+            results[0].entity.labels[0].catalog_number,
+            Some("NODATA 02".to_string())
+        );
+
+        assert!(client
+            .find_release_by_catalog_number(&parlophone, " ")
+            .unwrap_err()
+            .is_usage_error());
+    }
+
+    #[test]
+    fn find_release_by_catalog_number_pages() {
+        use std::sync::Mutex;
+
+        fn release(mbid: u32, label: &str) -> String {
+            format!(
+                r#"<release id="00000000-0000-0000-0000-{:012}" ext:score="100"><title>OK Computer</title><label-info-list><label-info><catalog-number>NODATA 02</catalog-number><label id="{}"><name>Label</name></label></label-info></label-info-list></release>"#,
+                mbid, label
+            )
+        }
+        const PARLOPHONE: &'static str = "df7d1c7f-ef95-425f-8eef-445b3d7bcbd9";
+        const EMI: &'static str = "c029628b-6633-439e-bcee-ed02e8a338f7";
+
+        let offsets = Arc::new(Mutex::new(Vec::new()));
+        let requested = offsets.clone();
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config().build().unwrap(),
+            FnTransport(move |request: &HttpRequest| {
+                let offset: u32 = request
+                    .url
+                    .query_pairs()
+                    .find(|&(ref key, _)| key == "offset")
+                    .map_or(0, |(_, value)| value.parse().unwrap());
+                requested.lock().unwrap().push(offset);
+
+                // A full first page with one match at its end, and a second
+                // page with one more match.
+                let releases: String = if offset == 0 {
+                    (0..100)
+                        .map(|i| release(i, if i == 99 { PARLOPHONE } else { EMI }))
+                        .collect()
+                } else {
+                    release(100, PARLOPHONE)
+                };
+                let body = format!(
+                    r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-list count="101" offset="{}">{}</release-list></metadata>"#,
+                    offset, releases
+                );
+                Ok(response(200, body))
+            }),
+        );
+
+        let results = client
+            .find_release_by_catalog_number(&PARLOPHONE.parse().unwrap(), "NODATA 02")
+            .unwrap();
+        let mbids: Vec<String> = results.iter().map(|r| r.entity.mbid.to_string()).collect();
+        assert_eq!(
+            mbids,
+            vec![
+                "00000000-0000-0000-0000-000000000099".to_string(),
+                "00000000-0000-0000-0000-000000000100".to_string(),
+            ]
+        );
+        assert_eq!(offsets.lock().unwrap().as_slice(), &[0, 100]);
+    }
+
     #[test]
     fn search_release_group() {
         let mut client = get_client("release_group_01");
@@ -0,0 +1,105 @@
+//! Checking whether the API is reachable.
+//!
+//! Applications can use this to show whether they are online before queuing
+//! work, instead of finding out with the first failing request. The check
+//! performs the lookup of a tiny entity and doesn't retry, so an unavailable
+//! server is reported immediately.
+
+use crate::client::Client;
+use crate::error::Error;
+
+use std::time::{Duration, Instant};
+
+/// The url requested by `Client::ping`, the lookup of the area `Japan`.
+const PING_URL: &'static str =
+    "https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c";
+
+/// The result of `Client::ping`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Ping {
+    /// Time from sending the request until the response was received or the
+    /// request failed.
+    pub latency: Duration,
+
+    /// The HTTP status code of the response, `None` if the server couldn't be
+    /// reached at all.
+    pub status: Option<u16>,
+}
+
+impl Ping {
+    /// Whether the server answered the request successfully.
+    pub fn is_available(&self) -> bool {
+        match self.status {
+            Some(status) => status < 400,
+            None => false,
+        }
+    }
+}
+
+/// Perform the request of `Client::ping`.
+pub(crate) fn ping(client: &mut Client) -> Result<Ping, Error> {
+    let rate_limit_wait = client.stats.rate_limit_wait;
+    let start = Instant::now();
+    let response = client.send_attempts(PING_URL.parse()?, Vec::new(), None, 1);
+    // Waiting for the rate limit isn't part of the latency.
+    let latency = start.elapsed() - (client.stats.rate_limit_wait - rate_limit_wait);
+
+    let status = match response {
+        Ok(response) => Some(response.status),
+        Err(e) => {
+            if e.is_cancelled() {
+                return Err(e);
+            }
+            e.status()
+        }
+    };
+    Ok(Ping { latency, status })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::error::ErrorKind;
+    use crate::util::test_utils::{response, FnTransport};
+
+    /// Answers with the statuses in order, failing to connect after the last
+    /// one.
+    fn status_responses(
+        mut statuses: Vec<u16>,
+    ) -> impl FnMut(&HttpRequest) -> Result<HttpResponse, Error> + Send {
+        move |request: &HttpRequest| {
+            assert_eq!(request.url.as_str(), PING_URL);
+            if statuses.is_empty() {
+                return Err(Error::new("Connection refused.", ErrorKind::Communication));
+            }
+            Ok(response(statuses.remove(0), Vec::new()))
+        }
+    }
+
+    #[test]
+    fn availability() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            FnTransport(status_responses(vec![200, 503])),
+        );
+
+        let ping = client.ping().unwrap();
+        assert!(ping.is_available());
+        assert_eq!(ping.status, Some(200));
+
+        // The server being unavailable isn't retried.
+        let ping = client.ping().unwrap();
+        assert!(!ping.is_available());
+        assert_eq!(ping.status, Some(503));
+        assert_eq!(client.stats().retries, 0);
+
+        let ping = client.ping().unwrap();
+        assert!(!ping.is_available());
+        assert_eq!(ping.status, None);
+        assert_eq!(client.stats().requests, 3);
+    }
+}
@@ -0,0 +1,92 @@
+use super::{CancellationToken, Client, ClientConfig, HttpTransport};
+
+#[cfg(feature = "default-transport")]
+use super::ReqwestTransport;
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Rate limit which can be shared between multiple clients.
+///
+/// Every request reserves the next free time slot, so concurrent clients are
+/// served in the order they asked and never closer together than the
+/// configured interval.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimiter {
+    next_slot: Arc<Mutex<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        RateLimiter {
+            next_slot: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Reserve a slot for a request and return the instant at which the
+    /// request may be performed.
+    pub fn reserve(&self, interval: Duration) -> Instant {
+        // The guarded value is always valid, so a poisoned lock can be used.
+        let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let slot = if *next_slot > now { *next_slot } else { now };
+        *next_slot = slot + interval;
+        slot
+    }
+
+    /// The instant at which the next slot is free.
+    pub fn next_slot(&self) -> Instant {
+        *self.next_slot.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Hands out `Client`s which share one rate limit, so an application can
+/// perform requests from multiple threads without exceeding the limits of
+/// the MusicBrainz API.
+///
+/// A `Client` requires `&mut self` for its requests, so every thread should
+/// get its own client from the pool. The pool itself is `Send` and `Sync` and
+/// can be shared e.g. using an `Arc`.
+///
+/// All clients of a pool also share one `CancellationToken`.
+#[derive(Clone, Debug)]
+pub struct ClientPool {
+    config: ClientConfig,
+    rate_limit: RateLimiter,
+    cancellation: CancellationToken,
+}
+
+impl ClientPool {
+    /// Create a new pool whose clients use the specified configuration.
+    pub fn new(config: ClientConfig) -> Self {
+        ClientPool {
+            config: config,
+            rate_limit: RateLimiter::new(),
+            cancellation: CancellationToken::new(),
+        }
+    }
+
+    /// Returns a new client performing its requests directly.
+    #[cfg(feature = "default-transport")]
+    pub fn client(&self) -> Client {
+        self.client_with_transport(ReqwestTransport::new())
+    }
+
+    /// Returns a new client performing its requests with the specified
+    /// `HttpTransport`.
+    pub fn client_with_transport<T>(&self, transport: T) -> Client
+    where
+        T: HttpTransport + Send + 'static,
+    {
+        let mut client = Client::with_transport(self.config.clone(), transport);
+        client.rate_limit = self.rate_limit.clone();
+        client.cancellation = self.cancellation.clone();
+        client
+    }
+
+    /// Returns the token which can be used to cancel the requests of all
+    /// clients of this pool.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+}
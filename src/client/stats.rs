@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+/// Counters about the requests performed by a `Client`.
+///
+/// Long running jobs can use these to report their progress, e.g. the
+/// average time per request multiplied with the number of remaining requests
+/// gives an estimate of the remaining time.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ClientStats {
+    /// Number of requests sent to the server, including retries.
+    pub requests: u64,
+
+    /// Number of times the server was unavailable, each of which causes a
+    /// retry unless the maximal number of retries is reached.
+    pub retries: u64,
+
+    /// Number of bytes of all response bodies.
+    pub bytes_downloaded: u64,
+
+    /// Time spent waiting for the rate limit.
+    pub rate_limit_wait: Duration,
+
+    /// Time spent waiting before retrying requests.
+    pub backoff_wait: Duration,
+}
+
+impl ClientStats {
+    /// Total time spent waiting instead of performing requests.
+    pub fn total_wait(&self) -> Duration {
+        self.rate_limit_wait + self.backoff_wait
+    }
+
+    /// Estimate how long `remaining` further requests will take, assuming
+    /// they have to wait as long as the previous ones on average.
+    ///
+    /// Every request takes at least `interval`, the minimal time between two
+    /// requests of the client. Time spent performing the requests is not
+    /// included, as the client doesn't measure it.
+    pub fn estimate_remaining(&self, remaining: u64, interval: Duration) -> Duration {
+        // Computed in nanoseconds, so neither huge request counts nor long
+        // waits can truncate or overflow, saturating at the maximal duration.
+        let total_wait = self.total_wait().as_nanos();
+        let average = if self.requests == 0 {
+            0
+        } else {
+            total_wait / u128::from(self.requests)
+        };
+        let per_request = average.max(interval.as_nanos());
+        let nanos = per_request.saturating_mul(u128::from(remaining));
+
+        let secs = nanos / 1_000_000_000;
+        if secs > u128::from(u64::max_value()) {
+            Duration::new(u64::max_value(), 999_999_999)
+        } else {
+            Duration::new(secs as u64, (nanos % 1_000_000_000) as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_remaining() {
+        let stats = ClientStats {
+            requests: 4,
+            rate_limit_wait: Duration::from_secs(6),
+            backoff_wait: Duration::from_secs(2),
+            ..ClientStats::default()
+        };
+        assert_eq!(
+            stats.estimate_remaining(10, Duration::from_secs(1)),
+            Duration::from_secs(20)
+        );
+        assert_eq!(
+            stats.estimate_remaining(10, Duration::from_secs(3)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn estimate_remaining_saturates() {
+        let stats = ClientStats {
+            requests: u64::max_value(),
+            rate_limit_wait: Duration::from_secs(u64::max_value()),
+            ..ClientStats::default()
+        };
+        assert_eq!(
+            stats.estimate_remaining(u64::max_value(), Duration::from_secs(2)),
+            Duration::new(u64::max_value(), 999_999_999)
+        );
+        assert_eq!(
+            ClientStats::default().estimate_remaining(0, Duration::from_secs(1)),
+            Duration::from_secs(0)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Looking up tracks by their MBID.
+//!
+//! The API has no lookup for tracks, but the search index of recordings
+//! contains the MBIDs of their tracks, which leads to the release containing
+//! the track. External databases often only store the track MBID, so this is
+//! the only way to get back to the release.
+
+use crate::client::{check_response_error, Client};
+use crate::entities::{Mbid, Release, ReleaseMedium, ReleaseOptions, ReleaseTrack};
+use crate::error::{Error, ErrorKind};
+
+use xpath_reader::Reader;
+
+/// A release together with the position of one of its tracks, as returned by
+/// `Client::get_track`.
+#[derive(Clone, Debug)]
+pub struct TrackLocation {
+    release: Release,
+    /// Index of the medium in the mediums of the release.
+    medium: usize,
+    /// Index of the track in the tracks of the medium.
+    track: usize,
+}
+
+impl TrackLocation {
+    /// The release containing the track, fetched with the artists and all
+    /// tracks.
+    pub fn release(&self) -> &Release {
+        &self.release
+    }
+
+    /// Returns the release containing the track.
+    pub fn into_release(self) -> Release {
+        self.release
+    }
+
+    /// The medium containing the track.
+    pub fn medium(&self) -> &ReleaseMedium {
+        // The release is always fetched with its tracks.
+        &self.release.mediums().unwrap()[self.medium]
+    }
+
+    /// The track itself.
+    pub fn track(&self) -> &ReleaseTrack {
+        &self.medium().tracks()[self.track]
+    }
+
+    /// The position of the medium on the release and of the track on the
+    /// medium, both starting at 1.
+    pub fn position(&self) -> (u16, u16) {
+        (self.medium().position(), self.track().position)
+    }
+}
+
+/// Find the release containing the track with the MBID `mbid`, see
+/// `Client::get_track`.
+pub(crate) fn get_track(client: &mut Client, mbid: &Mbid) -> Result<TrackLocation, Error> {
+    let url = format!("https://musicbrainz.org/ws/2/recording/?query=tid:{}", mbid);
+    let body = client.get_body(url.parse()?)?;
+
+    let context = crate::util::musicbrainz_context();
+    let reader = Reader::from_str(body.as_str(), Some(&context))?;
+    check_response_error(&reader)?;
+    // Every release of the recording is listed, but only the one containing
+    // the track lists it with its MBID.
+    let release_mbid: Option<Mbid> = reader.read(
+        format!(
+            "//mb:recording/mb:release-list/mb:release\
+             [mb:medium-list/mb:medium/mb:track-list/mb:track/@id='{}']/@id",
+            mbid
+        )
+        .as_str(),
+    )?;
+    let not_found = || {
+        Error::new(
+            format!("No release contains the track {}.", mbid),
+            ErrorKind::NotFound,
+        )
+    };
+    let release_mbid = release_mbid.ok_or_else(not_found)?;
+
+    let release: Release = client.get_by_mbid(&release_mbid, ReleaseOptions::for_tagging())?;
+    let found = release
+        .mediums()
+        .into_option()
+        .unwrap_or(&[])
+        .iter()
+        .enumerate()
+        .filter_map(|(m, medium)| {
+            medium
+                .tracks()
+                .iter()
+                .position(|t| &t.mbid == mbid)
+                .map(|t| (m, t))
+        })
+        .next();
+
+    match found {
+        Some((medium, track)) => Ok(TrackLocation {
+            release,
+            medium,
+            track,
+        }),
+        // The search index might be outdated.
+        None => Err(not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::util::test_utils::{response, FnTransport};
+
+    const TRACK: &'static str = "e57b3990-eb36-476e-beac-583e0bbe6f87";
+    const RELEASE: &'static str = "ed118c5f-d940-4b52-a37b-b1a205374abe";
+
+    /// Answers the search for recordings and the lookup of the release.
+    fn track_response(request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let body = if request.url.path() == "/ws/2/recording/" {
+            format!(r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><recording-list count="1" offset="0"><recording id="53f87e98-351e-453e-b949-bdacf4cbeccd" ext:score="100"><title>Inside My Head</title><release-list><release id="c8b3b5a9-2b3e-4a52-bb10-6a1d8e4ea0c1"><title>Creep</title><medium-list><medium><position>1</position><track-list count="3" offset="1"><track id="0a3e5d6f-1b2c-4d7e-8f90-a1b2c3d4e5f6"><number>2</number><title>Inside My Head</title></track></track-list></medium></medium-list></release><release id="{}"><title>Creep</title><medium-list><medium><position>2</position><track-list count="1" offset="0"><track id="{}"><number>A</number><title>Inside My Head</title></track></track-list></medium></medium-list></release></release-list></recording></recording-list></metadata>"#, RELEASE, TRACK)
+        } else {
+            assert!(request.url.path().ends_with(RELEASE));
+            format!(r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="{}"><title>Creep</title><medium-list count="2"><medium><position>1</position><track-list count="1"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>1</position><number>1</number><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Creep</title></recording></track></track-list></medium><medium><position>2</position><track-list count="1"><track id="{}"><position>1</position><number>A</number><recording id="53f87e98-351e-453e-b949-bdacf4cbeccd"><title>Inside My Head</title></recording></track></track-list></medium></medium-list></release></metadata>"#, RELEASE, TRACK)
+        };
+        Ok(response(200, body))
+    }
+
+    #[test]
+    fn track_on_second_medium() {
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config()
+                .request_interval(0)
+                .build()
+                .unwrap(),
+            FnTransport(track_response),
+        );
+
+        let location = client.get_track(&TRACK.parse().unwrap()).unwrap();
+        assert_eq!(location.release().mbid(), &RELEASE.parse().unwrap());
+        assert_eq!(location.position(), (2, 1));
+        assert_eq!(location.track().number, "A".to_string());
+        assert_eq!(location.track().recording.title, "Inside My Head".to_string());
+
+        let err = client
+            .get_track(&"00000000-0000-0000-0000-000000000000".parse().unwrap())
+            .unwrap_err();
+        assert!(err.is_not_found());
+    }
+}
@@ -0,0 +1,297 @@
+//! Abstraction over the HTTP stack used to communicate with the API.
+//!
+//! By default requests are performed with `reqwest` and recorded responses
+//! are replayed with `reqwest_mock`, but any type implementing
+//! `HttpTransport` can be plugged into a `Client`, e.g. to use a different
+//! HTTP library, to route requests through a proxy or to stub responses in
+//! tests. Without the default `default-transport` feature the crate doesn't
+//! depend on either of them and clients have to be created with
+//! `Client::with_transport`.
+
+use crate::error::{Error, ErrorKind};
+
+#[cfg(any(feature = "default-transport", test))]
+use reqwest_mock::Client as MockClient;
+#[cfg(any(feature = "default-transport", test))]
+use reqwest_mock::GenericClient;
+#[cfg(any(feature = "default-transport", test))]
+use reqwest_mock::header::Headers;
+use std::io::{self, Read};
+use std::time::Duration;
+use url::Url;
+
+/// A request to be performed by a `HttpTransport`.
+#[derive(Clone, Debug)]
+pub struct HttpRequest {
+    /// The url to request.
+    pub url: Url,
+
+    /// Headers to be sent along with the request as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+
+    /// Maximal time the request may take, if the transport supports it.
+    pub timeout: Option<Duration>,
+
+    /// Maximal size of the response body in bytes.
+    ///
+    /// Transports which read the body incrementally should stop and return
+    /// the truncated body once it is exceeded, the client fails the request
+    /// in that case. `ReqwestTransport` does so, replaying transports always
+    /// read the whole recording.
+    pub max_body_size: Option<u64>,
+}
+
+/// A response as returned by a `HttpTransport`.
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+    /// The HTTP status code of the response.
+    pub status: u16,
+
+    /// The headers of the response as `(name, value)` pairs.
+    pub headers: Vec<(String, String)>,
+
+    /// The raw body of the response.
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns the value of the first header with the specified name.
+    ///
+    /// Header names are compared case insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, ref v)| v.as_str())
+    }
+
+    /// Consumes the response and returns its body decoded as UTF-8, without
+    /// copying it.
+    pub fn into_body_utf8(self) -> Result<String, Error> {
+        String::from_utf8(self.body).map_err(|e| {
+            Error::new(
+                format!("response body is not valid UTF-8: {}", e),
+                ErrorKind::Communication,
+            )
+        })
+    }
+}
+
+/// A HTTP backend which can be used by the `Client`.
+pub trait HttpTransport {
+    /// Perform a `GET` request.
+    fn get(&mut self, request: &HttpRequest) -> Result<HttpResponse, Error>;
+
+    /// Perform a `POST` request with the specified body.
+    ///
+    /// This is only needed for submissions, e.g. to ListenBrainz. The default
+    /// implementation returns an error.
+    fn post(&mut self, request: &HttpRequest, _body: &[u8]) -> Result<HttpResponse, Error> {
+        Err(Error::new(
+            format!("The transport doesn't support POST requests to {}.", request.url),
+            ErrorKind::Communication,
+        ))
+    }
+
+    /// Called before a request is retried.
+    ///
+    /// Transports replaying recorded responses should make sure the next
+    /// request is actually performed, the default implementation does nothing.
+    fn force_record_next(&mut self) {}
+}
+
+/// The default transport, performing the requests directly with `reqwest`.
+///
+/// Request timeouts are applied and bodies are only read up to the
+/// `max_body_size` of the request, so a huge response never ends up in
+/// memory.
+#[cfg(feature = "default-transport")]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+    /// The timeout `client` was built with.
+    timeout: Option<Duration>,
+}
+
+#[cfg(feature = "default-transport")]
+impl ReqwestTransport {
+    /// Create a new transport without any timeout.
+    pub fn new() -> Self {
+        ReqwestTransport {
+            client: reqwest::Client::new(),
+            timeout: None,
+        }
+    }
+
+    /// Returns a client applying `timeout`, which is only rebuilt if the
+    /// timeout changed.
+    fn client(&mut self, timeout: Option<Duration>) -> Result<&reqwest::Client, Error> {
+        if timeout != self.timeout {
+            let mut builder = reqwest::Client::builder();
+            if let Some(timeout) = timeout {
+                builder.timeout(timeout);
+            }
+            self.client = builder.build()?;
+            self.timeout = timeout;
+        }
+        Ok(&self.client)
+    }
+}
+
+#[cfg(feature = "default-transport")]
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        ReqwestTransport::new()
+    }
+}
+
+#[cfg(feature = "default-transport")]
+impl HttpTransport for ReqwestTransport {
+    fn get(&mut self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let response = self
+            .client(request.timeout)?
+            .get(request.url.clone())
+            .headers(reqwest_headers(request))
+            .send()?;
+        read_response(response, request.max_body_size)
+    }
+
+    fn post(&mut self, request: &HttpRequest, body: &[u8]) -> Result<HttpResponse, Error> {
+        let response = self
+            .client(request.timeout)?
+            .post(request.url.clone())
+            .headers(reqwest_headers(request))
+            .body(body.to_vec())
+            .send()?;
+        read_response(response, request.max_body_size)
+    }
+}
+
+#[cfg(feature = "default-transport")]
+fn reqwest_headers(request: &HttpRequest) -> reqwest::header::Headers {
+    let mut headers = reqwest::header::Headers::new();
+    for &(ref name, ref value) in request.headers.iter() {
+        headers.set_raw(name.clone(), value.clone());
+    }
+    headers
+}
+
+#[cfg(feature = "default-transport")]
+fn read_response(
+    mut response: reqwest::Response,
+    max_body_size: Option<u64>,
+) -> Result<HttpResponse, Error> {
+    let mut result = HttpResponse {
+        status: response.status().as_u16(),
+        headers: response
+            .headers()
+            .iter()
+            .map(|h| (h.name().to_string(), h.value_string()))
+            .collect(),
+        body: Vec::new(),
+    };
+    let declared = result
+        .header("Content-Length")
+        .and_then(|len| len.trim().parse::<u64>().ok());
+    // The client fails the request because of the declared length anyway.
+    if let (Some(declared), Some(max)) = (declared, max_body_size) {
+        if declared > max {
+            return Ok(result);
+        }
+    }
+    result.body = read_body(&mut response, max_body_size).map_err(|e| {
+        Error::new(
+            format!("Reading the response failed: {}", e),
+            ErrorKind::Communication,
+        )
+    })?;
+    Ok(result)
+}
+
+/// Reads `source` up to one byte more than `max_size`, so a too large body
+/// can be detected without reading all of it.
+#[cfg(any(feature = "default-transport", test))]
+fn read_body<R: Read>(mut source: R, max_size: Option<u64>) -> io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    match max_size {
+        Some(max_size) => source.take(max_size + 1).read_to_end(&mut body)?,
+        None => source.read_to_end(&mut body)?,
+    };
+    Ok(body)
+}
+
+/// The transport replaying recorded responses, which doesn't support request
+/// timeouts.
+///
+/// Requests with a timeout are rejected with a usage error instead of
+/// silently waiting longer than configured. The recordings are always read
+/// completely, the client checks their size afterwards.
+#[cfg(any(feature = "default-transport", test))]
+impl HttpTransport for GenericClient {
+    fn get(&mut self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        check_no_timeout(request)?;
+        let response = MockClient::get(self, request.url.clone())
+            .headers(to_headers(request))
+            .send()?;
+        Ok(from_response(response))
+    }
+
+    fn post(&mut self, request: &HttpRequest, body: &[u8]) -> Result<HttpResponse, Error> {
+        check_no_timeout(request)?;
+        let response = MockClient::post(self, request.url.clone())
+            .headers(to_headers(request))
+            .body(body.to_vec())
+            .send()?;
+        Ok(from_response(response))
+    }
+
+    fn force_record_next(&mut self) {
+        GenericClient::force_record_next(self);
+    }
+}
+
+#[cfg(any(feature = "default-transport", test))]
+fn check_no_timeout(request: &HttpRequest) -> Result<(), Error> {
+    match request.timeout {
+        Some(_) => Err(Error::new(
+            "Replaying responses doesn't support request timeouts.",
+            ErrorKind::UsageError,
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg(any(feature = "default-transport", test))]
+fn to_headers(request: &HttpRequest) -> Headers {
+    let mut headers = Headers::new();
+    for &(ref name, ref value) in request.headers.iter() {
+        headers.set_raw(name.clone(), value.clone());
+    }
+    headers
+}
+
+#[cfg(any(feature = "default-transport", test))]
+fn from_response(response: reqwest_mock::Response) -> HttpResponse {
+    HttpResponse {
+        status: response.status.as_u16(),
+        headers: response
+            .headers
+            .iter()
+            .map(|h| (h.name().to_string(), h.value_string()))
+            .collect(),
+        body: response.body,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_limited_body() {
+        let body = b"<metadata></metadata>";
+        assert_eq!(read_body(&body[..], None).unwrap(), body.to_vec());
+        assert_eq!(read_body(&body[..], Some(100)).unwrap(), body.to_vec());
+        // One byte more than allowed is read, so the client notices.
+        assert_eq!(read_body(&body[..], Some(4)).unwrap(), b"<meta".to_vec());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::error::{Error, ErrorKind};
+
+use std::fmt::{self, Display, Formatter};
+
+/// Parts of user agents copied from documentation instead of being filled in.
+const PLACEHOLDERS: &[&str] = &[
+    "example.com",
+    "example.org",
+    "example.net",
+    "contact-url",
+    "contact-email",
+    "application name",
+    "<",
+    ">",
+];
+
+/// A user agent in the format recommended by MusicBrainz, i.e.
+/// `Application name/<version> ( contact )`.
+///
+/// A `UserAgent` can be converted into the `String` expected by
+/// `ClientConfig::user_agent`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct UserAgent {
+    app: String,
+    version: String,
+    contact: String,
+}
+
+impl UserAgent {
+    /// Create a user agent for version `version` of the application `app`.
+    ///
+    /// `contact` has to be either a url or an email address, under which the
+    /// developers of the application can be reached. Empty values and
+    /// placeholders copied from the documentation are rejected with a usage
+    /// error, as requests with such user agents are throttled.
+    pub fn new<A, V, C>(app: A, version: V, contact: C) -> Result<Self, Error>
+    where
+        A: Into<String>,
+        V: Into<String>,
+        C: Into<String>,
+    {
+        let user_agent = UserAgent {
+            app: app.into().trim().to_string(),
+            version: version.into().trim().to_string(),
+            contact: contact.into().trim().to_string(),
+        };
+        user_agent.validate().map_err(|problem| {
+            Error::new(format!("Invalid user agent: {}", problem), ErrorKind::UsageError)
+        })?;
+        Ok(user_agent)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.app.is_empty() || self.app.contains('/') {
+            return Err(format!("application name '{}' is invalid", self.app));
+        }
+        if self.version.is_empty() || self.version.contains(char::is_whitespace) {
+            return Err(format!("version '{}' is invalid", self.version));
+        }
+        if !self.contact.contains('@') && !self.contact.contains("://") {
+            return Err(format!(
+                "contact '{}' is neither a url nor an email address",
+                self.contact
+            ));
+        }
+        for part in &[&self.app, &self.version, &self.contact] {
+            let lower = part.to_lowercase();
+            if let Some(placeholder) = PLACEHOLDERS.iter().find(|p| lower.contains(*p)) {
+                return Err(format!("'{}' contains the placeholder '{}'", part, placeholder));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for UserAgent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}/{} ( {} )", self.app, self.version, self.contact)
+    }
+}
+
+impl From<UserAgent> for String {
+    fn from(user_agent: UserAgent) -> String {
+        user_agent.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format() {
+        let user_agent = UserAgent::new("Tagger", "1.2.0", "https://tagger.rs/contact").unwrap();
+        assert_eq!(
+            String::from(user_agent),
+            "Tagger/1.2.0 ( https://tagger.rs/contact )".to_string()
+        );
+        assert!(UserAgent::new("Tagger", "1.2.0", "me@tagger.rs").is_ok());
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(UserAgent::new("", "1.0", "me@tagger.rs")
+            .unwrap_err()
+            .is_usage_error());
+        assert!(UserAgent::new("Tagger", "", "me@tagger.rs").is_err());
+        assert!(UserAgent::new("Tagger", "1.0", "").is_err());
+        assert!(UserAgent::new("Tagger", "1.0", "tagger.rs").is_err());
+        assert!(UserAgent::new("Tagger", "1.0", "me@example.com").is_err());
+        assert!(UserAgent::new("Application name", "<version>", "me@tagger.rs").is_err());
+    }
+}
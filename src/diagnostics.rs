@@ -0,0 +1,55 @@
+//! Detection of XML elements in responses which aren't parsed by the crate.
+//!
+//! This is meant to notice changes of the web service schema, like new fields
+//! or includes which aren't supported yet. Only the direct children of the
+//! entity element are checked, as these are where new fields appear.
+
+use regex::Regex;
+
+/// Returns a warning for every distinct child element of the top level
+/// `entity` element of `body` whose name is not in `parsed`.
+pub(crate) fn unparsed_elements(body: &str, entity: &str, parsed: &[&str]) -> Vec<String> {
+    let tag = Regex::new(r"<(/?)([A-Za-z][\w:.-]*)[^>]*?(/?)>").unwrap();
+
+    let mut warnings = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    // Names of the currently open elements, the root element first.
+    let mut open: Vec<&str> = Vec::new();
+
+    for cap in tag.captures_iter(body) {
+        let name = cap.get(2).unwrap().as_str();
+        if !cap[1].is_empty() {
+            open.pop();
+            continue;
+        }
+
+        if open.len() == 2 && open[1] == entity && !parsed.contains(&name) && !seen.contains(&name)
+        {
+            seen.push(name);
+            warnings.push(format!("Unparsed element `{}` in `{}`.", name, entity));
+        }
+        if cap[3].is_empty() {
+            open.push(name);
+        }
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unparsed() {
+        let body = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area id="2db42837-c832-3c27-b4a3-08198f75693c"><name>Japan</name><sort-name>Japan</sort-name><life-span><begin>1600</begin></life-span><tag-list><tag count="1"><name>asia</name></tag></tag-list><alias-list/></area></metadata>"#;
+
+        assert_eq!(
+            unparsed_elements(body, "area", &["name", "sort-name", "life-span"]),
+            vec![
+                "Unparsed element `tag-list` in `area`.".to_string(),
+                "Unparsed element `alias-list` in `area`.".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+//! Compare two fetched versions of the same entity.
+//!
+//! This is useful for applications mirroring data from MusicBrainz in a local
+//! database, as only the fields which actually changed have to be updated.
+//!
+//! Fields which were only requested for one of the two versions are not
+//! compared, so make sure to fetch both versions with the same options.
+
+use crate::entities::{Artist, ArtistCredit, Mbid, OnRequest, Release, ReleaseTrack};
+
+use std::fmt::Display;
+
+/// A single difference between two versions of an entity.
+///
+/// Values are formatted for display, `None` means the field was not set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    /// A field of the entity itself changed.
+    Field {
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+
+    /// A track was added to the release.
+    TrackAdded(Mbid),
+
+    /// A track was removed from the release.
+    TrackRemoved(Mbid),
+
+    /// A field of a track contained in both versions changed.
+    TrackChanged {
+        track: Mbid,
+        field: &'static str,
+        old: Option<String>,
+        new: Option<String>,
+    },
+}
+
+/// Entities which can be compared with another version of themselves.
+pub trait Diff {
+    /// Returns the changes from `self` to `new`.
+    fn diff(&self, new: &Self) -> Vec<Change>;
+}
+
+/// Collects the changes of one entity.
+struct Changes {
+    changes: Vec<Change>,
+}
+
+impl Changes {
+    fn field<T: Display>(&mut self, field: &'static str, old: Option<T>, new: Option<T>) {
+        let old = old.map(|v| v.to_string());
+        let new = new.map(|v| v.to_string());
+        if old != new {
+            self.changes.push(Change::Field { field, old, new });
+        }
+    }
+
+    /// Compare a field which is only present if it was requested.
+    fn requested<T: Display>(&mut self, field: &'static str, old: OnRequest<T>, new: OnRequest<T>) {
+        match (old, new) {
+            (OnRequest::NotRequested, _) | (_, OnRequest::NotRequested) => {}
+            (old, new) => self.field(field, old.into_option(), new.into_option()),
+        }
+    }
+}
+
+/// Format an artist credit with the credited names and join phrases, including
+/// the names and MBIDs of the artists so a changed link to an artist credited
+/// with the same name is detected too.
+///
+/// Returns `None` for an empty credit, which means it wasn't listed.
+fn format_artist_credit(credit: &[ArtistCredit]) -> Option<String> {
+    if credit.is_empty() {
+        return None;
+    }
+    Some(
+        credit
+            .iter()
+            .map(|c| {
+                format!(
+                    "{} ({}, {}) {:?}",
+                    c.name, c.artist.name, c.artist.mbid, c.join_phrase
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+impl Diff for Release {
+    fn diff(&self, new: &Self) -> Vec<Change> {
+        let mut c = Changes { changes: Vec::new() };
+
+        c.field("title", Some(self.title()), Some(new.title()));
+        c.field("date", self.date(), new.date());
+        c.field("country", self.country(), new.country());
+        c.field("status", self.status(), new.status());
+        c.field("barcode", self.barcode(), new.barcode());
+        c.field("packaging", self.packaging(), new.packaging());
+        c.field(
+            "language",
+            self.language().map(|l| l.to_639_3()),
+            new.language().map(|l| l.to_639_3()),
+        );
+        c.field("script", self.script(), new.script());
+        c.field("disambiguation", self.disambiguation(), new.disambiguation());
+        c.requested("annotation", self.annotation(), new.annotation());
+        if let (OnRequest::Some(old_credit), OnRequest::Some(new_credit)) =
+            (self.artist_credit(), new.artist_credit())
+        {
+            c.field(
+                "artist_credit",
+                format_artist_credit(old_credit),
+                format_artist_credit(new_credit),
+            );
+        }
+        c.requested(
+            "release_group",
+            self.release_group().map(|g| g.mbid.clone()),
+            new.release_group().map(|g| g.mbid.clone()),
+        );
+
+        if let (OnRequest::Some(old_mediums), OnRequest::Some(new_mediums)) =
+            (self.mediums(), new.mediums())
+        {
+            // Tracks are identified by their MBID and compared including
+            // their location on the release.
+            let flatten = |mediums: &[crate::entities::ReleaseMedium]| -> Vec<(u16, ReleaseTrack)> {
+                mediums
+                    .iter()
+                    .flat_map(|m| m.tracks().iter().map(move |t| (m.position(), t.clone())))
+                    .collect()
+            };
+            let old_tracks = flatten(old_mediums);
+            let new_tracks = flatten(new_mediums);
+
+            for &(ref medium, ref old) in old_tracks.iter() {
+                match new_tracks.iter().find(|&&(_, ref t)| t.mbid == old.mbid) {
+                    Some(&(ref new_medium, ref new)) => {
+                        let mut track = Changes { changes: Vec::new() };
+                        track.field("medium", Some(medium), Some(new_medium));
+                        track.field("position", Some(old.position), Some(new.position));
+                        track.field("number", Some(&old.number), Some(&new.number));
+                        track.field("title", Some(&old.title), Some(&new.title));
+                        track.field(
+                            "length",
+                            old.length.map(|l| l.as_millis()),
+                            new.length.map(|l| l.as_millis()),
+                        );
+                        track.field(
+                            "recording",
+                            Some(&old.recording.mbid),
+                            Some(&new.recording.mbid),
+                        );
+                        track.field(
+                            "artist_credit",
+                            format_artist_credit(&old.artist_credit),
+                            format_artist_credit(&new.artist_credit),
+                        );
+                        for change in track.changes {
+                            if let Change::Field { field, old: o, new: n } = change {
+                                c.changes.push(Change::TrackChanged {
+                                    track: old.mbid.clone(),
+                                    field,
+                                    old: o,
+                                    new: n,
+                                });
+                            }
+                        }
+                    }
+                    None => c.changes.push(Change::TrackRemoved(old.mbid.clone())),
+                }
+            }
+            for &(_, ref new) in new_tracks.iter() {
+                if !old_tracks.iter().any(|&(_, ref t)| t.mbid == new.mbid) {
+                    c.changes.push(Change::TrackAdded(new.mbid.clone()));
+                }
+            }
+        }
+
+        c.changes
+    }
+}
+
+impl Diff for Artist {
+    fn diff(&self, new: &Self) -> Vec<Change> {
+        let mut c = Changes { changes: Vec::new() };
+
+        c.field("name", Some(self.name()), Some(new.name()));
+        c.field("sort_name", Some(self.sort_name()), Some(new.sort_name()));
+        c.field("disambiguation", self.disambiguation(), new.disambiguation());
+        c.field("artist_type", self.artist_type(), new.artist_type());
+        c.field("gender", self.gender(), new.gender());
+        c.field(
+            "area",
+            self.area().map(|a| &a.mbid),
+            new.area().map(|a| &a.mbid),
+        );
+        c.field("begin_date", self.begin_date(), new.begin_date());
+        c.field("end_date", self.end_date(), new.end_date());
+        c.field("ipi_code", self.ipi_code(), new.ipi_code());
+        c.field("isni_code", self.isni_code(), new.isni_code());
+        c.requested("annotation", self.annotation(), new.annotation());
+
+        c.changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::ReleaseOptions;
+
+    const OLD: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><status>Official</status><medium-list count="1"><medium><position>1</position><track-list count="2"><track id="bfc618d9-8595-3d97-8d19-5b8a2dcc9104"><position>1</position><number>1</number><length>237933</length><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title></recording></track><track id="237450df-3ba8-3627-b270-49231eca1165"><position>2</position><number>2</number><recording id="c17a2b44-8b59-4575-a557-b0bfa03df868"><title>Lurgee</title></recording></track></track-list></medium></medium-list></release></metadata>"#;
+
+    const NEW: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep (single)</title><status>Official</status><medium-list count="1"><medium><position>1</position><track-list count="2"><track id="bfc618d9-8595-3d97-8d19-5b8a2dcc9104"><position>1</position><number>1</number><length>237900</length><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title></recording></track><track id="6acd9fa0-e9a5-39b3-a0b4-70cd9e8bae01"><position>2</position><number>2</number><recording id="10b05f6e-e670-48c2-b269-6b442fde6736"><title>Inside My Head</title></recording></track></track-list></medium></medium-list></release></metadata>"#;
+
+    #[test]
+    fn release_changes() {
+        let mut options = ReleaseOptions::minimal();
+        options.recordings = true;
+        let old: Release = crate::util::test_utils::parse_entity(OLD, options.clone()).unwrap();
+        let new: Release = crate::util::test_utils::parse_entity(NEW, options).unwrap();
+
+        assert_eq!(old.diff(&old), Vec::new());
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                Change::Field {
+                    field: "title",
+                    old: Some("Creep".to_string()),
+                    new: Some("Creep (single)".to_string()),
+                },
+                Change::TrackChanged {
+                    track: "bfc618d9-8595-3d97-8d19-5b8a2dcc9104".parse().unwrap(),
+                    field: "length",
+                    old: Some("237933".to_string()),
+                    new: Some("237900".to_string()),
+                },
+                Change::TrackRemoved("237450df-3ba8-3627-b270-49231eca1165".parse().unwrap()),
+                Change::TrackAdded("6acd9fa0-e9a5-39b3-a0b4-70cd9e8bae01".parse().unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn artist_credit_changes() {
+        let release = |join_phrase: &str, credited: &str| {
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><artist-credit><name-credit joinphrase="{}"><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit><name-credit><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></name-credit></artist-credit><medium-list count="1"><medium><position>1</position><track-list count="1"><track id="bfc618d9-8595-3d97-8d19-5b8a2dcc9104"><position>1</position><number>1</number><artist-credit><name-credit><name>{}</name><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title></recording></track></track-list></medium></medium-list></release></metadata>"#,
+                join_phrase, credited
+            )
+        };
+        let mut options = ReleaseOptions::minimal();
+        options.artists = true;
+        options.recordings = true;
+        let parse = |xml: String| -> Release {
+            crate::util::test_utils::parse_entity(&xml, options.clone()).unwrap()
+        };
+        let old = parse(release(" feat. ", "Radiohead"));
+        let new = parse(release(" &amp; ", "On a Friday"));
+
+        assert_eq!(old.diff(&old), Vec::new());
+        assert_eq!(
+            old.diff(&new),
+            vec![
+                Change::Field {
+                    field: "artist_credit",
+                    old: Some(
+                        "Radiohead (Radiohead, a74b1b7f-71a5-4011-9441-d0b5e4122711) \" feat. \", \
+                         Thom Yorke (Thom Yorke, 8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b) \"\""
+                            .to_string()
+                    ),
+                    new: Some(
+                        "Radiohead (Radiohead, a74b1b7f-71a5-4011-9441-d0b5e4122711) \" & \", \
+                         Thom Yorke (Thom Yorke, 8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b) \"\""
+                            .to_string()
+                    ),
+                },
+                Change::TrackChanged {
+                    track: "bfc618d9-8595-3d97-8d19-5b8a2dcc9104".parse().unwrap(),
+                    field: "artist_credit",
+                    old: Some(
+                        "Radiohead (Radiohead, a74b1b7f-71a5-4011-9441-d0b5e4122711) \"\""
+                            .to_string()
+                    ),
+                    new: Some(
+                        "On a Friday (Radiohead, a74b1b7f-71a5-4011-9441-d0b5e4122711) \"\""
+                            .to_string()
+                    ),
+                },
+            ]
+        );
+    }
+}
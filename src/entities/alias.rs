@@ -6,10 +6,12 @@ enum_mb_xml_optional!(
         var SearchHint = "Search hint",
         var ArtistName = "Artist name",
         var LegalName = "Legal name",
+        var ReleaseName = "Release name",
     }
 );
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Alias {
     pub(crate) alias_type: Option<AliasType>,
     // TODO: I think SearchHint does not have a sort_name, at least according to the MB web
@@ -43,6 +45,24 @@ impl FromXml for Alias {
     }
 }
 
+#[cfg(feature = "streaming")]
+impl Alias {
+    pub(crate) fn from_node(node: &crate::streaming::Node) -> Result<Self, crate::Error> {
+        use crate::streaming::parse;
+
+        Ok(Alias {
+            alias_type: parse(node.attribute("type"))?,
+            sort_name: node.attribute("sort-name").map(String::from),
+            name: node.text.clone(),
+            locale: match node.attribute("locale") {
+                Some(l) => Some(Language::from_639_1(l)?),
+                None => None,
+            },
+            primary: node.attribute("primary") == Some("primary"),
+        })
+    }
+}
+
 impl Alias {
     pub fn alias_type(&self) -> Option<AliasType> {
         self.alias_type
@@ -0,0 +1,207 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::client::Client;
+use crate::entities::{
+    Area, Artist, ArtistOptions, Event, EventOptions, Label, LabelOptions, Mbid, Place,
+    PlaceOptions, Recording, RecordingOptions, Release, ReleaseGroup, ReleaseGroupOptions,
+    ReleaseOptions, Series, SeriesOptions, Work, WorkOptions,
+};
+use crate::error::{Error, ErrorKind};
+
+/// The types of entities which can be fetched from MusicBrainz.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum EntityKind {
+    Area,
+    Artist,
+    Event,
+    Label,
+    Place,
+    Recording,
+    Release,
+    ReleaseGroup,
+    Series,
+    Work,
+}
+
+impl EntityKind {
+    /// All entity kinds.
+    pub fn all() -> &'static [EntityKind] {
+        &[
+            EntityKind::Area,
+            EntityKind::Artist,
+            EntityKind::Event,
+            EntityKind::Label,
+            EntityKind::Place,
+            EntityKind::Recording,
+            EntityKind::Release,
+            EntityKind::ReleaseGroup,
+            EntityKind::Series,
+            EntityKind::Work,
+        ]
+    }
+
+    /// Name of the entity type as used in API paths and links to the
+    /// website, e.g. `release-group`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            EntityKind::Area => "area",
+            EntityKind::Artist => "artist",
+            EntityKind::Event => "event",
+            EntityKind::Label => "label",
+            EntityKind::Place => "place",
+            EntityKind::Recording => "recording",
+            EntityKind::Release => "release",
+            EntityKind::ReleaseGroup => "release-group",
+            EntityKind::Series => "series",
+            EntityKind::Work => "work",
+        }
+    }
+
+    /// Link to the entity of this kind with the MBID `mbid` on the
+    /// MusicBrainz website, e.g. `https://musicbrainz.org/release-group/<mbid>`.
+    pub fn permalink(&self, mbid: &Mbid) -> String {
+        format!("https://musicbrainz.org/{}/{}", self.name(), mbid)
+    }
+}
+
+impl Display for EntityKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Parses the name as returned by `EntityKind::name`.
+impl FromStr for EntityKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EntityKind::all()
+            .iter()
+            .find(|k| k.name() == s)
+            .cloned()
+            .ok_or_else(|| {
+                Error::new(
+                    format!("Unknown entity type: '{}'", s),
+                    ErrorKind::UsageError,
+                )
+            })
+    }
+}
+
+/// An entity of any type, as returned by `Client::get_dynamic`.
+#[derive(Clone, Debug)]
+pub enum AnyEntity {
+    Area(Area),
+    Artist(Artist),
+    Event(Event),
+    Label(Label),
+    Place(Place),
+    Recording(Recording),
+    Release(Release),
+    ReleaseGroup(ReleaseGroup),
+    Series(Series),
+    Work(Work),
+}
+
+impl AnyEntity {
+    /// The type of the contained entity.
+    pub fn kind(&self) -> EntityKind {
+        match *self {
+            AnyEntity::Area(_) => EntityKind::Area,
+            AnyEntity::Artist(_) => EntityKind::Artist,
+            AnyEntity::Event(_) => EntityKind::Event,
+            AnyEntity::Label(_) => EntityKind::Label,
+            AnyEntity::Place(_) => EntityKind::Place,
+            AnyEntity::Recording(_) => EntityKind::Recording,
+            AnyEntity::Release(_) => EntityKind::Release,
+            AnyEntity::ReleaseGroup(_) => EntityKind::ReleaseGroup,
+            AnyEntity::Series(_) => EntityKind::Series,
+            AnyEntity::Work(_) => EntityKind::Work,
+        }
+    }
+
+    /// MBID of the contained entity.
+    pub fn mbid(&self) -> &Mbid {
+        match *self {
+            AnyEntity::Area(ref e) => e.mbid(),
+            AnyEntity::Artist(ref e) => e.mbid(),
+            AnyEntity::Event(ref e) => e.mbid(),
+            AnyEntity::Label(ref e) => e.mbid(),
+            AnyEntity::Place(ref e) => e.mbid(),
+            AnyEntity::Recording(ref e) => e.mbid(),
+            AnyEntity::Release(ref e) => e.mbid(),
+            AnyEntity::ReleaseGroup(ref e) => e.mbid(),
+            AnyEntity::Series(ref e) => e.mbid(),
+            AnyEntity::Work(ref e) => e.mbid(),
+        }
+    }
+
+    /// Link to the contained entity on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        self.kind().permalink(self.mbid())
+    }
+}
+
+/// Fetch an entity of the given kind with the minimal options.
+pub(crate) fn get_dynamic(
+    client: &mut Client,
+    kind: EntityKind,
+    mbid: &Mbid,
+) -> Result<AnyEntity, Error> {
+    Ok(match kind {
+        EntityKind::Area => AnyEntity::Area(client.get_by_mbid(mbid, ())?),
+        EntityKind::Artist => {
+            AnyEntity::Artist(client.get_by_mbid(mbid, ArtistOptions::minimal())?)
+        }
+        EntityKind::Event => AnyEntity::Event(client.get_by_mbid(mbid, EventOptions::minimal())?),
+        EntityKind::Label => AnyEntity::Label(client.get_by_mbid(mbid, LabelOptions::minimal())?),
+        EntityKind::Place => AnyEntity::Place(client.get_by_mbid(mbid, PlaceOptions::minimal())?),
+        EntityKind::Recording => {
+            AnyEntity::Recording(client.get_by_mbid(mbid, RecordingOptions::minimal())?)
+        }
+        EntityKind::Release => {
+            AnyEntity::Release(client.get_by_mbid(mbid, ReleaseOptions::minimal())?)
+        }
+        EntityKind::ReleaseGroup => {
+            AnyEntity::ReleaseGroup(client.get_by_mbid(mbid, ReleaseGroupOptions::minimal())?)
+        }
+        EntityKind::Series => {
+            AnyEntity::Series(client.get_by_mbid(mbid, SeriesOptions::minimal())?)
+        }
+        EntityKind::Work => AnyEntity::Work(client.get_by_mbid(mbid, WorkOptions::minimal())?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_names() {
+        for kind in EntityKind::all() {
+            assert_eq!(&kind.name().parse::<EntityKind>().unwrap(), kind);
+        }
+        assert!("instrument".parse::<EntityKind>().unwrap_err().is_usage_error());
+    }
+
+    #[test]
+    fn permalinks() {
+        let mbid: Mbid = "b1392450-e666-3926-a536-22c65f834433".parse().unwrap();
+        assert_eq!(
+            EntityKind::ReleaseGroup.permalink(&mbid),
+            "https://musicbrainz.org/release-group/b1392450-e666-3926-a536-22c65f834433"
+                .to_string()
+        );
+        assert_eq!(
+            mbid.permalink(EntityKind::Artist),
+            "https://musicbrainz.org/artist/b1392450-e666-3926-a536-22c65f834433".to_string()
+        );
+
+        for kind in EntityKind::all() {
+            let (name, parsed) = crate::entities::parse_mb_url(&kind.permalink(&mbid)).unwrap();
+            assert_eq!(name.parse::<EntityKind>().unwrap(), *kind);
+            assert_eq!(parsed, mbid);
+        }
+    }
+}
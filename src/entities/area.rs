@@ -1,9 +1,11 @@
-use xpath_reader::{FromXml, Error, Reader};
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
 
-use crate::entities::{Mbid, Resource};
+use crate::entities::{write_summary, EntityKind, Mbid, Resource};
 use crate::client::Request;
 
-enum_mb_xml! {
+use std::fmt;
+
+enum_mb_xml_optional! {
     /// Specifies what a specific `Area` instance actually is.
     pub enum AreaType {
         /// Areas included (or previously included) in ISO 3166-1.
@@ -31,6 +33,11 @@ enum_mb_xml! {
 
         /// Islands and atolls which don't form subdivisions of their own.
         var Island = "Island",
+
+        /// Military bases which are not part of a city or municipality.
+        var MilitaryBase = "Military base",
+
+        unknown Unknown,
     }
 }
 
@@ -40,16 +47,21 @@ enum_mb_xml! {
 /// This is one of the *core entities* of MusicBrainz.
 ///
 /// [MusicBrainz documentation](https://musicbrainz.org/doc/Area).
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Area {
     response: AreaResponse,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AreaResponse {
     mbid: Mbid,
     name: String,
     sort_name: String,
-    area_type: AreaType,
+    area_type: Option<AreaType>,
+    area_type_id: Option<Mbid>,
     iso_3166: Option<String>,
 }
 
@@ -59,6 +71,11 @@ impl Area {
         &self.response.mbid
     }
 
+    /// Link to the area on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Area.permalink(self.mbid())
+    }
+
     /// The name of the area.
     pub fn name(&self) -> &String {
         &self.response.name
@@ -70,27 +87,57 @@ impl Area {
         &self.response.sort_name
     }
 
-    /// Type of the area, gives more information about
-    pub fn area_type(&self) -> AreaType {
+    /// Type of the area, gives more information about what kind of region
+    /// or settlement it is. Not every area has a type.
+    pub fn area_type(&self) -> Option<AreaType> {
         self.response.area_type.clone()
     }
 
+    /// MBID of the area type, which unlike its name is never translated.
+    pub fn area_type_id(&self) -> Option<&Mbid> {
+        self.response.area_type_id.as_ref()
+    }
+
     /// ISO 3166 code, assigned to countries and subdivisions.
     pub fn iso_3166(&self) -> Option<&String> {
         self.response.iso_3166.as_ref()
     }
 }
 
-impl FromXml for AreaResponse {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<AreaResponse, Error> {
-        Ok(AreaResponse {
-            mbid: reader.read(".//mb:area/@id")?,
-            name: reader.read(".//mb:area/mb:name/text()")?,
-            sort_name: reader.read(".//mb:area/mb:sort-name/text()")?,
-            area_type: reader.read(".//mb:area/@type")?,
-            iso_3166: reader
-                .read(".//mb:area/mb:iso-3166-1-code-list/mb:iso-3166-1-code/text()")?,
-        })
+impl fmt::Debug for Area {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Area")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("sort_name", self.sort_name())
+            .field("area_type", &self.area_type())
+            .field("iso_3166", &self.iso_3166())
+            .finish()
+    }
+}
+
+impl fmt::Display for Area {
+    /// Formats the area as e.g. `Japan (Country, JP)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_summary(
+            f,
+            self.name(),
+            &[
+                self.area_type().map(|t| t.to_string()),
+                self.iso_3166().cloned(),
+            ],
+        )
+    }
+}
+
+from_xml_paths! {
+    AreaResponse at ".//mb:area/" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        sort_name: "mb:sort-name/text()",
+        area_type: "@type",
+        area_type_id: "@type-id",
+        iso_3166: "mb:iso-3166-1-code-list/mb:iso-3166-1-code/text()",
     }
 }
 
@@ -107,6 +154,10 @@ impl Resource for Area {
         }
     }
 
+    #[cfg(feature = "diagnostics")]
+    const PARSED_ELEMENTS: Option<&'static [&'static str]> =
+        Some(&["name", "sort-name", "iso-3166-1-code-list"]);
+
     fn from_response(response: Self::Response, _: Self::Options) -> Self {
         Area { response }
     }
@@ -115,6 +166,7 @@ impl Resource for Area {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_utils::parse_typed;
     use std::str::FromStr;
 
     #[test]
@@ -125,7 +177,11 @@ mod tests {
         assert_eq!(area.mbid(), &mbid);
         assert_eq!(area.name(), &"Honolulu".to_string());
         assert_eq!(area.sort_name(), &"Honolulu".to_string());
-        assert_eq!(area.area_type(), AreaType::City);
+        assert_eq!(area.area_type(), Some(AreaType::City));
+        assert_eq!(
+            area.area_type_id(),
+            Some(&Mbid::from_str("6fd8f29a-3d0a-32fc-980d-ea697b69da78").unwrap())
+        );
         assert_eq!(area.iso_3166(), None);
     }
 
@@ -137,7 +193,30 @@ mod tests {
         assert_eq!(area.mbid(), &mbid);
         assert_eq!(area.name(), &"Japan".to_string());
         assert_eq!(area.sort_name(), &"Japan".to_string());
-        assert_eq!(area.area_type(), AreaType::Country);
+        assert_eq!(area.area_type(), Some(AreaType::Country));
         assert_eq!(area.iso_3166(), Some(&"JP".to_string()));
     }
+
+    #[test]
+    fn area_types() {
+        let area = |area_type| {
+            let children = "<name>Area</name><sort-name>Area</sort-name>";
+            parse_typed::<Area>(area_type, children, ())
+        };
+
+        let untyped = area(None);
+        assert_eq!(untyped.area_type(), None);
+        assert_eq!(untyped.to_string(), "Area");
+
+        let base = area(Some("Military base"));
+        assert_eq!(base.area_type(), Some(AreaType::MilitaryBase));
+        assert_eq!(base.to_string(), "Area (Military base)");
+
+        let unknown = area(Some("Space station"));
+        assert_eq!(
+            unknown.area_type(),
+            Some(AreaType::Unknown("Space station".to_string()))
+        );
+        assert_eq!(unknown.to_string(), "Area (Space station)");
+    }
 }
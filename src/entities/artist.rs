@@ -1,10 +1,15 @@
-use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
 
-use crate::entities::{Mbid, ResourceOld, OnRequest, Alias, Resource};
+use crate::entities::{write_summary, Mbid, OnRequest, Alias, Resource, Genre, Rating,
+                      Relation, Tag};
+use crate::entities::{include, join_includes, EntityKind, Include};
 use crate::entities::date::PartialDate;
+use crate::entities::helper::read_ended;
 use crate::entities::refs::AreaRef;
 use crate::client::Request;
 
+use std::fmt;
+
 enum_mb_xml_optional! {
     /// Specification of the gender of an artist.
     pub enum Gender {
@@ -23,6 +28,7 @@ enum_mb_xml_optional! {
         var Choir = "Choir",
         var Character = "Character",
         var Other = "Other",
+        unknown Unknown,
     }
 }
 
@@ -32,9 +38,12 @@ enum_mb_xml_optional! {
 ///
 /// Additional information can be found in the [MusicBrainz
 /// docs](https://musicbrainz.org/doc/Artist).
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Artist {
     response: ArtistResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
     options: ArtistOptions,
 }
 
@@ -42,9 +51,14 @@ pub struct Artist {
 pub struct ArtistOptions {
     pub annotation: bool,
     pub aliases: bool,
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+    pub url_rels: bool,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArtistResponse {
     mbid: Mbid,
     name: String,
@@ -53,20 +67,55 @@ pub struct ArtistResponse {
     annotation: Option<String>,
     disambiguation: Option<String>,
     artist_type: Option<ArtistType>,
+    artist_type_id: Option<Mbid>,
     gender: Option<Gender>,
+    gender_id: Option<Mbid>,
     area: Option<AreaRef>,
     begin_date: Option<PartialDate>,
     end_date: Option<PartialDate>,
+    ended: Option<bool>,
     ipi_code: Option<String>,
     isni_code: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+    url_rels: Vec<Relation>,
 }
 
 impl Artist {
+    /// MBID of `Various Artists`, credited on compilations of many artists.
+    pub const VARIOUS_ARTISTS_MBID: &'static str = "89ad4ac3-39f7-470e-963a-56509c546377";
+
+    /// MBID of `[unknown]`, credited if the artist isn't known.
+    pub const UNKNOWN_MBID: &'static str = "125ec42a-7229-4250-afc5-e057484327fe";
+
+    /// MBID of `[anonymous]`, credited if the artist intentionally isn't
+    /// named.
+    pub const ANONYMOUS_MBID: &'static str = "f731ccc4-e22a-43af-a747-64213329e088";
+
+    /// MBID of `[data]`, credited on data tracks.
+    pub const DATA_MBID: &'static str = "33cf029c-63b0-41a0-9855-be2a3665fb3b";
+
+    /// MBID of `[no artist]`, credited on tracks without an artist, e.g.
+    /// silence.
+    pub const NO_ARTIST_MBID: &'static str = "eec63d3c-3b81-4ad4-b1e4-7c147d4d2b61";
+
+    /// MBID of `[traditional]`, credited as the writer of traditional works.
+    pub const TRADITIONAL_MBID: &'static str = "9be7f096-97ec-4615-8957-8d40b5dcbc41";
+
+    /// MBID of `[dialogue]`, credited on spoken tracks of soundtracks.
+    pub const DIALOGUE_MBID: &'static str = "314e1c25-dde7-4e4d-b2f4-0a7b9f7c56dc";
+
     /// MBID of the artist in the MusicBrainz database.
     pub fn mbid(&self) -> &Mbid {
         &self.response.mbid
     }
 
+    /// Link to the artist on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Artist.permalink(self.mbid())
+    }
+
     /// The official name of the artist.
     pub fn name(&self) -> &String {
         &self.response.name
@@ -86,11 +135,7 @@ impl Artist {
     /// spellings, common misspellings, versions in different scripts and
     /// other variations of the `Artist` name.
     pub fn aliases(&self) -> OnRequest<&[Alias]> {
-        if self.options.aliases {
-            OnRequest::Some(self.response.aliases.as_ref())
-        } else {
-            OnRequest::NotRequested
-        }
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
     }
 
     /// Any additional free form annotation for this `Artist`.
@@ -112,11 +157,21 @@ impl Artist {
         self.response.artist_type.clone()
     }
 
+    /// MBID of the artist type, which unlike its name is never translated.
+    pub fn artist_type_id(&self) -> Option<&Mbid> {
+        self.response.artist_type_id.as_ref()
+    }
+
     /// If the `Artist` is a single person this indicates their gender.
     pub fn gender(&self) -> Option<Gender> {
         self.response.gender.clone()
     }
 
+    /// MBID of the gender of the `Artist`.
+    pub fn gender_id(&self) -> Option<&Mbid> {
+        self.response.gender_id.as_ref()
+    }
+
     /// The area an `Artist` is primarily identified with. Often, but not
     /// always, birth/formation country of the artist/group.
     pub fn area(&self) -> Option<&AreaRef> {
@@ -137,6 +192,15 @@ impl Artist {
         self.response.end_date.as_ref()
     }
 
+    /// Whether the person died or the group was dissolved, even if the
+    /// `end_date` isn't known.
+    ///
+    /// `None` if the response doesn't say, the API only includes this for
+    /// artists which ended.
+    pub fn ended(&self) -> Option<bool> {
+        self.response.ended
+    }
+
     /// [IPI Code](https://wiki.musicbrainz.org/IPI) of the `Artist`.
     pub fn ipi_code(&self) -> Option<&String> {
         self.response.ipi_code.as_ref()
@@ -146,6 +210,66 @@ impl Artist {
     pub fn isni_code(&self) -> Option<&String> {
         self.response.isni_code.as_ref()
     }
+
+    /// The tags users attached to the `Artist`.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the `Artist`.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the `Artist`.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
+
+    /// The relationships to URLs, e.g. the official homepage or the
+    /// Wikidata entry of the `Artist`.
+    pub fn url_rels(&self) -> OnRequest<&[Relation]> {
+        OnRequest::from_value(self.response.url_rels.as_ref(), self.options.url_rels)
+    }
+}
+
+impl fmt::Debug for Artist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Artist")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("sort_name", self.sort_name())
+            .field("disambiguation", &self.disambiguation())
+            .field("artist_type", &self.artist_type())
+            .field("gender", &self.gender())
+            .field("area", &self.area())
+            .field("begin_date", &self.begin_date())
+            .field("end_date", &self.end_date())
+            .field("ended", &self.ended())
+            .field("ipi_code", &self.ipi_code())
+            .field("isni_code", &self.isni_code())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .field("url_rels", &self.url_rels())
+            .finish()
+    }
+}
+
+impl fmt::Display for Artist {
+    /// Formats the artist as e.g. `Radiohead (group, GB)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_summary(
+            f,
+            self.name(),
+            &[
+                self.artist_type().map(|t| t.to_string().to_lowercase()),
+                self.area().and_then(|a| a.iso_3166.as_ref().map(|c| c.to_string())),
+            ],
+        )
+    }
 }
 
 impl ArtistOptions {
@@ -153,6 +277,10 @@ impl ArtistOptions {
         ArtistOptions {
             annotation: true,
             aliases: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+            url_rels: true,
         }
     }
 
@@ -160,27 +288,100 @@ impl ArtistOptions {
         ArtistOptions {
             annotation: false,
             aliases: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+            url_rels: false,
+        }
+    }
+
+    /// Request what is usually shown on an artist page: the aliases, tags,
+    /// genres and links to other sites.
+    pub fn for_display() -> Self {
+        ArtistOptions {
+            aliases: true,
+            tags: true,
+            genres: true,
+            url_rels: true,
+            ..ArtistOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        ArtistOptions {
+            annotation: true,
+            genres: true,
+            ..ArtistOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for artists.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = ArtistOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                Include::UrlRels => options.url_rels = true,
+                _ => return Err(include::unsupported(EntityKind::Artist, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
         }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        if self.url_rels {
+            includes.push(Include::UrlRels);
+        }
+        includes
     }
 }
 
-impl FromXml for ArtistResponse {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(ArtistResponse {
-            aliases: reader.read(".//mb:artist/mb:alias-list/mb:alias")?,
-            annotation: reader.read(".//mb:artist/mb:annotation/text()")?,
-            area: reader.read(".//mb:artist/mb:area")?,
-            artist_type: reader.read(".//mb:artist/@type")?,
-            begin_date: reader.read(".//mb:artist/mb:life-span/mb:begin/text()")?,
-            disambiguation: reader.read(".//mb:artist/mb:disambiguation/text()")?,
-            end_date: reader.read(".//mb:artist/mb:life-span/mb:end/text()")?,
-            gender: reader.read(".//mb:artist/mb:gender/text()")?,
-            ipi_code: reader.read(".//mb:artist/mb:ipi/text()")?,
-            isni_code: reader.read(".//mb:artist/mb:isni-list/mb:isni/text()")?,
-            mbid: reader.read(".//mb:artist/@id")?,
-            name: reader.read(".//mb:artist/mb:name/text()")?,
-            sort_name: reader.read(".//mb:artist/mb:sort-name/text()")?,
-        })
+from_xml_paths! {
+    ArtistResponse {
+        aliases: ".//mb:artist/mb:alias-list/mb:alias",
+        annotation: ".//mb:artist/mb:annotation/text()",
+        area: ".//mb:artist/mb:area",
+        artist_type: ".//mb:artist/@type",
+        artist_type_id: ".//mb:artist/@type-id",
+        begin_date: ".//mb:artist/mb:life-span/mb:begin/text()",
+        disambiguation: ".//mb:artist/mb:disambiguation/text()",
+        end_date: ".//mb:artist/mb:life-span/mb:end/text()",
+        ended: ".//mb:artist/mb:life-span/mb:ended/text()" => read_ended,
+        gender: ".//mb:artist/mb:gender/text()",
+        gender_id: ".//mb:artist/mb:gender/@id",
+        ipi_code: ".//mb:artist/mb:ipi/text()",
+        isni_code: ".//mb:artist/mb:isni-list/mb:isni/text()",
+        mbid: ".//mb:artist/@id",
+        name: ".//mb:artist/mb:name/text()",
+        sort_name: ".//mb:artist/mb:sort-name/text()",
+        tags: "//mb:metadata/mb:artist/mb:tag-list/mb:tag",
+        genres: "//mb:metadata/mb:artist/mb:genre-list/mb:genre",
+        rating: "//mb:metadata/mb:artist/mb:rating",
+        url_rels: "//mb:metadata/mb:artist/mb:relation-list[@target-type='url']/mb:relation",
     }
 }
 
@@ -190,37 +391,41 @@ impl Resource for Artist {
     const NAME: &'static str = "artist";
 
     fn request(options: &Self::Options) -> Request {
-        let mut includes = Vec::new();
-
-        if options.aliases {
-            includes.push("aliases");
-        }
-        if options.annotation {
-            includes.push("annotation");
-        }
-
         Request {
             name: "artist".into(),
-            include: includes.join("+"),
+            include: join_includes(&options.includes()),
         }
     }
 
+    #[cfg(feature = "diagnostics")]
+    const PARSED_ELEMENTS: Option<&'static [&'static str]> = Some(&[
+        "name",
+        "sort-name",
+        "alias-list",
+        "annotation",
+        "disambiguation",
+        "gender",
+        "area",
+        "life-span",
+        "ipi",
+        "isni-list",
+        "tag-list",
+        "genre-list",
+        "rating",
+        "relation-list",
+    ]);
+
     fn from_response(response: Self::Response, options: Self::Options) -> Self {
         Artist { response, options }
     }
 }
 
-impl ResourceOld for ArtistResponse {
-    const NAME: &'static str = "artist";
-    const INCL: &'static str = "aliases";
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::str::FromStr;
     use std::iter::FromIterator;
-    use crate::entities::{AliasType, Language};
+    use crate::entities::{AliasType, Country, Language, RelationTarget};
 
     #[test]
     fn artist_read_xml1() {
@@ -238,6 +443,7 @@ mod tests {
             Some(&PartialDate::from_str("2014-03").unwrap())
         );
         assert_eq!(artist.end_date(), None);
+        assert_eq!(artist.ended(), None);
 
         let area = artist.area().unwrap();
         assert_eq!(
@@ -246,7 +452,7 @@ mod tests {
         );
         assert_eq!(area.name, "Japan".to_string());
         assert_eq!(area.sort_name, "Japan".to_string());
-        assert_eq!(area.iso_3166, Some("JP".to_string()));
+        assert_eq!(area.iso_3166, Some(Country::from_code("JP").unwrap()));
 
         assert_eq!(artist.artist_type(), Some(ArtistType::Group));
         assert_eq!(artist.gender(), None);
@@ -257,7 +463,11 @@ mod tests {
     #[test]
     fn artist_read_xml2() {
         let mbid = Mbid::from_str("650e7db6-b795-4eb5-a702-5ea2fc46c848").unwrap();
-        let options = ArtistOptions::everything();
+        let options = ArtistOptions {
+            annotation: true,
+            aliases: true,
+            ..ArtistOptions::minimal()
+        };
         let artist: Artist = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
 
         assert_eq!(artist.mbid(), &mbid);
@@ -305,12 +515,113 @@ mod tests {
         );
         assert_eq!(area.name, "United States".to_string());
         assert_eq!(area.sort_name, "United States".to_string());
-        assert_eq!(area.iso_3166, Some("US".to_string()));
+        assert_eq!(area.iso_3166, Some(Country::from_code("US").unwrap()));
 
         assert_eq!(artist.artist_type(), Some(ArtistType::Person));
         assert_eq!(artist.gender(), Some(Gender::Female));
+        assert_eq!(
+            artist.artist_type_id(),
+            Some(&Mbid::from_str("b6e035f4-3ce9-331c-97df-83397230b0df").unwrap())
+        );
+        assert_eq!(
+            artist.gender_id(),
+            Some(&Mbid::from_str("93452b5a-a947-30c8-934f-6a4056b151c2").unwrap())
+        );
         assert_eq!(artist.ipi_code(), Some(&"00519338344".to_string()));
         assert_eq!(artist.isni_code(), Some(&"0000000120254559".to_string()));
     }
 
+    #[test]
+    fn tags_genres_rating() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><artist type="Group" id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name><area id="8a754a16-0027-3a29-b6d7-2b40ea0481ed"><name>United Kingdom</name><sort-name>United Kingdom</sort-name><tag-list><tag count="3"><name>europe</name></tag></tag-list></area><tag-list><tag count="22"><name>alternative rock</name></tag><tag count="-1"><name>britpop</name></tag></tag-list><genre-list><genre count="22" id="ceeaa283-5d7b-4202-8d1d-e25d116b2a18"><name>alternative rock</name></genre></genre-list><rating votes-count="52">4.6</rating></artist></metadata>"#;
+        let options = ArtistOptions {
+            tags: true,
+            genres: true,
+            ratings: true,
+            ..ArtistOptions::minimal()
+        };
+        let artist: Artist = crate::util::test_utils::parse_entity(xml, options).unwrap();
+
+        assert_eq!(
+            artist.tags().unwrap(),
+            &[
+                Tag {
+                    name: "alternative rock".to_string(),
+                    count: 22,
+                },
+                Tag {
+                    name: "britpop".to_string(),
+                    count: -1,
+                },
+            ]
+        );
+        assert_eq!(artist.genres().unwrap()[0].name, "alternative rock".to_string());
+        assert_eq!(
+            artist.rating(),
+            OnRequest::Some(&Rating {
+                value: Some(4.6),
+                votes: 52,
+            })
+        );
+
+        let artist: Artist =
+            crate::util::test_utils::parse_entity(xml, ArtistOptions::minimal()).unwrap();
+        assert_eq!(artist.tags(), OnRequest::NotRequested);
+    }
+
+    #[test]
+    fn url_rels() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><artist type="Group" id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name><relation-list target-type="url"><relation type-id="fe33d22f-c3b0-4d68-bd53-a856badf2b15" type="official homepage"><target id="1b5e3bbf-be6c-4d6c-9e3a-4b8a3e2d6d70">http://www.radiohead.com/</target></relation><relation type-id="689870a4-a1e4-4912-b17f-7b2664215698" type="wikidata"><target id="f3a8ea5f-d3b8-4a46-9bb8-65d0b6dc6ba5">https://www.wikidata.org/wiki/Q44190</target></relation></relation-list></artist></metadata>"#;
+        let options = ArtistOptions::for_display();
+        assert!(options.includes().contains(&Include::UrlRels));
+        let artist: Artist = crate::util::test_utils::parse_entity(xml, options).unwrap();
+
+        let urls: Vec<_> = artist
+            .url_rels()
+            .unwrap()
+            .iter()
+            .map(|r| (r.relation_type.as_str(), r.target.clone()))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                (
+                    "official homepage",
+                    RelationTarget::Url("http://www.radiohead.com/".to_string())
+                ),
+                (
+                    "wikidata",
+                    RelationTarget::Url("https://www.wikidata.org/wiki/Q44190".to_string())
+                ),
+            ]
+        );
+
+        let artist: Artist =
+            crate::util::test_utils::parse_entity(xml, ArtistOptions::minimal()).unwrap();
+        assert_eq!(artist.url_rels(), OnRequest::NotRequested);
+    }
+
+    #[test]
+    fn ended() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><artist type="Group" id="0383dadf-2a4e-4d10-a46a-e9e041da8eb3"><name>Queen</name><sort-name>Queen</sort-name><life-span><begin>1970</begin><ended>true</ended></life-span></artist></metadata>"#;
+        let artist: Artist =
+            crate::util::test_utils::parse_entity(xml, ArtistOptions::minimal()).unwrap();
+
+        assert_eq!(artist.end_date(), None);
+        assert_eq!(artist.ended(), Some(true));
+    }
+
+    #[test]
+    fn display() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><artist type="Group" id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name><area id="8a754a16-0027-3a29-b6d7-2b40ea0481ed"><name>United Kingdom</name><sort-name>United Kingdom</sort-name><iso-3166-1-code-list><iso-3166-1-code>GB</iso-3166-1-code></iso-3166-1-code-list></area></artist></metadata>"#;
+        let artist: Artist =
+            crate::util::test_utils::parse_entity(xml, ArtistOptions::minimal()).unwrap();
+
+        assert_eq!(artist.to_string(), "Radiohead (group, GB)".to_string());
+
+        let debug = format!("{:?}", artist);
+        assert!(debug.starts_with("Artist { mbid: "));
+        assert!(debug.contains("aliases: NotRequested"));
+        assert!(!debug.contains("options"));
+    }
 }
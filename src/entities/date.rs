@@ -2,6 +2,7 @@
 // TODO: validate input dates for validity
 // TODO: Write conversions to and from `chrono` date types for interoperability.
 use std;
+use std::cmp::{min, Ordering};
 use std::str::FromStr;
 use std::num::ParseIntError;
 use std::error::Error;
@@ -43,6 +44,24 @@ impl PartialDate {
         self.day
     }
 
+    /// Compare two dates up to the precision of the less precise one, so e.g.
+    /// `2020` is equal to `2020-05`.
+    ///
+    /// Only the leading known components are compared, a date with an unknown
+    /// year is equal to every other date.
+    pub fn cmp_coarse(&self, other: &PartialDate) -> Ordering {
+        let components = |d: &PartialDate| -> Vec<u16> {
+            vec![d.year, d.month.map(u16::from), d.day.map(u16::from)]
+                .into_iter()
+                .take_while(Option::is_some)
+                .map(Option::unwrap)
+                .collect()
+        };
+        let (a, b) = (components(self), components(other));
+        let n = min(a.len(), b.len());
+        a[..n].cmp(&b[..n])
+    }
+
     /// If this `PartialDate` is fully specified, `Some(FullDate)` will be
     /// returned,
     /// otherwise `None` will be returned.
@@ -135,23 +154,23 @@ impl FromStr for PartialDate {
 
 impl Display for PartialDate {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        // TODO optimize later (no allocations)
-        let year = self
-            .year
-            .map(|n| format!("{:04}", n))
-            .unwrap_or_else(|| "????".to_string());
-        let month = self
-            .month
-            .map(|n| format!("{:02}", n))
-            .unwrap_or_else(|| "??".to_string());
-        let day = self
-            .day
-            .map(|n| format!("{:02}", n))
-            .unwrap_or_else(|| "??".to_string());
-        write!(f, "{}-{}-{}", year, month, day)
+        match self.year {
+            Some(n) => write!(f, "{:04}", n)?,
+            None => f.write_str("????")?,
+        }
+        match self.month {
+            Some(n) => write!(f, "-{:02}", n)?,
+            None => f.write_str("-??")?,
+        }
+        match self.day {
+            Some(n) => write!(f, "-{:02}", n),
+            None => f.write_str("-??"),
+        }
     }
 }
 
+serialize_as_string!(PartialDate);
+
 impl FromXmlOptional for PartialDate {
     fn from_xml_optional<'d>(
         reader: &'d Reader<'d>,
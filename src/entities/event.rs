@@ -1,8 +1,12 @@
-use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
 
-use crate::entities::{Mbid, ResourceOld};
+use crate::client::Request;
+use crate::entities::{include, join_includes, EntityKind, Genre, Include, Mbid, OnRequest,
+                      Rating, Resource, Tag};
 use crate::entities::date::PartialDate;
 
+use std::fmt;
+
 enum_mb_xml_optional! {
     pub enum EventType {
         var Concert = "Concert",
@@ -10,6 +14,9 @@ enum_mb_xml_optional! {
         var LaunchEvent = "Launch event",
         var ConventionExpo = "Convention/Expo",
         var MasterclassClinic = "Masterclass/Clinic",
+        var StagePerformance = "Stage performance",
+        var AwardCeremony = "Award ceremony",
+        unknown Unknown,
     }
 }
 
@@ -17,89 +24,337 @@ enum_mb_xml_optional! {
 ///
 /// Additional information can be found in the [MusicBrainz
 /// docs](https://musicbrainz.org/doc/Event)
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Event {
+    response: EventResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: EventOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct EventOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EventResponse {
+    mbid: Mbid,
+    name: String,
+    aliases: Vec<String>,
+    event_type: Option<EventType>,
+    setlist: Option<String>,
+    begin_date: PartialDate,
+    end_date: Option<PartialDate>,
+    disambiguation: Option<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+impl Event {
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the event on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Event.permalink(self.mbid())
+    }
 
     /// The official name of the event or a descriptive name if the event
     /// doesn't have an official name.
-    pub name: String,
+    pub fn name(&self) -> &String {
+        &self.response.name
+    }
 
     /// Aternative event names.
-    pub aliases: Vec<String>,
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
 
     /// Describes what type of event this is exactly.
-    pub event_type: Option<EventType>,
+    pub fn event_type(&self) -> Option<EventType> {
+        self.response.event_type.clone()
+    }
 
     /// List of songs played at the event.
     ///
     /// This is provided in an extensive text format, for which parsing is not
     /// yet implemented.
-    pub setlist: Option<String>,
+    pub fn setlist(&self) -> Option<&String> {
+        self.response.setlist.as_ref()
+    }
 
     /// Begin date of the event.
-    pub begin_date: PartialDate,
+    pub fn begin_date(&self) -> &PartialDate {
+        &self.response.begin_date
+    }
 
     /// End date of the event.
-    pub end_date: Option<PartialDate>,
+    pub fn end_date(&self) -> Option<&PartialDate> {
+        self.response.end_date.as_ref()
+    }
 
     /// Additional disambiguation if there are multiple `Event`s with the same
     /// name.
-    pub disambiguation: Option<String>,
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
 
     /// Any additional free form annotation for this `Event`.
-    pub annotation: Option<String>,
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
+
+    /// The tags users attached to the event.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the event.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the event.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
 }
 
-impl ResourceOld for Event {
-    const NAME: &'static str = "event";
-    const INCL: &'static str = "aliases+annotation";
+impl fmt::Debug for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Event")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("event_type", &self.event_type())
+            .field("begin_date", self.begin_date())
+            .field("end_date", &self.end_date())
+            .field("disambiguation", &self.disambiguation())
+            .field("setlist", &self.setlist())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
+    }
+}
+
+impl EventOptions {
+    pub fn everything() -> Self {
+        EventOptions {
+            aliases: true,
+            annotation: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        EventOptions {
+            aliases: false,
+            annotation: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+        }
+    }
+
+    /// Request what is usually shown on an event page: the aliases, tags and
+    /// genres.
+    pub fn for_display() -> Self {
+        EventOptions {
+            aliases: true,
+            tags: true,
+            genres: true,
+            ..EventOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        EventOptions {
+            annotation: true,
+            genres: true,
+            ..EventOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for events.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = EventOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                _ => return Err(include::unsupported(EntityKind::Event, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        includes
+    }
 }
 
-impl FromXml for Event {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(Event {
-            mbid: reader.read(".//mb:event/@id")?,
-            name: reader.read(".//mb:event/mb:name")?,
-            aliases: reader.read(".//mb:event/mb:alias-list/mb:alias/text()")?,
-            event_type: reader.read(".//mb:event/@type")?,
-            setlist: reader.read(".//mb:event/mb:setlist")?,
-            begin_date: reader.read(".//mb:event/mb:life-span/mb:begin")?,
-            end_date: reader.read(".//mb:event/mb:life-span/mb:end")?,
-            disambiguation: reader.read(".//mb:event/mb:disambiguation")?,
-            annotation: reader.read(".//mb:event/mb:annotation/mb:text/text()")?,
-        })
+/// The aliases and the annotation, which were always requested before events
+/// had options.
+impl Default for EventOptions {
+    fn default() -> Self {
+        EventOptions {
+            aliases: true,
+            annotation: true,
+            ..EventOptions::minimal()
+        }
+    }
+}
+
+from_xml_paths! {
+    EventResponse at ".//mb:event/" {
+        mbid: "@id",
+        name: "mb:name",
+        aliases: "mb:alias-list/mb:alias/text()",
+        event_type: "@type",
+        setlist: "mb:setlist",
+        begin_date: "mb:life-span/mb:begin",
+        end_date: "mb:life-span/mb:end",
+        disambiguation: "mb:disambiguation",
+        annotation: "mb:annotation/mb:text/text()",
+        tags: "mb:tag-list/mb:tag",
+        genres: "mb:genre-list/mb:genre",
+        rating: "mb:rating",
+    }
+}
+
+impl Resource for Event {
+    type Options = EventOptions;
+    type Response = EventResponse;
+    const NAME: &'static str = "event";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "event".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Event { response, options }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_utils::parse_typed;
     use std::str::FromStr;
 
     #[test]
     fn read_1() {
         let mbid = Mbid::from_str("6e2ab7d5-f340-4c41-99a3-c901733402b4").unwrap();
-        let event: Event = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let event: Event =
+            crate::util::test_utils::fetch_entity(&mbid, EventOptions::default()).unwrap();
 
-        assert_eq!(event.mbid, mbid);
-        assert_eq!(event.name, "25. Wave-Gotik-Treffen".to_string());
-        assert_eq!(event.aliases, vec!["WGT 2016".to_string()]);
-        assert_eq!(event.event_type, Some(EventType::Festival));
-        assert_eq!(event.setlist, None);
-        assert_eq!(event.begin_date, "2016-05-13".parse().unwrap());
-        assert_eq!(event.end_date.unwrap(), "2016-05-16".parse().unwrap());
-        assert_eq!(event.disambiguation, None);
-        assert_eq!(event.annotation.unwrap().len(), 2233);
+        assert_eq!(event.mbid(), &mbid);
+        assert_eq!(event.name(), &"25. Wave-Gotik-Treffen".to_string());
+        assert_eq!(event.aliases().unwrap(), &["WGT 2016".to_string()]);
+        assert_eq!(event.event_type(), Some(EventType::Festival));
+        assert_eq!(event.setlist(), None);
+        assert_eq!(event.begin_date(), &PartialDate::from_str("2016-05-13").unwrap());
+        assert_eq!(
+            event.end_date(),
+            Some(&PartialDate::from_str("2016-05-16").unwrap())
+        );
+        assert_eq!(event.disambiguation(), None);
+        assert_eq!(event.annotation().unwrap().len(), 2233);
+        assert!(event.tags().into_option().is_none());
     }
 
     #[test]
     fn read_2() {
         let mbid = Mbid::from_str("9754f4dd-6fad-49b7-8f30-940c9af6b776").unwrap();
-        let event: Event = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let event: Event =
+            crate::util::test_utils::fetch_entity(&mbid, EventOptions::default()).unwrap();
+
+        assert_eq!(event.event_type(), Some(EventType::Concert));
+        assert_eq!(event.setlist().unwrap().len(), 225);
+        assert!(event.annotation().into_option().is_none());
+    }
+
+    #[test]
+    fn options() {
+        let options = EventOptions::from_includes(&[Include::Annotation, Include::Ratings]);
+        assert_eq!(
+            options.unwrap().includes(),
+            vec![Include::Annotation, Include::Ratings]
+        );
+        assert!(EventOptions::from_includes(&[Include::Isrcs]).is_err());
+        assert_eq!(
+            Event::request(&EventOptions::default()).include,
+            "aliases+annotation"
+        );
+        assert_eq!(
+            Event::request(&EventOptions::for_mirror()).include,
+            "annotation+genres"
+        );
+    }
+
+    #[test]
+    fn event_types() {
+        let ceremony = parse_typed::<Event>(
+            Some("Award ceremony"),
+            "<name>65th Annual Grammy Awards</name><life-span><begin>2023-02-05</begin><end>2023-02-05</end></life-span>",
+            EventOptions::minimal(),
+        );
+        assert_eq!(ceremony.name(), &"65th Annual Grammy Awards".to_string());
+        assert_eq!(ceremony.event_type(), Some(EventType::AwardCeremony));
+
+        let performance = parse_typed::<Event>(
+            Some("Stage performance"),
+            "<name>Hamilton</name><life-span><begin>2015-08-06</begin></life-span>",
+            EventOptions::minimal(),
+        );
+        assert_eq!(performance.event_type(), Some(EventType::StagePerformance));
+        assert_eq!(performance.end_date(), None);
 
-        assert_eq!(event.event_type, Some(EventType::Concert));
-        assert_eq!(event.setlist.unwrap().len(), 225);
+        assert_eq!(
+            "Hologram show".parse::<EventType>().unwrap(),
+            EventType::Unknown("Hologram show".to_string())
+        );
+        assert_eq!(EventType::AwardCeremony.to_string(), "Award ceremony");
     }
 }
@@ -1,6 +1,83 @@
-use std::time::Duration;
-use xpath_reader::Reader;
+/// The fallback variant of an enum defined by `enum_mb_xml` holding the
+/// value `s`, if it has one.
+macro_rules! enum_fallback
+{
+    ($enum:ident, $s:expr) => { None::<$enum> };
+    ($enum:ident, $s:expr, $fallback:ident) => { Some($enum::$fallback($s.to_string())) };
+}
+
+/// The definition of an enum defined by `enum_mb_xml`, which can only be
+/// `Copy` if it doesn't have a fallback variant holding the unknown value.
+macro_rules! enum_definition
+{
+    (
+        $(#[$attr:meta])* pub enum $enum:ident {
+            $( $(#[$attr2:meta])* $variant:ident, )+
+        }
+    )
+        =>
+    {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub enum $enum {
+            $(
+                $(#[$attr2])* $variant ,
+            )+
+        }
+    };
+    (
+        $(#[$attr:meta])* pub enum $enum:ident {
+            $( $(#[$attr2:meta])* $variant:ident, )+
+        }
+        unknown $fallback:ident
+    )
+        =>
+    {
+        $(#[$attr])*
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        pub enum $enum {
+            $(
+                $(#[$attr2])* $variant ,
+            )+
+            /// A value which is not known to this version of the crate, as
+            /// returned by the server.
+            $fallback(String),
+        }
+    };
+}
 
+/// Implement `Serialize` for a type by serializing its string form, e.g. for
+/// MBIDs, dates and the values of enums defined by `enum_mb_xml`.
+macro_rules! serialize_as_string
+{
+    ($type:ident) => {
+        #[cfg(feature = "serde")]
+        impl ::serde::Serialize for $type {
+            fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+    };
+}
+
+/// The variant following the optional `unknown` token is used for all values
+/// which aren't listed, instead of failing to parse them. It holds the value
+/// as returned by the server, which is also what it is displayed as. This
+/// should be used for lists of types which are extended by MusicBrainz from
+/// time to time, as otherwise a single entity with a new type breaks every
+/// request containing it.
+///
+/// ```ignore
+/// enum_mb_xml_optional! {
+///     pub enum PlaceType {
+///         var Studio = "Studio",
+///         var Venue = "Venue",
+///         unknown Unknown,
+///     }
+/// }
+/// ```
+///
 /// Note that the requirement of the `var` (variant) token is rather ugly but
 /// required,
 /// which is a limitation of the current Rust macro implementation.
@@ -19,16 +96,16 @@ macro_rules! enum_mb_xml
                 var $variant:ident = $str:expr
             ),+
             ,
+            $( unknown $fallback:ident, )?
         }
     )
         =>
     {
-        $(#[$attr])*
-        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-        pub enum $enum {
-            $(
-                $(#[$attr2])* $variant ,
-            )+
+        enum_definition! {
+            $(#[$attr])* pub enum $enum {
+                $( $(#[$attr2])* $variant, )+
+            }
+            $( unknown $fallback )?
         }
 
         impl FromXml for $enum {
@@ -38,7 +115,7 @@ macro_rules! enum_mb_xml
                     $(
                         $str => Ok($enum::$variant),
                     )+
-                    s => Err(
+                    s => enum_fallback!($enum, s $(, $fallback)?).ok_or_else(||
                         ::xpath_reader::Error::custom_msg(
                             format!("Unknown `{}` value: '{}'", stringify!($enum), s)
                         )
@@ -54,10 +131,33 @@ macro_rules! enum_mb_xml
                     $(
                         $enum::$variant => $str,
                     )+
+                    $(
+                        $enum::$fallback(ref value) => value.as_str(),
+                    )?
                 };
                 write!(f, "{}", s)
             }
         }
+
+        impl ::std::str::FromStr for $enum {
+            type Err = crate::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err>
+            {
+                match s {
+                    $(
+                        $str => Ok($enum::$variant),
+                    )+
+                    s => enum_fallback!($enum, s $(, $fallback)?).ok_or_else(||
+                        crate::Error::parse_error(
+                            format!("Unknown `{}` value: '{}'", stringify!($enum), s)
+                        )
+                    )
+                }
+            }
+        }
+
+        serialize_as_string!($enum);
     }
 }
 
@@ -70,16 +170,16 @@ macro_rules! enum_mb_xml_optional
                 var $variant:ident = $str:expr
             ),+
             ,
+            $( unknown $fallback:ident, )?
         }
     )
         =>
     {
-        $(#[$attr])*
-        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
-        pub enum $enum {
-            $(
-                $(#[$attr2])* $variant ,
-            )+
+        enum_definition! {
+            $(#[$attr])* pub enum $enum {
+                $( $(#[$attr2])* $variant, )+
+            }
+            $( unknown $fallback )?
         }
 
         impl FromXmlOptional for $enum {
@@ -91,7 +191,7 @@ macro_rules! enum_mb_xml_optional
                         $(
                             $str => Ok(Some($enum::$variant)),
                         )+
-                        s => Err(
+                        s => enum_fallback!($enum, s $(, $fallback)?).map(Some).ok_or_else(||
                             ::xpath_reader::Error::custom_msg(
                                 format!("Unknown `{}` value: '{}'", stringify!($enum), s)
                             )
@@ -110,22 +210,174 @@ macro_rules! enum_mb_xml_optional
                     $(
                         $enum::$variant => $str,
                     )+
+                    $(
+                        $enum::$fallback(ref value) => value.as_str(),
+                    )?
                 };
                 write!(f, "{}", s)
             }
         }
+
+        impl ::std::str::FromStr for $enum {
+            type Err = crate::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err>
+            {
+                match s {
+                    $(
+                        $str => Ok($enum::$variant),
+                    )+
+                    s => enum_fallback!($enum, s $(, $fallback)?).ok_or_else(||
+                        crate::Error::parse_error(
+                            format!("Unknown `{}` value: '{}'", stringify!($enum), s)
+                        )
+                    )
+                }
+            }
+        }
+
+        serialize_as_string!($enum);
     }
 }
 
-pub fn read_mb_duration<'d>(
-    reader: &'d Reader<'d>,
-    path: &str,
-) -> Result<Option<Duration>, ::xpath_reader::Error> {
-    let s: Option<String> = reader.read(path)?;
-    match s {
-        Some(millis) => Ok(Some(Duration::from_millis(
-            millis.parse().map_err(::xpath_reader::Error::custom_err)?,
-        ))),
-        None => Ok(None),
+/// Implement `FromXml` for a struct whose fields are all read from an xpath
+/// expression each, which is the case for most entities.
+///
+/// The optional `at` prefix is prepended to every path, so the common part
+/// doesn't have to be repeated for each field:
+///
+/// ```ignore
+/// from_xml_paths! {
+///     LabelRef at ".//" {
+///         mbid: "@id",
+///         name: "mb:name/text()",
+///     }
+/// }
+/// ```
+///
+/// A field can be followed by `=> function` to convert the value read from
+/// its path, e.g. `ended: "mb:life-span/mb:ended/text()" => read_ended`.
+///
+/// In tests the resulting paths are available as `XML_PATHS`, so they can be
+/// checked with `check_xml_path`.
+macro_rules! from_xml_paths
+{
+    (
+        $type:ident at $base:literal {
+            $( $field:ident: $path:literal $( => $convert:path )? ),+
+            $(,)*
+        }
+    )
+        =>
+    {
+        impl FromXml for $type {
+            fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, ::xpath_reader::Error>
+            {
+                Ok($type {
+                    $(
+                        $field: $( $convert )? (reader.read(concat!($base, $path))?),
+                    )+
+                })
+            }
+        }
+
+        #[cfg(test)]
+        impl $type {
+            pub(crate) const XML_PATHS: &'static [&'static str] = &[
+                $( concat!($base, $path), )+
+            ];
+        }
+    };
+    (
+        $type:ident {
+            $( $field:ident: $path:literal $( => $convert:path )? ),+
+            $(,)*
+        }
+    )
+        =>
+    {
+        from_xml_paths! { $type at "" { $( $field: $path $( => $convert )? ),+ } }
+    };
+}
+
+/// Convert the text of an `ended` element, which is `true` if the life span
+/// of an entity ended.
+pub(crate) fn read_ended(ended: Option<String>) -> Option<bool> {
+    ended.map(|e| e == "true")
+}
+
+/// Check that every element in `path` is qualified with the `mb` namespace,
+/// returning the first step which isn't.
+///
+/// Elements without the prefix never match anything in MusicBrainz
+/// responses, so a typo like `mb-isni` silently results in a missing value.
+#[cfg(test)]
+pub(crate) fn check_xml_path(path: &str) -> Result<(), String> {
+    // Predicates may contain slashes, so they are removed first.
+    let mut stripped = String::new();
+    let mut depth = 0;
+    for c in path.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+    }
+
+    for step in stripped.split(|c| c == '/' || c == '|').map(str::trim) {
+        let valid = step.is_empty()
+            || step.starts_with("mb:")
+            || step.starts_with('@')
+            || step.starts_with('.')
+            || step == "text()"
+            || step == "*";
+        if !valid {
+            return Err(format!("invalid step '{}' in path '{}'", step, path));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_xml_path;
+    use crate::entities::artist::ArtistResponse;
+    use crate::entities::event::EventResponse;
+    use crate::entities::label::LabelResponse;
+    use crate::entities::place::PlaceResponse;
+    use crate::entities::recording::RecordingResponse;
+    use crate::entities::release_group::ReleaseGroupResponse;
+    use crate::entities::series::SeriesResponse;
+    use crate::entities::*;
+
+    #[test]
+    fn xml_paths() {
+        let paths = [
+            AreaResponse::XML_PATHS,
+            ArtistRef::XML_PATHS,
+            ArtistResponse::XML_PATHS,
+            EventRef::XML_PATHS,
+            EventResponse::XML_PATHS,
+            Genre::XML_PATHS,
+            LabelRef::XML_PATHS,
+            LabelResponse::XML_PATHS,
+            PlaceRef::XML_PATHS,
+            PlaceResponse::XML_PATHS,
+            RecordingRef::XML_PATHS,
+            RecordingResponse::XML_PATHS,
+            ReleaseGroupRef::XML_PATHS,
+            ReleaseGroupResponse::XML_PATHS,
+            ReleaseRef::XML_PATHS,
+            SeriesResponse::XML_PATHS,
+            Tag::XML_PATHS,
+            WorkRef::XML_PATHS,
+        ];
+        for path in paths.iter().flat_map(|p| p.iter()) {
+            assert_eq!(check_xml_path(path), Ok(()));
+        }
+
+        assert!(check_xml_path("//mb:metadata/mb:label/mb:isni-list/mb-isni/text()").is_err());
+        assert!(check_xml_path("./mb:relation-list[@target-type='area']/mb:relation").is_ok());
     }
 }
@@ -0,0 +1,274 @@
+//! Includes, which request additional data along with an entity.
+//!
+//! The options of the entities are converted to includes to build requests,
+//! so the names used by the API are only defined here.
+
+use crate::entities::EntityKind;
+use crate::error::{Error, ErrorKind};
+use std::fmt;
+use std::str::FromStr;
+
+/// Additional data which can be requested along with an entity, e.g. its
+/// aliases.
+///
+/// Not every include is supported for every entity type, see `is_valid_for`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Include {
+    Aliases,
+    Annotation,
+    ArtistCredits,
+    Artists,
+    DiscIds,
+    Genres,
+    Isrcs,
+    Labels,
+    Media,
+    Ratings,
+    Recordings,
+    ReleaseGroups,
+    Releases,
+    Tags,
+    VariousArtists,
+    Works,
+    AreaRels,
+    ArtistRels,
+    EventRels,
+    InstrumentRels,
+    LabelRels,
+    PlaceRels,
+    RecordingRels,
+    ReleaseRels,
+    ReleaseGroupRels,
+    SeriesRels,
+    UrlRels,
+    WorkRels,
+    /// Along with the tracks of a release, list the relationships of their
+    /// recordings which are requested by the other relationship includes.
+    RecordingLevelRels,
+    /// Along with the relationships of recordings to works, list the
+    /// relationships of the works which are requested by the other
+    /// relationship includes.
+    WorkLevelRels,
+}
+
+impl Include {
+    /// All includes.
+    pub fn all() -> &'static [Include] {
+        &[
+            Include::Aliases,
+            Include::Annotation,
+            Include::ArtistCredits,
+            Include::Artists,
+            Include::DiscIds,
+            Include::Genres,
+            Include::Isrcs,
+            Include::Labels,
+            Include::Media,
+            Include::Ratings,
+            Include::Recordings,
+            Include::ReleaseGroups,
+            Include::Releases,
+            Include::Tags,
+            Include::VariousArtists,
+            Include::Works,
+            Include::AreaRels,
+            Include::ArtistRels,
+            Include::EventRels,
+            Include::InstrumentRels,
+            Include::LabelRels,
+            Include::PlaceRels,
+            Include::RecordingRels,
+            Include::ReleaseRels,
+            Include::ReleaseGroupRels,
+            Include::SeriesRels,
+            Include::UrlRels,
+            Include::WorkRels,
+            Include::RecordingLevelRels,
+            Include::WorkLevelRels,
+        ]
+    }
+
+    /// Name of the include as used in the `inc` parameter, e.g.
+    /// `release-groups`.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Include::Aliases => "aliases",
+            Include::Annotation => "annotation",
+            Include::ArtistCredits => "artist-credits",
+            Include::Artists => "artists",
+            Include::DiscIds => "discids",
+            Include::Genres => "genres",
+            Include::Isrcs => "isrcs",
+            Include::Labels => "labels",
+            Include::Media => "media",
+            Include::Ratings => "ratings",
+            Include::Recordings => "recordings",
+            Include::ReleaseGroups => "release-groups",
+            Include::Releases => "releases",
+            Include::Tags => "tags",
+            Include::VariousArtists => "various-artists",
+            Include::Works => "works",
+            Include::AreaRels => "area-rels",
+            Include::ArtistRels => "artist-rels",
+            Include::EventRels => "event-rels",
+            Include::InstrumentRels => "instrument-rels",
+            Include::LabelRels => "label-rels",
+            Include::PlaceRels => "place-rels",
+            Include::RecordingRels => "recording-rels",
+            Include::ReleaseRels => "release-rels",
+            Include::ReleaseGroupRels => "release-group-rels",
+            Include::SeriesRels => "series-rels",
+            Include::UrlRels => "url-rels",
+            Include::WorkRels => "work-rels",
+            Include::RecordingLevelRels => "recording-level-rels",
+            Include::WorkLevelRels => "work-level-rels",
+        }
+    }
+
+    /// Whether the include requests relationships, e.g. `UrlRels`.
+    pub fn is_relationship(&self) -> bool {
+        self.name().ends_with("-rels")
+    }
+
+    /// Whether the API supports the include for entities of type `kind`.
+    pub fn is_valid_for(&self, kind: EntityKind) -> bool {
+        use crate::entities::EntityKind::*;
+
+        match *self {
+            Include::Aliases
+            | Include::Annotation
+            | Include::Genres
+            | Include::Tags
+            | Include::AreaRels
+            | Include::ArtistRels
+            | Include::EventRels
+            | Include::InstrumentRels
+            | Include::LabelRels
+            | Include::PlaceRels
+            | Include::RecordingRels
+            | Include::ReleaseRels
+            | Include::ReleaseGroupRels
+            | Include::SeriesRels
+            | Include::UrlRels
+            | Include::WorkRels => true,
+            Include::Ratings => match kind {
+                Artist | Event | Label | Place | Recording | ReleaseGroup | Work => true,
+                _ => false,
+            },
+            Include::Artists | Include::ArtistCredits => match kind {
+                Recording | Release | ReleaseGroup => true,
+                _ => false,
+            },
+            Include::DiscIds | Include::Isrcs => match kind {
+                Artist | Recording | Release => true,
+                _ => false,
+            },
+            Include::Labels => kind == Release,
+            Include::Media => match kind {
+                Artist | Release | ReleaseGroup => true,
+                _ => false,
+            },
+            Include::Recordings | Include::ReleaseGroups => match kind {
+                Artist | Release => true,
+                _ => false,
+            },
+            Include::Releases => match kind {
+                Artist | Label | Recording | ReleaseGroup => true,
+                _ => false,
+            },
+            // Only has an effect together with `Releases`.
+            Include::VariousArtists => kind == Artist,
+            Include::Works => kind == Artist,
+            Include::RecordingLevelRels | Include::WorkLevelRels => kind == Release,
+        }
+    }
+}
+
+impl fmt::Display for Include {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses the name as returned by `Include::name`.
+impl FromStr for Include {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Include::all()
+            .iter()
+            .find(|i| i.name() == s)
+            .cloned()
+            .ok_or_else(|| {
+                Error::new(format!("Unknown include: '{}'", s), ErrorKind::UsageError)
+            })
+    }
+}
+
+/// Join includes into the value of the `inc` parameter, e.g. `aliases+tags`.
+pub(crate) fn join_includes(includes: &[Include]) -> String {
+    includes
+        .iter()
+        .map(Include::name)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+/// The error returned by the `from_includes` constructors of the options of
+/// entities of type `kind` for an include they don't support.
+pub(crate) fn unsupported(kind: EntityKind, include: Include) -> Error {
+    let message = if include.is_valid_for(kind) {
+        format!("The include '{}' isn't supported for {} yet.", include, kind)
+    } else {
+        format!("The include '{}' can't be requested for {}.", include, kind)
+    };
+    Error::new(message, ErrorKind::UsageError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::*;
+
+    #[test]
+    fn names() {
+        for include in Include::all() {
+            assert_eq!(include.name().parse::<Include>().unwrap(), *include);
+        }
+        assert!("release-group".parse::<Include>().is_err());
+        assert_eq!(
+            join_includes(&[Include::Aliases, Include::ReleaseGroups]),
+            "aliases+release-groups"
+        );
+        assert_eq!(join_includes(&[]), "");
+        assert!(Include::ReleaseGroupRels.is_relationship());
+        assert!(!Include::ReleaseGroups.is_relationship());
+    }
+
+    #[test]
+    fn options() {
+        // Every include the options can request is valid for the entity.
+        let includes = [
+            (EntityKind::Artist, ArtistOptions::everything().includes()),
+            (EntityKind::Event, EventOptions::everything().includes()),
+            (EntityKind::Label, LabelOptions::everything().includes()),
+            (EntityKind::Place, PlaceOptions::everything().includes()),
+            (
+                EntityKind::Recording,
+                RecordingOptions::everything().includes(),
+            ),
+            (EntityKind::Release, ReleaseOptions::everything().includes()),
+            (
+                EntityKind::ReleaseGroup,
+                ReleaseGroupOptions::everything().includes(),
+            ),
+            (EntityKind::Series, SeriesOptions::everything().includes()),
+            (EntityKind::Work, WorkOptions::everything().includes()),
+        ];
+        for &(kind, ref includes) in includes.iter() {
+            for include in includes {
+                assert!(include.is_valid_for(kind), "{} for {}", include, kind);
+            }
+        }
+    }
+}
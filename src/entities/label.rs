@@ -1,7 +1,14 @@
-use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
 
-use crate::entities::{Mbid, ResourceOld};
+use crate::client::Request;
+use crate::entities::{write_summary, Country, Genre, Mbid, OnRequest, Rating, Relation,
+                      RelationTarget, Resource, Tag};
+use crate::entities::{include, join_includes, EntityKind, Include};
 use crate::entities::date::PartialDate;
+use crate::entities::helper::read_ended;
+use crate::entities::refs::AreaRef;
+
+use std::fmt;
 
 /// A label entity in the MusicBrainz database.
 /// There is quite some controversy in the music industry what a 'label'
@@ -10,73 +17,386 @@ use crate::entities::date::PartialDate;
 /// For a complete disambiguation see the `LabelType` enum. The labels in
 /// MusicBrainz are mostly
 /// imprints.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Label {
+    response: LabelResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: LabelOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct LabelOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+
+    /// Request the relationships to other labels, e.g. renames and parent
+    /// labels.
+    pub label_rels: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct LabelResponse {
+    mbid: Mbid,
+    name: String,
+    sort_name: String,
+    disambiguation: Option<String>,
+    aliases: Vec<String>,
+    annotation: Option<String>,
+    label_code: Option<String>,
+    label_type: Option<LabelType>,
+    country: Option<Country>,
+    area: Option<AreaRef>,
+    ipi_code: Option<String>,
+    isni_code: Option<String>,
+    begin_date: Option<PartialDate>,
+    end_date: Option<PartialDate>,
+    ended: Option<bool>,
+    label_rels: Vec<Relation>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+impl Label {
+    /// MBID of `[no label]`, used for self-released releases.
+    pub const NO_LABEL_MBID: &'static str = "157afde4-4bf5-4039-8ad2-5a15acc85176";
+
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the label on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Label.permalink(self.mbid())
+    }
 
     /// The official name of the label.
-    pub name: String,
+    pub fn name(&self) -> &String {
+        &self.response.name
+    }
 
     /// Version of the `name` converted to latin characters for sorting.
-    pub sort_name: String,
+    pub fn sort_name(&self) -> &String {
+        &self.response.sort_name
+    }
 
     /// If there are multiple labels with the same name in the database, a
     /// short disambiguation
     /// comment is provided which allows to differentiate the entities.
-    pub disambiguation: Option<String>,
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
 
     /// Variants of the name mainly used as search help.
     /// These can be variants, spellings of names, missing titles and common
     /// misspellings.
-    pub aliases: Vec<String>,
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
+
+    /// Any additional free form annotation for this label.
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
 
     /// LC code of the label, as issued by the IFPI.
-    pub label_code: Option<String>,
+    pub fn label_code(&self) -> Option<&String> {
+        self.response.label_code.as_ref()
+    }
 
     /// Describes the main activity of the label.
-    pub label_type: Option<LabelType>,
+    pub fn label_type(&self) -> Option<LabelType> {
+        self.response.label_type.clone()
+    }
 
-    /// ISO 3166 country of origin for the label.
-    pub country: Option<String>,
+    /// Country of origin for the label.
+    pub fn country(&self) -> Option<&Country> {
+        self.response.country.as_ref()
+    }
+
+    /// The area the label is based in, which is more precise than the
+    /// `country`, e.g. a city.
+    pub fn area(&self) -> Option<&AreaRef> {
+        self.response.area.as_ref()
+    }
 
     /// Identifying number of the label as assigned by the CISAC database.
-    pub ipi_code: Option<String>,
+    pub fn ipi_code(&self) -> Option<&String> {
+        self.response.ipi_code.as_ref()
+    }
 
     /// ISNI code of the label.
-    pub isni_code: Option<String>,
+    pub fn isni_code(&self) -> Option<&String> {
+        self.response.isni_code.as_ref()
+    }
 
     /// The date when this label was founded.
     /// (Consult the MusicBrainz manual for disclaimers about the significance
     /// of these
     /// informations.)
-    pub begin_date: Option<PartialDate>,
+    pub fn begin_date(&self) -> Option<&PartialDate> {
+        self.response.begin_date.as_ref()
+    }
 
     /// The date when this label ceased to exist or its last release ever was
     /// released.
-    pub end_date: Option<PartialDate>,
+    pub fn end_date(&self) -> Option<&PartialDate> {
+        self.response.end_date.as_ref()
+    }
+
+    /// Whether the label ceased to exist, even if the `end_date` isn't known.
+    ///
+    /// `None` if the response doesn't say, the API only includes this for
+    /// labels which ended.
+    pub fn ended(&self) -> Option<bool> {
+        self.response.ended
+    }
+
+    /// Relationships to other labels, e.g. the label this one was renamed
+    /// into or its parent label.
+    ///
+    /// Use `LabelRelationType::of` to match on the type of a relationship.
+    pub fn label_rels(&self) -> OnRequest<&[Relation]> {
+        OnRequest::from_value(self.response.label_rels.as_ref(), self.options.label_rels)
+    }
+
+    /// The tags users attached to the label.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the label.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the label.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
+}
+
+impl fmt::Debug for Label {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Label")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("sort_name", self.sort_name())
+            .field("disambiguation", &self.disambiguation())
+            .field("label_type", &self.label_type())
+            .field("label_code", &self.label_code())
+            .field("country", &self.country())
+            .field("area", &self.area())
+            .field("ipi_code", &self.ipi_code())
+            .field("isni_code", &self.isni_code())
+            .field("begin_date", &self.begin_date())
+            .field("end_date", &self.end_date())
+            .field("ended", &self.ended())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("label_rels", &self.label_rels())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
+    }
+}
+
+impl fmt::Display for Label {
+    /// Formats the label as e.g. `EMI (Original Production, GB)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_summary(
+            f,
+            self.name(),
+            &[
+                self.label_type().map(|t| t.to_string()),
+                self.country().map(|c| c.to_string()),
+            ],
+        )
+    }
+}
+
+impl LabelOptions {
+    pub fn everything() -> Self {
+        LabelOptions {
+            aliases: true,
+            annotation: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+            label_rels: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        LabelOptions {
+            aliases: false,
+            annotation: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+            label_rels: false,
+        }
+    }
+
+    /// Request what is usually shown on a label page: the aliases and the
+    /// related labels.
+    pub fn for_display() -> Self {
+        LabelOptions {
+            aliases: true,
+            label_rels: true,
+            ..LabelOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        LabelOptions {
+            annotation: true,
+            genres: true,
+            ..LabelOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for labels.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = LabelOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                Include::LabelRels => options.label_rels = true,
+                _ => return Err(include::unsupported(EntityKind::Label, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        if self.label_rels {
+            includes.push(Include::LabelRels);
+        }
+        includes
+    }
 }
 
-impl ResourceOld for Label {
+// Related labels are nested inside the label element, so the paths are
+// anchored at the document root to not match their fields.
+from_xml_paths! {
+    LabelResponse at "//mb:metadata/mb:label/" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        sort_name: "mb:sort-name/text()",
+        disambiguation: "mb:disambiguation/text()",
+        aliases: "mb:alias-list/mb:alias/text()",
+        annotation: "mb:annotation/text()",
+        label_code: "mb:label-code/text()",
+        label_type: "@type",
+        country: "mb:country/text()",
+        area: "mb:area",
+        ipi_code: "mb:ipi/text()",
+        isni_code: "mb:isni-list/mb:isni/text()",
+        begin_date: "mb:life-span/mb:begin/text()",
+        end_date: "mb:life-span/mb:end/text()",
+        ended: "mb:life-span/mb:ended/text()" => read_ended,
+        label_rels: "mb:relation-list[@target-type='label']/mb:relation",
+        tags: "mb:tag-list/mb:tag",
+        genres: "mb:genre-list/mb:genre",
+        rating: "mb:rating",
+    }
+}
+
+impl Resource for Label {
+    type Options = LabelOptions;
+    type Response = LabelResponse;
     const NAME: &'static str = "label";
-    const INCL: &'static str = "aliases";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "label".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    #[cfg(feature = "diagnostics")]
+    const PARSED_ELEMENTS: Option<&'static [&'static str]> = Some(&[
+        "name",
+        "sort-name",
+        "disambiguation",
+        "alias-list",
+        "annotation",
+        "label-code",
+        "country",
+        "area",
+        "ipi",
+        "isni-list",
+        "life-span",
+        "relation-list",
+        "tag-list",
+        "genre-list",
+        "rating",
+    ]);
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Label { response, options }
+    }
+}
+
+enum_mb_xml_optional! {
+    /// The types of relationships between two labels.
+    pub enum LabelRelationType {
+        /// Forward: this label owns (is the parent of) the other label.
+        var Ownership = "label ownership",
+        /// Forward: this label was renamed into the other label.
+        var Rename = "label rename",
+        /// Forward: the other label is an imprint of this label.
+        var Imprint = "imprint",
+        /// Forward: this label distributes releases of the other label.
+        var Distribution = "label distribution",
+        /// Forward: this label reissues releases of the other label.
+        var Reissue = "label reissue",
+        /// The two labels had some kind of business association.
+        var BusinessAssociation = "business association",
+        unknown Unknown,
+    }
 }
 
-impl FromXml for Label {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Label, Error> {
-        Ok(Label {
-            mbid: reader.read(".//mb:label/@id")?,
-            name: reader.read(".//mb:label/mb:name/text()")?,
-            sort_name: reader.read(".//mb:label/mb:sort-name/text()")?,
-            disambiguation: reader.read(".//mb:label/mb:disambiguation/text()")?,
-            aliases: reader.read(".//mb:label/mb:alias-list/mb:alias/text()")?,
-            label_code: reader.read(".//mb:label/mb:label-code/text()")?,
-            label_type: reader.read(".//mb:label/@type")?,
-            country: reader.read(".//mb:label/mb:country/text()")?,
-            ipi_code: reader.read(".//mb:label/mb:ipi/text()")?,
-            isni_code: reader.read(".//mb:label/mb:isni-list/mb-isni/text()")?,
-            begin_date: reader.read(".//mb:label/mb:life-span/mb:begin/text()")?,
-            end_date: reader.read(".//mb:label/mb:life-span/mb:end/text()")?,
-        })
+impl LabelRelationType {
+    /// The type of `relation`, `None` if it doesn't point to another label.
+    pub fn of(relation: &Relation) -> Option<LabelRelationType> {
+        match relation.target {
+            RelationTarget::Label(_) => relation.relation_type.parse().ok(),
+            _ => None,
+        }
     }
 }
 
@@ -102,67 +422,220 @@ enum_mb_xml_optional! {
         /// Companies mainly distributing other labels production, often in a
         /// specfic region of the
         /// world.
-        var Distribution = "Distribution",
+        var Distribution = "Distributor",
         /// Holdings, conglomerates or other financial entities that don't mainly
         /// produce records but
         /// manage a large set of recording labels owned by them.
         var Holding = "Holding",
         /// An organization which collects royalties on behalf of the artists.
-        var RightsSociety = "RightsSociety",
+        var RightsSociety = "Rights Society",
+        /// Companies manufacturing the physical media of releases, e.g. pressing
+        /// plants.
+        var Manufacturer = "Manufacturer",
+
+        unknown Unknown,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::LabelRef;
+    use crate::util::test_utils::parse_typed;
     use std::str::FromStr;
 
     #[test]
     fn label_read_xml1() {
         let mbid = Mbid::from_str("c029628b-6633-439e-bcee-ed02e8a338f7").unwrap();
-        let label: Label = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let options = LabelOptions {
+            aliases: true,
+            ..LabelOptions::minimal()
+        };
+        let label: Label = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
 
-        assert_eq!(label.mbid, mbid);
-        assert_eq!(label.name, "EMI".to_string());
-        assert_eq!(label.sort_name, "EMI".to_string());
+        assert_eq!(label.mbid(), &mbid);
+        assert_eq!(label.name(), &"EMI".to_string());
+        assert_eq!(label.sort_name(), &"EMI".to_string());
         assert_eq!(
-            label.disambiguation,
-            Some("EMI Records, since 1972".to_string())
+            label.disambiguation(),
+            Some(&"EMI Records, since 1972".to_string())
         );
         assert_eq!(
-            label.aliases,
-            vec![
+            label.aliases().unwrap(),
+            &[
                 "EMI".to_string(),
                 "EMI Records (UK)".to_string(),
                 "EMI Records Ltd".to_string(),
                 "EMI UK".to_string(),
             ]
         );
-        assert_eq!(label.label_code, Some("542".to_string()));
-        assert_eq!(label.label_type, Some(LabelType::ProductionOriginal));
-        assert_eq!(label.country, Some("GB".to_string()));
-        assert_eq!(label.ipi_code, None);
-        assert_eq!(label.isni_code, None);
+        assert_eq!(label.label_code(), Some(&"542".to_string()));
+        assert_eq!(label.label_type(), Some(LabelType::ProductionOriginal));
+        assert_eq!(label.country().map(|c| c.as_str()), Some("GB"));
+        assert_eq!(label.ipi_code(), None);
+        assert_eq!(label.isni_code(), None);
         assert_eq!(
-            label.begin_date,
-            Some(PartialDate::from_str("1972").unwrap())
+            label.begin_date(),
+            Some(&PartialDate::from_str("1972").unwrap())
         );
-        assert_eq!(label.end_date, None);
+        assert_eq!(label.end_date(), None);
+        assert_eq!(label.ended(), None);
     }
 
     #[test]
     fn read_aliases() {
         let mbid = Mbid::from_str("168f48c8-057e-4974-9600-aa9956d21e1a").unwrap();
-        let label: Label = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let options = LabelOptions {
+            aliases: true,
+            ..LabelOptions::minimal()
+        };
+        let label: Label = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
 
         let mut expected = vec![
             "Avex Trax Japan".to_string(),
             "エイベックス・トラックス".to_string(),
         ];
         expected.sort();
-        let mut actual = label.aliases.clone();
+        let mut actual = label.aliases().unwrap().to_vec();
         actual.sort();
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn read_label_rels() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><label type="Original Production" id="c029628b-6633-439e-bcee-ed02e8a338f7"><name>EMI</name><sort-name>EMI</sort-name><isni-list><isni>0000000121564301</isni></isni-list><life-span><begin>1972</begin></life-span><relation-list target-type="label"><relation type="label rename" type-id="e6159066-6013-4d09-a2f8-bf473f21e89e"><target>df7d1c7f-ef95-425f-8eef-445b3d7bcbd9</target><direction>backward</direction><begin>1972</begin><ended>true</ended><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name><sort-name>Parlophone</sort-name><label-code>299</label-code></label></relation><relation type="label ownership" type-id="d1094da7-0d5d-4e8d-8e61-4f0a8bd54c6e"><target>376d9b4d-8cdd-44be-bc0f-ed5dfd2d2340</target><label id="376d9b4d-8cdd-44be-bc0f-ed5dfd2d2340"><name>Cherrytree Records</name><sort-name>Cherrytree Records</sort-name></label></relation><relation type="label merger" type-id="af2a4d3b-3c2c-4c0d-9e63-ba7b5c0fbc2b"><target>376d9b4d-8cdd-44be-bc0f-ed5dfd2d2340</target><label id="376d9b4d-8cdd-44be-bc0f-ed5dfd2d2340"><name>Cherrytree Records</name><sort-name>Cherrytree Records</sort-name></label></relation></relation-list></label></metadata>"#;
+        let label: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::everything()).unwrap();
+
+        assert_eq!(label.name(), &"EMI".to_string());
+        assert_eq!(label.label_code(), None);
+        assert_eq!(label.isni_code(), Some(&"0000000121564301".to_string()));
+
+        let rels = label.label_rels().unwrap();
+        assert_eq!(rels.len(), 3);
+
+        assert_eq!(
+            LabelRelationType::of(&rels[0]),
+            Some(LabelRelationType::Rename)
+        );
+        assert_eq!(rels[0].direction, RelationDirection::Backward);
+        assert_eq!(
+            rels[0].target,
+            RelationTarget::Label(LabelRef {
+                mbid: Mbid::from_str("df7d1c7f-ef95-425f-8eef-445b3d7bcbd9").unwrap(),
+                name: "Parlophone".to_string(),
+                sort_name: "Parlophone".to_string(),
+                label_code: Some("299".to_string()),
+            })
+        );
+        assert_eq!(
+            rels[0].begin_date,
+            Some(PartialDate::from_str("1972").unwrap())
+        );
+        assert_eq!(rels[0].end_date, None);
+        assert!(rels[0].ended);
+
+        assert_eq!(
+            LabelRelationType::of(&rels[1]),
+            Some(LabelRelationType::Ownership)
+        );
+        assert_eq!(rels[1].direction, RelationDirection::Forward);
+        assert_eq!(
+            rels[1].target,
+            RelationTarget::Label(LabelRef {
+                mbid: Mbid::from_str("376d9b4d-8cdd-44be-bc0f-ed5dfd2d2340").unwrap(),
+                name: "Cherrytree Records".to_string(),
+                sort_name: "Cherrytree Records".to_string(),
+                label_code: None,
+            })
+        );
+        assert_eq!(rels[1].begin_date, None);
+        assert!(!rels[1].ended);
+
+        assert_eq!(
+            LabelRelationType::of(&rels[2]),
+            Some(LabelRelationType::Unknown("label merger".to_string()))
+        );
+    }
+
+    #[test]
+    fn ended() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><label type="Original Production" id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name><sort-name>Parlophone</sort-name><life-span><begin>1896</begin><ended>true</ended></life-span></label></metadata>"#;
+        let label: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::minimal()).unwrap();
+
+        assert_eq!(label.end_date(), None);
+        assert_eq!(label.ended(), Some(true));
+    }
+
+    #[test]
+    fn label_types() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><label type="Rights Society" id="d1ab65f8-d082-492a-bd70-ce375548dabf"><name>GEMA</name><sort-name>GEMA</sort-name><country>DE</country><life-span><begin>1933</begin></life-span></label></metadata>"#;
+        let gema: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::minimal()).unwrap();
+        assert_eq!(gema.name(), &"GEMA".to_string());
+        assert_eq!(gema.label_type(), Some(LabelType::RightsSociety));
+
+        let label = |label_type| {
+            let children = "<name>Label</name><sort-name>Label</sort-name>";
+            parse_typed::<Label>(Some(label_type), children, LabelOptions::minimal())
+        };
+
+        assert_eq!(
+            label("Manufacturer").label_type(),
+            Some(LabelType::Manufacturer)
+        );
+        assert_eq!(
+            label("Distributor").label_type(),
+            Some(LabelType::Distribution)
+        );
+        assert_eq!(
+            label("Broadcaster").label_type(),
+            Some(LabelType::Unknown("Broadcaster".to_string()))
+        );
+        assert_eq!(LabelType::RightsSociety.to_string(), "Rights Society");
+    }
+
+    #[test]
+    fn area() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><label type="Original Production" id="c029628b-6633-439e-bcee-ed02e8a338f7"><name>EMI</name><sort-name>EMI</sort-name><country>GB</country><area id="8a754a16-0027-3a29-b6d7-2b40ea0481ed"><name>United Kingdom</name><sort-name>United Kingdom</sort-name><iso-3166-1-code-list><iso-3166-1-code>GB</iso-3166-1-code></iso-3166-1-code-list></area></label></metadata>"#;
+        let label: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::minimal()).unwrap();
+
+        let area = label.area().unwrap();
+        assert_eq!(
+            area.mbid,
+            Mbid::from_str("8a754a16-0027-3a29-b6d7-2b40ea0481ed").unwrap()
+        );
+        assert_eq!(area.name, "United Kingdom".to_string());
+        assert_eq!(area.iso_3166, label.country().cloned());
+    }
+
+    #[test]
+    fn annotation_tags_genres_rating() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><label type="Original Production" id="c029628b-6633-439e-bcee-ed02e8a338f7"><name>EMI</name><sort-name>EMI</sort-name><annotation>Not to be confused with EMI Music.</annotation><tag-list><tag count="2"><name>british</name></tag></tag-list><genre-list><genre count="1" id="911c7bbb-172d-4df8-9478-dbff4296e791"><name>pop</name></genre></genre-list><rating votes-count="3">3.5</rating></label></metadata>"#;
+        let label: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::everything()).unwrap();
+
+        assert_eq!(
+            label.annotation(),
+            OnRequest::Some(&"Not to be confused with EMI Music.".to_string())
+        );
+        assert_eq!(
+            label.tags().unwrap(),
+            &[Tag {
+                name: "british".to_string(),
+                count: 2,
+            }]
+        );
+        assert_eq!(label.genres().unwrap()[0].name, "pop".to_string());
+        assert_eq!(label.rating().unwrap().value, Some(3.5));
+        assert_eq!(label.rating().unwrap().votes, 3);
+
+        let label: Label =
+            crate::util::test_utils::parse_entity(xml, LabelOptions::minimal()).unwrap();
+        assert_eq!(label.tags(), OnRequest::NotRequested);
+        assert_eq!(label.genres(), OnRequest::NotRequested);
+    }
 }
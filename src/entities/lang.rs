@@ -67,3 +67,6 @@ impl ToString for Language {
         self.to_639_3().to_string()
     }
 }
+
+// Serialized as the ISO 639-3 code.
+serialize_as_string!(Language);
@@ -0,0 +1,132 @@
+use std::fmt::{self, Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+use std::time::Duration;
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
+
+/// The length of a track or recording.
+///
+/// MusicBrainz stores lengths in milliseconds, which is also the unit used
+/// in searches.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TrackLength {
+    millis: u64,
+}
+
+impl TrackLength {
+    /// Create a new length from a number of milliseconds.
+    pub fn from_millis(millis: u64) -> Self {
+        TrackLength { millis }
+    }
+
+    /// Create a new length from a number of seconds.
+    pub fn from_secs(secs: u64) -> Self {
+        TrackLength {
+            millis: secs * 1000,
+        }
+    }
+
+    /// The length in milliseconds.
+    pub fn as_millis(&self) -> u64 {
+        self.millis
+    }
+
+    /// The length in seconds, rounded to the nearest second.
+    pub fn as_secs_rounded(&self) -> u64 {
+        (self.millis + 500) / 1000
+    }
+
+    /// The length as a `Duration`.
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_millis(self.millis)
+    }
+}
+
+impl From<Duration> for TrackLength {
+    /// Converts a `Duration`, discarding any precision below milliseconds.
+    fn from(duration: Duration) -> Self {
+        TrackLength {
+            millis: duration.as_secs() * 1000 + u64::from(duration.subsec_millis()),
+        }
+    }
+}
+
+impl From<TrackLength> for Duration {
+    fn from(length: TrackLength) -> Self {
+        length.as_duration()
+    }
+}
+
+/// Formats the length rounded to seconds as `m:ss`, or `h:mm:ss` for lengths
+/// of at least one hour, like the MusicBrainz website does.
+impl Display for TrackLength {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let secs = self.as_secs_rounded();
+        let (hours, minutes, seconds) = (secs / 3600, (secs / 60) % 60, secs % 60);
+        if hours > 0 {
+            write!(f, "{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            write!(f, "{}:{:02}", minutes, seconds)
+        }
+    }
+}
+
+/// Parses a number of milliseconds, as found in the API responses.
+impl FromStr for TrackLength {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(TrackLength::from_millis(s.trim().parse()?))
+    }
+}
+
+impl FromXmlOptional for TrackLength {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, xpath_reader::Error> {
+        match Option::<String>::from_xml(reader)? {
+            Some(s) => s
+                .parse()
+                .map(Some)
+                .map_err(xpath_reader::Error::custom_err),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_support {
+    use super::TrackLength;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Serialized as the number of milliseconds.
+    impl Serialize for TrackLength {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_u64(self.millis)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for TrackLength {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            u64::deserialize(deserializer).map(TrackLength::from_millis)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(TrackLength::from_millis(237933).to_string(), "3:58");
+        assert_eq!(TrackLength::from_millis(59_499).to_string(), "0:59");
+        assert_eq!(TrackLength::from_secs(3 * 3600 + 62).to_string(), "3:01:02");
+    }
+
+    #[test]
+    fn conversions() {
+        let length: TrackLength = "232000".parse().unwrap();
+        assert_eq!(length.as_millis(), 232000);
+        assert_eq!(length.as_duration(), Duration::from_millis(232000));
+        assert_eq!(TrackLength::from(Duration::new(1, 2_500_000)).as_millis(), 1002);
+    }
+}
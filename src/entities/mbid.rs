@@ -1,14 +1,93 @@
 use std::fmt::{self, Debug, Display, Formatter};
 use std::str::FromStr;
+use url::Url;
 use uuid::{self, Uuid};
-use xpath_reader::{FromXml, Reader};
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
+
+use crate::entities::{Artist, EntityKind, Label};
 
 /// Identifier for entities in the MusicBrainz database.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Mbid {
     uuid: Uuid,
 }
 
+impl Mbid {
+    /// Extract the MBID from a link to an entity on the MusicBrainz website,
+    /// e.g. `https://musicbrainz.org/release/<mbid>`.
+    pub fn from_mb_url(url: &str) -> Result<Mbid, crate::Error> {
+        parse_mb_url(url).map(|(_, mbid)| mbid)
+    }
+
+    /// Link to the entity of type `kind` with this MBID on the MusicBrainz
+    /// website, see `EntityKind::permalink`.
+    pub fn permalink(&self, kind: EntityKind) -> String {
+        kind.permalink(self)
+    }
+
+    /// Whether this is the MBID of a special purpose artist or label, like
+    /// `Various Artists` or `[unknown]`, which doesn't stand for a real
+    /// artist.
+    ///
+    /// The MBIDs are available as constants, e.g.
+    /// `Artist::VARIOUS_ARTISTS_MBID`.
+    pub fn is_special_purpose(&self) -> bool {
+        let mbid = self.to_string();
+        SPECIAL_PURPOSE.contains(&mbid.as_str())
+    }
+
+    /// Whether this is the MBID of `Various Artists`.
+    pub fn is_various_artists(&self) -> bool {
+        self.to_string() == Artist::VARIOUS_ARTISTS_MBID
+    }
+}
+
+/// MBIDs of all special purpose artists and labels.
+const SPECIAL_PURPOSE: &[&str] = &[
+    Artist::VARIOUS_ARTISTS_MBID,
+    Artist::UNKNOWN_MBID,
+    Artist::ANONYMOUS_MBID,
+    Artist::DATA_MBID,
+    Artist::NO_ARTIST_MBID,
+    Artist::TRADITIONAL_MBID,
+    Artist::DIALOGUE_MBID,
+    Label::NO_LABEL_MBID,
+];
+
+/// Split a link to an entity on the MusicBrainz website into the name of the
+/// entity type, as used in API paths, and the MBID of the entity.
+///
+/// Subdomains like `beta.` as well as additional path segments, queries and
+/// fragments are accepted.
+pub(crate) fn parse_mb_url(url: &str) -> Result<(String, Mbid), crate::Error> {
+    use crate::error::ErrorKind;
+
+    let invalid = |reason: &str| {
+        crate::Error::new(
+            format!("Invalid MusicBrainz link '{}': {}", url, reason),
+            ErrorKind::UsageError,
+        )
+    };
+
+    let parsed = Url::parse(url.trim()).map_err(|e| invalid(&e.to_string()))?;
+    match parsed.host_str() {
+        Some(host) if host == "musicbrainz.org" || host.ends_with(".musicbrainz.org") => {}
+        _ => return Err(invalid("not a musicbrainz.org link")),
+    }
+
+    let mut segments = parsed
+        .path_segments()
+        .ok_or_else(|| invalid("missing path"))?
+        .filter(|s| !s.is_empty());
+    match (segments.next(), segments.next()) {
+        (Some(kind), Some(mbid)) => {
+            let mbid = mbid.parse().map_err(|_| invalid("invalid MBID"))?;
+            Ok((kind.to_string(), mbid))
+        }
+        _ => Err(invalid("expected a path like /<entity>/<mbid>")),
+    }
+}
+
 impl From<Uuid> for Mbid {
     fn from(uuid: Uuid) -> Self {
         Mbid { uuid: uuid }
@@ -43,6 +122,8 @@ impl Display for Mbid {
     }
 }
 
+serialize_as_string!(Mbid);
+
 impl FromXml for Mbid {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, ::xpath_reader::Error> {
         String::from_xml(reader)?
@@ -50,3 +131,57 @@ impl FromXml for Mbid {
             .map_err(|e| ::xpath_reader::Error::custom_err_msg(e, "Parse MBID error"))
     }
 }
+
+impl FromXmlOptional for Mbid {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, ::xpath_reader::Error> {
+        match Option::<String>::from_xml(reader)? {
+            Some(s) => s
+                .parse()
+                .map(Some)
+                .map_err(|e| ::xpath_reader::Error::custom_err_msg(e, "Parse MBID error")),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_url() {
+        let mbid = Mbid::from_str("ed118c5f-d940-4b52-a37b-b1a205374abe").unwrap();
+
+        assert_eq!(
+            parse_mb_url("https://musicbrainz.org/release/ed118c5f-d940-4b52-a37b-b1a205374abe")
+                .unwrap(),
+            ("release".to_string(), mbid.clone())
+        );
+        assert_eq!(
+            Mbid::from_mb_url(
+                "http://beta.musicbrainz.org/release/ed118c5f-d940-4b52-a37b-b1a205374abe/discids"
+            )
+            .unwrap(),
+            mbid
+        );
+        assert!(Mbid::from_mb_url("https://example.com/release/ed118c5f-d940-4b52-a37b-b1a205374abe")
+            .unwrap_err()
+            .is_usage_error());
+        assert!(Mbid::from_mb_url("https://musicbrainz.org/release/").is_err());
+        assert!(Mbid::from_mb_url("not a url").is_err());
+    }
+
+    #[test]
+    fn special_purpose() {
+        let various = Mbid::from_str(Artist::VARIOUS_ARTISTS_MBID).unwrap();
+        assert!(various.is_special_purpose());
+        assert!(various.is_various_artists());
+
+        let no_label = Mbid::from_str(Label::NO_LABEL_MBID).unwrap();
+        assert!(no_label.is_special_purpose());
+        assert!(!no_label.is_various_artists());
+
+        let radiohead = Mbid::from_str("a74b1b7f-71a5-4011-9441-d0b5e4122711").unwrap();
+        assert!(!radiohead.is_special_purpose());
+    }
+}
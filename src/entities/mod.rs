@@ -1,4 +1,29 @@
 //! Defines types representing the entities from the MusicBrainz database.
+//!
+//! The `Debug` output of an entity lists its fields through the accessors,
+//! so it doesn't depend on how the response is stored and fields which
+//! weren't requested show up as `NotRequested`.
+//!
+//! # Migrating from the old API
+//!
+//! Entities used to be fetched without options and exposed their data as
+//! public fields. The old entry points are deprecated but still compile:
+//!
+//! - `Client::get_by_mbid_old::<E>(&mbid)` becomes
+//!   `Client::get_by_mbid(&mbid, options)`. The `Default` options of every
+//!   entity request the same data as before.
+//! - `FetchFullOld::fetch_full(&r, client)` becomes
+//!   `FetchFull::fetch_full(&r, client, options)`.
+//! - Implementations of `ResourceOld` become implementations of `Resource`,
+//!   whose `Options` replace the `INCL` constant.
+//! - Fields like `event.name` become accessors like `event.name()`. Data
+//!   depending on the options is returned as `OnRequest`, use `as_option`
+//!   to get the previous `Option`.
+//! - `Relationship<E>` was an empty placeholder and is replaced by
+//!   `Relation`.
+//!
+//! No type aliases are provided for the entities, as their names didn't
+//! change and an alias can't turn fields into accessors.
 
 pub use std::time::Duration;
 
@@ -11,15 +36,41 @@ pub use self::date::{FullDate, ParseDateError, PartialDate};
 mod lang;
 pub use self::lang::Language;
 
+mod country;
+pub use self::country::Country;
+
+mod script;
+pub use self::script::Script;
+
+mod length;
+pub use self::length::TrackLength;
+
+mod track_number;
+pub use self::track_number::TrackNumber;
+
+mod include;
+pub use self::include::Include;
+pub(crate) use self::include::join_includes;
+
+mod tag;
+pub use self::tag::{Genre, Rating, Tag};
+
+mod relation;
+pub use self::relation::{DatedRelation, DatedRelations, Entity, Relation, RelationDirection,
+                         RelationTarget};
+
 pub mod refs;
-pub use self::refs::{AreaRef, ArtistRef, LabelRef, RecordingRef, ReleaseRef, FetchFull};
+pub use self::refs::{AreaRef, ArtistCredit, ArtistRef, EventRef, LabelRef, PlaceRef, RecordingRef, ReleaseGroupRef,
+                     ReleaseRef, WorkRef, FetchFull};
+#[allow(deprecated)]
+pub use self::refs::FetchFullOld;
 
 mod alias;
+mod any;
 mod area;
 mod artist;
 mod event;
 mod label;
-// mod medium;
 mod place;
 mod recording;
 mod release;
@@ -27,42 +78,51 @@ mod release_group;
 mod series;
 // mod track
 // mod url
-// mod work
+mod work;
 pub use self::alias::{Alias, AliasType};
+pub use self::any::{AnyEntity, EntityKind};
+pub(crate) use self::any::get_dynamic;
 pub use self::area::{Area, AreaType};
+pub(crate) use self::area::AreaResponse;
 pub use self::artist::{Artist, ArtistType, ArtistOptions, Gender};
-pub use self::event::{Event, EventType};
-pub use self::label::Label;
-pub use self::place::{Coordinates, Place, PlaceType};
-pub use self::recording::Recording;
-pub use self::release::{LabelInfo, Release, ReleaseMedium, ReleaseStatus, ReleaseTrack, ReleaseOptions};
-pub use self::release_group::{ReleaseGroup, ReleaseGroupPrimaryType, ReleaseGroupSecondaryType,
-ReleaseGroupType};
-pub use self::series::Series;
+pub use self::event::{Event, EventOptions, EventType};
+pub use self::label::{Label, LabelOptions, LabelRelationType, LabelType};
+pub use self::place::{Coordinates, Place, PlaceOptions, PlaceType};
+pub use self::recording::{Recording, RecordingOptions};
+pub use self::release::{LabelInfo, Release, ReleaseEvent, ReleaseMedium, ReleaseOptions,
+                        ReleaseQuality, ReleaseStatus, ReleaseTrack};
+pub use self::release_group::{ReleaseGroup, ReleaseGroupOptions, ReleaseGroupPrimaryType,
+                              ReleaseGroupSecondaryType, ReleaseGroupType, ReleasePreferences};
+pub use self::series::{Series, SeriesOptions, SeriesType};
+pub use self::work::{Work, WorkAttribute, WorkOptions, WorkType};
 // TODO it's pretty useless as of now.
-// pub use self::series::Series;
+// pub use self::series::{Series, SeriesType};
 
-use std::marker::PhantomData;
+use std::fmt;
 
 mod mbid;
 pub use self::mbid::Mbid;
+pub(crate) use self::mbid::parse_mb_url;
 use xpath_reader::FromXml;
 use crate::client::Request;
+use crate::error::ParseWarning;
 
-/// Represents an instance of an entity from the database.
-///
-/// Along with the data of the entity this can also optionally hold
-/// relationship data from the database.
-pub struct Entity<E> {
-    /// The actual data of the entity.
-    pub data: E,
-
-    /// The relationship data of the entity.
-    pub rels: Vec<Relationship<E>>,
-}
-
-pub struct Relationship<E> {
-    _e: PhantomData<E>,
+/// Write the one line summary used by the `Display` implementations of the
+/// entities, e.g. `Radiohead (group, GB)`. Missing details are left out.
+pub(crate) fn write_summary(
+    f: &mut fmt::Formatter,
+    name: &str,
+    details: &[Option<String>],
+) -> fmt::Result {
+    let details: Vec<&str> = details
+        .iter()
+        .filter_map(|d| d.as_ref().map(|d| d.as_str()))
+        .collect();
+    if details.is_empty() {
+        write!(f, "{}", name)
+    } else {
+        write!(f, "{} ({})", name, details.join(", "))
+    }
 }
 
 /// A Resource is any entity which can be directly retrieved from MusicBrainz.
@@ -70,6 +130,11 @@ pub struct Relationship<E> {
 /// We define this trait for the sake of using the `Client` type more
 /// efficiently, users of the `musicbrainz` crate shouldn't need to use this
 /// type directly.
+///
+/// All entities implement `Resource` now and no entity implements this trait
+/// anymore, it is only kept so code naming it still compiles. Options which
+/// implement `Default` request the data the `INCL` of the entity used to.
+#[deprecated(note = "implement `Resource` instead")]
 pub trait ResourceOld {
     /// Name of the resource for inclusion in api paths, e.g. `artist`.
     const NAME: &'static str;
@@ -97,6 +162,33 @@ pub trait Resource {
     fn request(options: &Self::Options) -> Request;
 
     fn from_response(response: Self::Response, options: Self::Options) -> Self;
+
+    /// Parts of the response which were skipped because they couldn't be
+    /// parsed.
+    ///
+    /// Only resources which support lenient parsing can return warnings.
+    fn parse_warnings(&self) -> &[ParseWarning] {
+        &[]
+    }
+
+    /// Names of the child elements of the entity element which are parsed.
+    ///
+    /// Used to report unparsed elements, `None` disables the check for the
+    /// resource.
+    #[cfg(feature = "diagnostics")]
+    const PARSED_ELEMENTS: Option<&'static [&'static str]> = None;
+
+    /// Parse a response with the `quick-xml` backend.
+    ///
+    /// Returns `None` if the resource doesn't support the backend for the
+    /// provided options, in which case the XPath backend is used instead.
+    #[cfg(feature = "streaming")]
+    fn parse_streaming(
+        _body: &str,
+        _options: &Self::Options,
+    ) -> Option<Result<Self::Response, crate::Error>> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -115,6 +207,87 @@ impl<T> OnRequest<T> {
         }
     }
 
+    /// For collection fields, which are always present in the response but
+    /// empty if they weren't requested.
+    pub(crate) fn from_value(value: T, requested: bool) -> OnRequest<T> {
+        if requested {
+            OnRequest::Some(value)
+        } else {
+            OnRequest::NotRequested
+        }
+    }
+
+    /// Converts into an `Option`, discarding the reason a value is missing.
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            OnRequest::Some(val) => Some(val),
+            OnRequest::NotAvailable | OnRequest::NotRequested => None,
+        }
+    }
+
+    /// Returns a reference to the value, if there is one.
+    pub fn as_option(&self) -> Option<&T> {
+        match self {
+            OnRequest::Some(val) => Some(val),
+            OnRequest::NotAvailable | OnRequest::NotRequested => None,
+        }
+    }
+
+    /// Converts from `&OnRequest<T>` to `OnRequest<&T>`.
+    pub fn as_ref(&self) -> OnRequest<&T> {
+        match self {
+            OnRequest::Some(val) => OnRequest::Some(val),
+            OnRequest::NotAvailable => OnRequest::NotAvailable,
+            OnRequest::NotRequested => OnRequest::NotRequested,
+        }
+    }
+
+    /// Maps the value if there is one, keeping the reason otherwise.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> OnRequest<U> {
+        match self {
+            OnRequest::Some(val) => OnRequest::Some(f(val)),
+            OnRequest::NotAvailable => OnRequest::NotAvailable,
+            OnRequest::NotRequested => OnRequest::NotRequested,
+        }
+    }
+
+    /// Converts into a `Result`, with `err` in case there is no value.
+    pub fn ok_or<E>(self, err: E) -> Result<T, E> {
+        self.into_option().ok_or(err)
+    }
+
+    /// Like `ok_or`, but the error is only computed if there is no value.
+    pub fn ok_or_else<E, F: FnOnce() -> E>(self, err: F) -> Result<T, E> {
+        self.into_option().ok_or_else(err)
+    }
+
+    /// Returns the value or `default` if there is none.
+    pub fn unwrap_or(self, default: T) -> T {
+        self.into_option().unwrap_or(default)
+    }
+
+    /// True if there is a value.
+    pub fn is_some(&self) -> bool {
+        match self {
+            OnRequest::Some(_) => true,
+            OnRequest::NotAvailable | OnRequest::NotRequested => false,
+        }
+    }
+
+    /// True if the value was requested, regardless of whether the server
+    /// returned one.
+    pub fn is_requested(&self) -> bool {
+        match self {
+            OnRequest::NotRequested => false,
+            OnRequest::Some(_) | OnRequest::NotAvailable => true,
+        }
+    }
+
+    /// An iterator over the value, yielding nothing if there is none.
+    pub fn iter(&self) -> ::std::option::IntoIter<&T> {
+        self.as_option().into_iter()
+    }
+
     pub fn unwrap(self) -> T {
         match self {
             OnRequest::Some(val) => val,
@@ -155,20 +328,45 @@ impl<T: PartialEq> PartialEq for OnRequest<T> {
 
 impl<T: Eq> Eq for OnRequest<T> {}
 
-/*
 impl<T> From<OnRequest<T>> for Option<T> {
     fn from(o: OnRequest<T>) -> Option<T> {
-        match o {
-            OnRequest::Some(t) => Some(t),
-            OnRequest::NotAvailable | OnRequest::NotRequested => None,
-        }
+        o.into_option()
     }
 }
-*/
 
-// TODO pub struct Work {}
+impl<T> IntoIterator for OnRequest<T> {
+    type Item = T;
+    type IntoIter = ::std::option::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_option().into_iter()
+    }
+}
 
 // TODO pub struct Url {}
 
 // TODO: rating, tag, collection
 // TODO: discid, isrc, iswc
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn on_request_combinators() {
+        let some: OnRequest<u32> = OnRequest::Some(2);
+        let missing: OnRequest<u32> = OnRequest::NotAvailable;
+
+        assert_eq!(some.clone().map(|v| v * 2), OnRequest::Some(4));
+        assert_eq!(
+            OnRequest::NotRequested.map(|v: u32| v * 2),
+            OnRequest::NotRequested
+        );
+        assert_eq!(some.as_option(), Some(&2));
+        assert_eq!(missing.clone().ok_or("missing"), Err("missing"));
+        assert_eq!(Option::from(some.clone()), Some(2));
+        assert_eq!(some.iter().count(), 1);
+        assert_eq!(missing.clone().into_iter().count(), 0);
+        assert!(missing.is_requested() && !missing.is_some());
+    }
+}
@@ -1,7 +1,11 @@
-use crate::entities::{Mbid, PartialDate, ResourceOld};
+use crate::client::Request;
+use crate::entities::{include, join_includes, EntityKind, Genre, Include, Mbid, OnRequest,
+                      PartialDate, Rating, Resource, Tag};
 use crate::entities::refs::AreaRef;
 use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
 
+use std::fmt;
+
 enum_mb_xml_optional! {
     /// Specifies what a `Place` instance actually is.
     pub enum PlaceType {
@@ -10,7 +14,11 @@ enum_mb_xml_optional! {
         var Stadium = "Stadium",
         var IndoorArena = "Indoor arena",
         var ReligiousBuilding = "Religious building",
+        var EducationalInstitution = "Educational institution",
+        var PressingPlant = "Pressing plant",
+        var Park = "Park",
         var Other = "Other",
+        unknown Unknown,
     }
 }
 
@@ -19,6 +27,7 @@ enum_mb_xml_optional! {
 /// TODO: Parsing of the coordinate values, currently they are only unchecked
 /// string values.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Coordinates {
     pub latitude: String,
     pub longitude: String,
@@ -43,106 +52,337 @@ impl FromXmlOptional for Coordinates {
 ///
 /// Additional information can be found in the [MusicBrainz
 /// docs](https://musicbrainz.org/doc/Place).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Place {
+    response: PlaceResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: PlaceOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaceOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlaceResponse {
+    mbid: Mbid,
+    name: String,
+    place_type: Option<PlaceType>,
+    address: Option<String>,
+    coordinates: Option<Coordinates>,
+    area: Option<AreaRef>,
+    begin: Option<PartialDate>,
+    end: Option<PartialDate>,
+    aliases: Vec<String>,
+    disambiguation: Option<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+impl Place {
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the place on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Place.permalink(self.mbid())
+    }
 
     /// The official name of a `Place`.
-    pub name: String,
+    pub fn name(&self) -> &String {
+        &self.response.name
+    }
 
     /// The type of the `Place`.
-    pub place_type: Option<PlaceType>,
+    pub fn place_type(&self) -> Option<PlaceType> {
+        self.response.place_type.clone()
+    }
 
     /// Address of the `Place` in the local adressing format.
-    pub address: Option<String>,
+    pub fn address(&self) -> Option<&String> {
+        self.response.address.as_ref()
+    }
 
     /// The exact coordinates of the place.
-    pub coordinates: Option<Coordinates>,
+    pub fn coordinates(&self) -> Option<&Coordinates> {
+        self.response.coordinates.as_ref()
+    }
 
     /// Specifies the `Area` the `Place` is located in.
-    pub area: Option<AreaRef>,
+    pub fn area(&self) -> Option<&AreaRef> {
+        self.response.area.as_ref()
+    }
 
     /// When the `Place` was founded.
-    pub begin: Option<PartialDate>,
+    pub fn begin(&self) -> Option<&PartialDate> {
+        self.response.begin.as_ref()
+    }
 
     /// When the `Place` closed down.
-    pub end: Option<PartialDate>,
+    pub fn end(&self) -> Option<&PartialDate> {
+        self.response.end.as_ref()
+    }
 
     /// Alternative versions of this `Place`'s name.
-    pub aliases: Vec<String>,
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
 
     /// Additional disambiguation if there are multiple places with the same
     /// name.
-    pub disambiguation: Option<String>,
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
 
     /// Any additional free form annotation for this `Place`.
-    pub annotation: Option<String>,
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
+
+    /// The tags users attached to the place.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the place.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the place.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
 }
 
-impl FromXml for Place {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(Place {
-            address: reader.read(".//mb:place/mb:address/text()")?,
-            aliases: reader.read(".//mb:place/mb:aliases/text()")?,
-            annotation: reader.read(".//mb:place/mb:annotation/text()")?,
-            area: reader.read(".//mb:place/mb:area")?,
-            begin: reader.read(".//mb:place/mb:life-span/mb:begin/text()")?,
-            coordinates: reader.read(".//mb:place/mb:coordinates")?,
-            disambiguation: reader.read(".//mb:place/mb:disambiguation/text()")?,
-            end: reader.read(".//mb:place/mb:life-span/mb:end/text()")?,
-            mbid: reader.read(".//mb:place/@id")?,
-            name: reader.read(".//mb:place/mb:name/text()")?,
-            place_type: reader.read(".//mb:place/@type")?,
-        })
+impl fmt::Debug for Place {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Place")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("place_type", &self.place_type())
+            .field("address", &self.address())
+            .field("coordinates", &self.coordinates())
+            .field("area", &self.area())
+            .field("begin", &self.begin())
+            .field("end", &self.end())
+            .field("disambiguation", &self.disambiguation())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
     }
 }
 
-impl ResourceOld for Place {
+impl PlaceOptions {
+    pub fn everything() -> Self {
+        PlaceOptions {
+            aliases: true,
+            annotation: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        PlaceOptions {
+            aliases: false,
+            annotation: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+        }
+    }
+
+    /// Request what is usually shown on a place page: the aliases, tags and
+    /// genres.
+    pub fn for_display() -> Self {
+        PlaceOptions {
+            aliases: true,
+            tags: true,
+            genres: true,
+            ..PlaceOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        PlaceOptions {
+            annotation: true,
+            genres: true,
+            ..PlaceOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for places.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = PlaceOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                _ => return Err(include::unsupported(EntityKind::Place, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        includes
+    }
+}
+
+/// The aliases and the annotation, which were always requested before places
+/// had options.
+impl Default for PlaceOptions {
+    fn default() -> Self {
+        PlaceOptions {
+            aliases: true,
+            annotation: true,
+            ..PlaceOptions::minimal()
+        }
+    }
+}
+
+from_xml_paths! {
+    PlaceResponse at ".//mb:place/" {
+        address: "mb:address/text()",
+        aliases: "mb:alias-list/mb:alias/text()",
+        annotation: "mb:annotation/text()",
+        area: "mb:area",
+        begin: "mb:life-span/mb:begin/text()",
+        coordinates: "mb:coordinates",
+        disambiguation: "mb:disambiguation/text()",
+        end: "mb:life-span/mb:end/text()",
+        mbid: "@id",
+        name: "mb:name/text()",
+        place_type: "@type",
+        tags: "mb:tag-list/mb:tag",
+        genres: "mb:genre-list/mb:genre",
+        rating: "mb:rating",
+    }
+}
+
+impl Resource for Place {
+    type Options = PlaceOptions;
+    type Response = PlaceResponse;
     const NAME: &'static str = "place";
-    const INCL: &'static str = "annotation+aliases";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "place".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Place { response, options }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_utils::parse_typed;
     use std::str::FromStr;
 
     #[test]
     fn place_read_1() {
         let mbid = Mbid::from_str("d1ab65f8-d082-492a-bd70-ce375548dabf").unwrap();
-        let p: Place = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let p: Place =
+            crate::util::test_utils::fetch_entity(&mbid, PlaceOptions::default()).unwrap();
 
         // Check parsed values.
-        assert_eq!(p.mbid, mbid);
-        assert_eq!(p.name, "Chipping Norton Recording Studios".to_string());
-        assert_eq!(p.place_type, Some(PlaceType::Studio));
+        assert_eq!(p.mbid(), &mbid);
+        assert_eq!(p.name(), &"Chipping Norton Recording Studios".to_string());
+        assert_eq!(p.place_type(), Some(PlaceType::Studio));
         assert_eq!(
-            p.address,
-            Some("28–30 New Street, Chipping Norton".to_string())
+            p.address(),
+            Some(&"28–30 New Street, Chipping Norton".to_string())
         );
         assert_eq!(
-            p.coordinates,
-            Some(Coordinates {
+            p.coordinates(),
+            Some(&Coordinates {
                 latitude: "51.9414".to_string(),
                 longitude: "-1.548".to_string(),
             })
         );
         assert_eq!(
-            p.area,
-            Some(AreaRef {
+            p.area(),
+            Some(&AreaRef {
                 mbid: Mbid::from_str("716234d3-b8ed-45ac-8983-e7219eb85956").unwrap(),
                 name: "Chipping Norton".to_string(),
                 sort_name: "Chipping Norton".to_string(),
                 iso_3166: None,
             })
         );
-        assert_eq!(p.begin, PartialDate::from_str("1971").ok());
-        assert_eq!(p.end, PartialDate::from_str("1999-10").ok());
-        assert_eq!(p.aliases, Vec::<String>::new());
-        assert_eq!(p.disambiguation, None);
-        assert_eq!(p.annotation, None);
+        assert_eq!(p.begin(), PartialDate::from_str("1971").ok().as_ref());
+        assert_eq!(p.end(), PartialDate::from_str("1999-10").ok().as_ref());
+        assert!(p.aliases().unwrap().is_empty());
+        assert_eq!(p.disambiguation(), None);
+        assert_eq!(p.annotation(), OnRequest::NotAvailable);
+        assert_eq!(p.tags(), OnRequest::NotRequested);
     }
 
     // TODO more expansive example testing all fields
+
+    #[test]
+    fn place_types() {
+        let place = |place_type| {
+            let children = "<name>Place</name>";
+            parse_typed::<Place>(Some(place_type), children, PlaceOptions::minimal())
+        };
+
+        assert_eq!(place("Park").place_type(), Some(PlaceType::Park));
+        assert_eq!(
+            place("Educational institution").place_type(),
+            Some(PlaceType::EducationalInstitution)
+        );
+        assert_eq!(
+            place("Pressing plant").place_type(),
+            Some(PlaceType::PressingPlant)
+        );
+        assert_eq!(
+            place("Lighthouse").place_type(),
+            Some(PlaceType::Unknown("Lighthouse".to_string()))
+        );
+        assert_eq!(place("Other").place_type(), Some(PlaceType::Other));
+    }
 }
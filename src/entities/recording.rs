@@ -1,57 +1,296 @@
-use std::time::Duration;
-use xpath_reader::{FromXml, Error, Reader};
+use xpath_reader::{FromXml, Reader};
 
-use crate::entities::{Mbid, ResourceOld};
+use crate::client::Request;
+use crate::entities::{include, join_includes, write_summary, EntityKind, Genre, Include, Mbid,
+                      OnRequest, Rating, Resource, Tag, TrackLength};
 use crate::entities::refs::ArtistRef;
 
+use std::fmt;
+
 /// Represents a unique audio that has been used to produce at least one
 /// released track through
 /// copying or mastering.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Recording {
+    response: RecordingResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: RecordingOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecordingOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+
+    /// Request the artists the recording is credited to.
+    pub artists: bool,
+
+    /// Request the ISRCs assigned to the recording.
+    pub isrcs: bool,
+
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RecordingResponse {
+    mbid: Mbid,
+    title: String,
+    artists: Vec<ArtistRef>,
+    duration: Option<TrackLength>,
+    isrc_code: Option<String>,
+    disambiguation: Option<String>,
+    aliases: Vec<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+impl Recording {
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the recording on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Recording.permalink(self.mbid())
+    }
 
     /// The title of the recording.
-    pub title: String,
+    pub fn title(&self) -> &String {
+        &self.response.title
+    }
 
     /// The artists that the recording is primarily credited to.
-    pub artists: Vec<ArtistRef>,
+    pub fn artists(&self) -> OnRequest<&[ArtistRef]> {
+        OnRequest::from_value(self.response.artists.as_ref(), self.options.artists)
+    }
 
     /// Approximation of the length of the recording, calculated from the
     /// tracks using it.
-    pub duration: Option<Duration>,
+    pub fn duration(&self) -> Option<&TrackLength> {
+        self.response.duration.as_ref()
+    }
 
     /// ISRC (International Standard Recording Code) assigned to the recording.
-    pub isrc_code: Option<String>,
+    pub fn isrc_code(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.isrc_code.as_ref(), self.options.isrcs)
+    }
 
     /// Disambiguation comment.
-    pub disambiguation: Option<String>,
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
+
+    /// Alternative titles of the recording.
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
 
     /// Any additional free form annotation for this `Recording`.
-    pub annotation: Option<String>,
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
+
+    /// The tags users attached to the recording.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the recording.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the recording.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
+}
+
+impl fmt::Debug for Recording {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recording")
+            .field("mbid", self.mbid())
+            .field("title", self.title())
+            .field("artists", &self.artists())
+            .field("duration", &self.duration())
+            .field("isrc_code", &self.isrc_code())
+            .field("disambiguation", &self.disambiguation())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
+    }
+}
+
+impl fmt::Display for Recording {
+    /// Formats the recording as e.g. `Airbag (Radiohead, 4:44)`, the artists
+    /// are only listed if they were requested.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let artists: Vec<&str> = self
+            .response
+            .artists
+            .iter()
+            .map(|a| a.name.as_str())
+            .collect();
+        write_summary(
+            f,
+            self.title(),
+            &[
+                Some(artists.join(", ")).filter(|a| !a.is_empty()),
+                self.duration().map(|d| d.to_string()),
+            ],
+        )
+    }
+}
+
+impl RecordingOptions {
+    pub fn everything() -> Self {
+        RecordingOptions {
+            aliases: true,
+            annotation: true,
+            artists: true,
+            isrcs: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        RecordingOptions {
+            aliases: false,
+            annotation: false,
+            artists: false,
+            isrcs: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+        }
+    }
+
+    /// Request what is usually shown on a recording page: the artists, the
+    /// ISRCs, tags and genres.
+    pub fn for_display() -> Self {
+        RecordingOptions {
+            artists: true,
+            isrcs: true,
+            tags: true,
+            genres: true,
+            ..RecordingOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        RecordingOptions {
+            annotation: true,
+            genres: true,
+            ..RecordingOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for recordings.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = RecordingOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Artists => options.artists = true,
+                Include::Isrcs => options.isrcs = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                _ => return Err(include::unsupported(EntityKind::Recording, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.artists {
+            includes.push(Include::Artists);
+        }
+        if self.isrcs {
+            includes.push(Include::Isrcs);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        includes
+    }
 }
 
-impl FromXml for Recording {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(Recording {
-            mbid: reader.read(".//mb:recording/@id")?,
-            title: reader.read(".//mb:recording/mb:title/text()")?,
-            artists: reader.read(".//mb:recording/mb:artist-credit/mb:name-credit")?,
-            duration: crate::entities::helper::read_mb_duration(
-                reader,
-                ".//mb:recording/mb:length/text()",
-            )?,
-            isrc_code: reader.read(".//mb:recording/mb:isrc-list/mb:isrc/@id")?,
-            disambiguation: reader.read(".//mb:recording/mb:disambiguation/text()")?,
-            annotation: reader.read(".//mb:recording/mb:annotation/text()")?,
-        })
+/// The annotation, artists and ISRCs, which were always requested before
+/// recordings had options.
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        RecordingOptions {
+            annotation: true,
+            artists: true,
+            isrcs: true,
+            ..RecordingOptions::minimal()
+        }
     }
 }
 
-impl ResourceOld for Recording {
+from_xml_paths! {
+    RecordingResponse at ".//mb:recording/" {
+        mbid: "@id",
+        title: "mb:title/text()",
+        artists: "mb:artist-credit/mb:name-credit",
+        duration: "mb:length/text()",
+        isrc_code: "mb:isrc-list/mb:isrc/@id",
+        disambiguation: "mb:disambiguation/text()",
+        aliases: "mb:alias-list/mb:alias/text()",
+        annotation: "mb:annotation/text()",
+        tags: "mb:tag-list/mb:tag",
+        genres: "mb:genre-list/mb:genre",
+        rating: "mb:rating",
+    }
+}
+
+impl Resource for Recording {
+    type Options = RecordingOptions;
+    type Response = RecordingResponse;
     const NAME: &'static str = "recording";
-    const INCL: &'static str = "artists+annotation+isrcs";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "recording".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Recording { response, options }
+    }
 }
 
 #[cfg(test)]
@@ -62,24 +301,33 @@ mod tests {
     #[test]
     fn read_xml1() {
         let mbid = Mbid::from_str("fbe3d0b9-3990-4a76-bddb-12f4a0447a2c").unwrap();
-        let recording: Recording = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let recording: Recording =
+            crate::util::test_utils::fetch_entity(&mbid, RecordingOptions::default()).unwrap();
 
-        assert_eq!(recording.mbid, mbid);
+        assert_eq!(recording.mbid(), &mbid);
         assert_eq!(
-            recording.title,
-            "The Perfect Drug (Nine Inch Nails)".to_string()
+            recording.title(),
+            &"The Perfect Drug (Nine Inch Nails)".to_string()
         );
-        assert_eq!(recording.duration, Some(Duration::from_millis(499000)));
         assert_eq!(
-            recording.artists,
-            vec![ArtistRef {
+            recording.duration(),
+            Some(&TrackLength::from_millis(499000))
+        );
+        assert_eq!(
+            recording.artists().unwrap(),
+            &[ArtistRef {
                 mbid: Mbid::from_str("b7ffd2af-418f-4be2-bdd1-22f8b48613da").unwrap(),
                 name: "Nine Inch Nails".to_string(),
                 sort_name: "Nine Inch Nails".to_string(),
             },]
         );
-        assert_eq!(recording.isrc_code, Some("USIR19701296".to_string()));
-        assert_eq!(recording.annotation, None);
-        assert_eq!(recording.disambiguation, None);
+        assert_eq!(recording.isrc_code().unwrap(), "USIR19701296");
+        assert_eq!(recording.annotation(), OnRequest::NotAvailable);
+        assert_eq!(recording.aliases(), OnRequest::NotRequested);
+        assert_eq!(recording.disambiguation(), None);
+        assert_eq!(
+            recording.to_string(),
+            "The Perfect Drug (Nine Inch Nails) (Nine Inch Nails, 8:19)"
+        );
     }
 }
@@ -3,10 +3,9 @@
 
 // TODO: Better documentation in this file.
 
-use std::time::Duration;
 use xpath_reader::{FromXml, FromXmlOptional, Reader};
 
-use crate::entities::Mbid;
+use crate::entities::{Country, EventType, Mbid, PlaceType, ReleaseGroupType, TrackLength};
 use crate::entities::date::PartialDate;
 use crate::entities::release::{ReleaseStatus, ReleaseOptions};
 use crate::client::Client;
@@ -19,18 +18,38 @@ pub trait FetchFull {
     fn fetch_full(&self, client: &mut Client, options: Self::Options) -> Result<Self::Full, Error>;
 }
 
+/// Fetching the full entity without options.
+///
+/// Kept for code written before all entities had options, it is implemented
+/// for every reference whose options implement `Default`, which requests the
+/// same data as before.
+#[deprecated(note = "use `FetchFull`, passing the options of the full entity")]
 pub trait FetchFullOld {
     type Full;
 
     fn fetch_full(&self, client: &mut Client) -> Result<Self::Full, Error>;
 }
 
+#[allow(deprecated)]
+impl<R> FetchFullOld for R
+where
+    R: FetchFull,
+    R::Options: Default,
+{
+    type Full = R::Full;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::Full, Error> {
+        FetchFull::fetch_full(self, client, R::Options::default())
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AreaRef {
     pub mbid: Mbid,
     pub name: String,
     pub sort_name: String,
-    pub iso_3166: Option<String>,
+    pub iso_3166: Option<Country>,
 }
 
 impl FromXmlOptional for AreaRef {
@@ -50,23 +69,51 @@ impl FromXmlOptional for AreaRef {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArtistRef {
     pub mbid: Mbid,
     pub name: String,
     pub sort_name: String,
 }
 
-impl FromXml for ArtistRef {
+from_xml_paths! {
+    ArtistRef at ".//" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        sort_name: "mb:sort-name/text()",
+    }
+}
+
+/// An artist as credited on a release or track.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArtistCredit {
+    /// The credited artist.
+    pub artist: ArtistRef,
+
+    /// The name the artist is credited with, which can differ from the name
+    /// of the artist, e.g. for a former name.
+    pub name: String,
+
+    /// The phrase joining this credit with the next one, e.g. `" feat. "`.
+    pub join_phrase: String,
+}
+
+impl FromXml for ArtistCredit {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
-        Ok(ArtistRef {
-            mbid: reader.read(".//@id")?,
-            name: reader.read(".//mb:name/text()")?,
-            sort_name: reader.read(".//mb:sort-name/text()")?,
+        let artist: ArtistRef = reader.read("./mb:artist")?;
+        let name: Option<String> = reader.read("./mb:name/text()")?;
+        let join_phrase: Option<String> = reader.read("./@joinphrase")?;
+        Ok(ArtistCredit {
+            name: name.unwrap_or_else(|| artist.name.clone()),
+            join_phrase: join_phrase.unwrap_or_default(),
+            artist: artist,
         })
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LabelRef {
     pub mbid: Mbid,
     pub name: String,
@@ -74,52 +121,257 @@ pub struct LabelRef {
     pub label_code: Option<String>,
 }
 
-impl FromXml for LabelRef {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
-        Ok(LabelRef {
-            mbid: reader.read(".//@id")?,
-            name: reader.read(".//mb:name/text()")?,
-            sort_name: reader.read(".//mb:sort-name/text()")?,
-            label_code: reader.read(".//mb:label-code/text()")?,
-        })
+from_xml_paths! {
+    LabelRef at ".//" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        sort_name: "mb:sort-name/text()",
+        label_code: "mb:label-code/text()",
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RecordingRef {
     pub mbid: Mbid,
     pub title: String,
-    pub length: Option<Duration>,
+    pub length: Option<TrackLength>,
 }
 
-impl FromXml for RecordingRef {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
-        Ok(RecordingRef {
-            mbid: reader.read(".//@id")?,
-            title: reader.read(".//mb:title/text()")?,
-            length: crate::entities::helper::read_mb_duration(reader, ".//mb:length/text()")?,
-        })
+from_xml_paths! {
+    RecordingRef at ".//" {
+        mbid: "@id",
+        title: "mb:title/text()",
+        length: "mb:length/text()",
     }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ReleaseRef {
     pub mbid: Mbid,
     pub title: String,
     pub date: Option<PartialDate>,
     pub status: Option<ReleaseStatus>,
-    pub country: Option<String>,
+    pub country: Option<Country>,
+    /// Formats of the mediums, e.g. `CD`, only listed if the mediums were
+    /// requested.
+    pub formats: Vec<String>,
 }
 
-impl FromXml for ReleaseRef {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
-        Ok(ReleaseRef {
-            mbid: reader.read(".//@id")?,
-            title: reader.read(".//mb:title/text()")?,
-            date: reader.read(".//mb:date/text()")?,
-            status: reader.read(".//mb:status/text()")?,
-            country: reader.read(".//mb:country/text()")?,
-        })
+from_xml_paths! {
+    ReleaseRef at ".//" {
+        mbid: "@id",
+        title: "mb:title/text()",
+        date: "mb:date/text()",
+        status: "mb:status/text()",
+        country: "mb:country/text()",
+        formats: "mb:medium-list/mb:medium/mb:format/text()",
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReleaseGroupRef {
+    pub mbid: Mbid,
+    pub title: String,
+    pub release_type: ReleaseGroupType,
+    pub first_release_date: Option<PartialDate>,
+}
+
+from_xml_paths! {
+    ReleaseGroupRef {
+        mbid: "./@id",
+        title: "./mb:title/text()",
+        release_type: ".",
+        first_release_date: "./mb:first-release-date/text()",
+    }
+}
+
+impl FromXmlOptional for ReleaseGroupRef {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, xpath_reader::Error> {
+        if reader.anchor_nodeset().size() < 1 {
+            Ok(None)
+        } else {
+            Ok(Some(ReleaseGroupRef::from_xml(reader)?))
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WorkRef {
+    pub mbid: Mbid,
+    pub title: String,
+}
+
+from_xml_paths! {
+    WorkRef at "./" {
+        mbid: "@id",
+        title: "mb:title/text()",
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EventRef {
+    pub mbid: Mbid,
+    pub name: String,
+    pub event_type: Option<EventType>,
+    pub begin_date: Option<PartialDate>,
+    pub end_date: Option<PartialDate>,
+}
+
+from_xml_paths! {
+    EventRef at "./" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        event_type: "@type",
+        begin_date: "mb:life-span/mb:begin/text()",
+        end_date: "mb:life-span/mb:end/text()",
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PlaceRef {
+    pub mbid: Mbid,
+    pub name: String,
+    pub place_type: Option<PlaceType>,
+}
+
+from_xml_paths! {
+    PlaceRef at "./" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        place_type: "@type",
+    }
+}
+
+#[cfg(feature = "streaming")]
+mod streaming {
+    use super::*;
+    use crate::streaming::{parse, require, Node};
+
+    impl AreaRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(AreaRef {
+                mbid: require(node.descendant_attribute("id"), "area id")?,
+                name: require(node.descendant_text("name"), "area name")?,
+                sort_name: require(node.descendant_text("sort-name"), "area sort name")?,
+                iso_3166: parse(node.descendant_text("iso-3166-1-code"))?,
+            })
+        }
+    }
+
+    impl ArtistRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(ArtistRef {
+                mbid: require(node.descendant_attribute("id"), "artist id")?,
+                name: require(node.descendant_text("name"), "artist name")?,
+                sort_name: require(node.descendant_text("sort-name"), "artist sort name")?,
+            })
+        }
+    }
+
+    impl ArtistCredit {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            let artist = match node.child("artist") {
+                Some(artist) => ArtistRef::from_node(artist)?,
+                None => return Err(Error::parse_error("Missing credited artist.")),
+            };
+            Ok(ArtistCredit {
+                name: node
+                    .text_at(&["name"])
+                    .map(String::from)
+                    .unwrap_or_else(|| artist.name.clone()),
+                join_phrase: node.attribute("joinphrase").unwrap_or("").to_string(),
+                artist: artist,
+            })
+        }
+    }
+
+    impl LabelRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(LabelRef {
+                mbid: require(node.descendant_attribute("id"), "label id")?,
+                name: require(node.descendant_text("name"), "label name")?,
+                sort_name: require(node.descendant_text("sort-name"), "label sort name")?,
+                label_code: node.descendant_text("label-code").map(String::from),
+            })
+        }
+    }
+
+    impl RecordingRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(RecordingRef {
+                mbid: require(node.descendant_attribute("id"), "recording id")?,
+                title: require(node.descendant_text("title"), "recording title")?,
+                length: parse(node.descendant_text("length"))?,
+            })
+        }
+    }
+
+    impl ReleaseRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(ReleaseRef {
+                mbid: require(node.descendant_attribute("id"), "release id")?,
+                title: require(node.descendant_text("title"), "release title")?,
+                date: parse(node.descendant_text("date"))?,
+                status: parse(node.descendant_text("status"))?,
+                country: parse(node.descendant_text("country"))?,
+                formats: match node.descendant("medium-list") {
+                    Some(list) => list
+                        .children("medium")
+                        .filter_map(|m| m.text_at(&["format"]))
+                        .map(String::from)
+                        .collect(),
+                    None => Vec::new(),
+                },
+            })
+        }
+    }
+
+    impl ReleaseGroupRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(ReleaseGroupRef {
+                mbid: require(node.attribute("id"), "release group id")?,
+                title: require(node.text_at(&["title"]), "release group title")?,
+                release_type: ReleaseGroupType::from_node(node)?,
+                first_release_date: parse(node.text_at(&["first-release-date"]))?,
+            })
+        }
+    }
+
+    impl WorkRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(WorkRef {
+                mbid: require(node.attribute("id"), "work id")?,
+                title: require(node.text_at(&["title"]), "work title")?,
+            })
+        }
+    }
+
+    impl EventRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(EventRef {
+                mbid: require(node.attribute("id"), "event id")?,
+                name: require(node.text_at(&["name"]), "event name")?,
+                event_type: parse(node.attribute("type"))?,
+                begin_date: parse(node.text_at(&["life-span", "begin"]))?,
+                end_date: parse(node.text_at(&["life-span", "end"]))?,
+            })
+        }
+    }
+
+    impl PlaceRef {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(PlaceRef {
+                mbid: require(node.attribute("id"), "place id")?,
+                name: require(node.text_at(&["name"]), "place name")?,
+                place_type: parse(node.attribute("type"))?,
+            })
+        }
     }
 }
 
@@ -142,31 +394,127 @@ macro_rules! ref_fetch_full
     }
 }
 
-macro_rules! ref_fetch_full_old
-{
-    ($($ref:ty, $full:ty);+)
-=>
-    {
-        $(
-            impl FetchFullOld for $ref {
-                type Full = $full;
-
-                fn fetch_full(&self, client: &mut Client) -> Result<Self::Full, Error>
-                {
-                    client.get_by_mbid_old(&self.mbid)
-                }
-            }
-        )+
-    }
-}
-
 ref_fetch_full!(
     AreaRef, crate::entities::Area, ();
     ArtistRef, crate::entities::Artist, crate::entities::ArtistOptions;
-    ReleaseRef, crate::entities::Release, crate::entities::ReleaseOptions
+    EventRef, crate::entities::Event, crate::entities::EventOptions;
+    LabelRef, crate::entities::Label, crate::entities::LabelOptions;
+    PlaceRef, crate::entities::Place, crate::entities::PlaceOptions;
+    RecordingRef, crate::entities::Recording, crate::entities::RecordingOptions;
+    ReleaseRef, crate::entities::Release, crate::entities::ReleaseOptions;
+    ReleaseGroupRef, crate::entities::ReleaseGroup, crate::entities::ReleaseGroupOptions;
+    WorkRef, crate::entities::Work, crate::entities::WorkOptions
 );
 
-ref_fetch_full_old!(
-    LabelRef, crate::entities::Label;
-    RecordingRef, crate::entities::Recording
-);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::entities::{OnRequest, ReleaseGroupPrimaryType, Work, WorkOptions};
+    use crate::util::test_utils::{response, FnTransport};
+
+    /// Read the reference at `path` from a document containing `body`.
+    fn read_ref<T: FromXml>(path: &str, body: &str) -> T {
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#">{}</metadata>"#,
+            body
+        );
+        let context = crate::util::musicbrainz_context();
+        let reader = Reader::from_str(xml.as_str(), Some(&context)).unwrap();
+        reader.read(path).unwrap()
+    }
+
+    #[test]
+    fn release_group_ref() {
+        let rg: ReleaseGroupRef = read_ref(
+            "//mb:release-group",
+            r#"<release-group id="b1392450-e666-3926-a536-22c65f834433" type="Album"><title>OK Computer</title><first-release-date>1997-05-21</first-release-date><primary-type>Album</primary-type></release-group>"#,
+        );
+        assert_eq!(rg.mbid.to_string(), "b1392450-e666-3926-a536-22c65f834433");
+        assert_eq!(rg.title, "OK Computer".to_string());
+        assert_eq!(
+            rg.release_type.primary,
+            Some(ReleaseGroupPrimaryType::Album)
+        );
+        assert_eq!(
+            rg.first_release_date,
+            Some(PartialDate::new(Some(1997), Some(5), Some(21)))
+        );
+    }
+
+    #[test]
+    fn work_ref() {
+        let work: WorkRef = read_ref(
+            "//mb:work",
+            r#"<work id="9ab2c2a1-ad1a-3f0d-a1b4-0cd1f2d3ab9f"><title>Paranoid Android</title></work>"#,
+        );
+        assert_eq!(
+            work.mbid.to_string(),
+            "9ab2c2a1-ad1a-3f0d-a1b4-0cd1f2d3ab9f"
+        );
+        assert_eq!(work.title, "Paranoid Android".to_string());
+    }
+
+    #[test]
+    fn event_ref() {
+        let event: EventRef = read_ref(
+            "//mb:event",
+            r#"<event id="a24cb8e3-0a6f-4b3c-8d5e-3b6d6c3e1f5d" type="Festival"><name>Fuji Rock Festival 2016</name><life-span><begin>2016-07-22</begin><end>2016-07-24</end></life-span></event>"#,
+        );
+        assert_eq!(event.name, "Fuji Rock Festival 2016".to_string());
+        assert_eq!(event.event_type, Some(EventType::Festival));
+        assert_eq!(
+            event.begin_date,
+            Some(PartialDate::new(Some(2016), Some(7), Some(22)))
+        );
+        assert_eq!(
+            event.end_date,
+            Some(PartialDate::new(Some(2016), Some(7), Some(24)))
+        );
+
+        let event: EventRef = read_ref(
+            "//mb:event",
+            r#"<event id="a24cb8e3-0a6f-4b3c-8d5e-3b6d6c3e1f5d"><name>Unnamed</name></event>"#,
+        );
+        assert_eq!(event.event_type, None);
+        assert_eq!(event.begin_date, None);
+    }
+
+    #[test]
+    fn place_ref() {
+        let place: PlaceRef = read_ref(
+            "//mb:place",
+            r#"<place id="bea135c0-a32e-49be-85fd-9234c73fa0a8" type="Studio"><name>Abbey Road Studios</name></place>"#,
+        );
+        assert_eq!(
+            place.mbid.to_string(),
+            "bea135c0-a32e-49be-85fd-9234c73fa0a8"
+        );
+        assert_eq!(place.name, "Abbey Road Studios".to_string());
+        assert_eq!(place.place_type, Some(PlaceType::Studio));
+    }
+
+    #[test]
+    fn fetch_full_with_options() {
+        const WORK: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><work id="9ab2c2a1-ad1a-3f0d-a1b4-0cd1f2d3ab9f"><title>Paranoid Android</title><annotation><text>Written in three parts.</text></annotation></work></metadata>"#;
+        let mut client = Client::with_transport(
+            crate::util::test_utils::config().build().unwrap(),
+            FnTransport(|request: &HttpRequest| -> Result<HttpResponse, Error> {
+                assert_eq!(request.url.query(), Some("inc=annotation+genres"));
+                Ok(response(200, WORK))
+            }),
+        );
+        let work_ref = WorkRef {
+            mbid: "9ab2c2a1-ad1a-3f0d-a1b4-0cd1f2d3ab9f".parse().unwrap(),
+            title: "Paranoid Android".to_string(),
+        };
+
+        let work: Work =
+            FetchFull::fetch_full(&work_ref, &mut client, WorkOptions::for_mirror()).unwrap();
+        assert_eq!(work.title(), "Paranoid Android");
+        assert_eq!(
+            work.annotation(),
+            OnRequest::Some(&"Written in three parts.".to_string())
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Relationships between entities, as requested with the relationship
+//! includes like `Include::UrlRels`, and helpers for relationships which are
+//! limited to a period of time, e.g. the membership of an artist in a band.
+
+use crate::entities::{AreaRef, ArtistRef, EventRef, LabelRef, Mbid, PartialDate, PlaceRef,
+                      RecordingRef, ReleaseGroupRef, ReleaseRef, WorkRef};
+
+use std::cmp::Ordering;
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
+
+/// An entity along with its relationships, as returned by
+/// `Client::get_by_mbid_with_rels`.
+#[derive(Clone, Debug)]
+pub struct Entity<E> {
+    /// The actual data of the entity.
+    pub data: E,
+
+    /// The relationships of the entity which were requested.
+    pub rels: Vec<Relation>,
+}
+
+impl<E> Entity<E> {
+    /// The relationships of the type `relation_type`, e.g. `member of band`.
+    pub fn rels_of_type<'a>(
+        &'a self,
+        relation_type: &'a str,
+    ) -> impl Iterator<Item = &'a Relation> {
+        self.rels
+            .iter()
+            .filter(move |r| r.relation_type == relation_type)
+    }
+}
+
+/// A relationship of an entity to another entity or to an URL.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Relation {
+    /// The name of the relationship type, e.g. `member of band`.
+    pub relation_type: String,
+
+    /// The MBID of the relationship type.
+    pub type_id: Option<Mbid>,
+
+    /// Whether the relationship points from the entity to `target` or the
+    /// other way around.
+    pub direction: RelationDirection,
+
+    /// The other end of the relationship.
+    pub target: RelationTarget,
+
+    /// Relationships of the target, e.g. the composers of a work, only
+    /// listed for the works of recordings if work-level relationships were
+    /// requested.
+    pub target_rels: Vec<Relation>,
+
+    /// Attributes of the relationship, e.g. the instruments played by a
+    /// member of a band.
+    pub attributes: Vec<String>,
+
+    /// The date the relationship started.
+    pub begin_date: Option<PartialDate>,
+
+    /// The date the relationship ended.
+    pub end_date: Option<PartialDate>,
+
+    /// Whether the relationship has ended, even if the exact end date is not
+    /// known.
+    pub ended: bool,
+}
+
+enum_mb_xml_optional! {
+    /// Direction of a relationship, relative to the entity it was fetched
+    /// with.
+    pub enum RelationDirection {
+        var Forward = "forward",
+        var Backward = "backward",
+    }
+}
+
+
+/// The other end of a relationship.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum RelationTarget {
+    Area(AreaRef),
+    Artist(ArtistRef),
+    Event(EventRef),
+    Label(LabelRef),
+    Place(PlaceRef),
+    Recording(RecordingRef),
+    Release(ReleaseRef),
+    ReleaseGroup(ReleaseGroupRef),
+    Work(WorkRef),
+    Url(String),
+    /// An entity without a reference type, e.g. a series or an instrument.
+    Other {
+        /// The type of the entity as named by the API, e.g. `series`.
+        target_type: String,
+        mbid: Option<Mbid>,
+    },
+}
+
+impl RelationTarget {
+    /// The MBID of the target, `None` for URLs.
+    pub fn mbid(&self) -> Option<&Mbid> {
+        match *self {
+            RelationTarget::Area(ref r) => Some(&r.mbid),
+            RelationTarget::Artist(ref r) => Some(&r.mbid),
+            RelationTarget::Event(ref r) => Some(&r.mbid),
+            RelationTarget::Label(ref r) => Some(&r.mbid),
+            RelationTarget::Place(ref r) => Some(&r.mbid),
+            RelationTarget::Recording(ref r) => Some(&r.mbid),
+            RelationTarget::Release(ref r) => Some(&r.mbid),
+            RelationTarget::ReleaseGroup(ref r) => Some(&r.mbid),
+            RelationTarget::Work(ref r) => Some(&r.mbid),
+            RelationTarget::Url(_) => None,
+            RelationTarget::Other { ref mbid, .. } => mbid.as_ref(),
+        }
+    }
+}
+
+impl FromXml for Relation {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        let target_type: String = reader.read("../@target-type")?;
+        let direction: Option<RelationDirection> = reader.read("./mb:direction/text()")?;
+        let ended: Option<String> = reader.read("./mb:ended/text()")?;
+
+        let area: Option<AreaRef> = match target_type.as_str() {
+            "area" => reader.read("./mb:area")?,
+            _ => None,
+        };
+        let target = match (target_type.as_str(), area) {
+            (_, Some(area)) => RelationTarget::Area(area),
+            ("artist", _) => RelationTarget::Artist(reader.read("./mb:artist")?),
+            ("event", _) => RelationTarget::Event(reader.read("./mb:event")?),
+            ("label", _) => RelationTarget::Label(reader.read("./mb:label")?),
+            ("place", _) => RelationTarget::Place(reader.read("./mb:place")?),
+            ("recording", _) => RelationTarget::Recording(reader.read("./mb:recording")?),
+            ("release", _) => RelationTarget::Release(reader.read("./mb:release")?),
+            ("release_group", _) => {
+                RelationTarget::ReleaseGroup(reader.read("./mb:release-group")?)
+            }
+            ("work", _) => RelationTarget::Work(reader.read("./mb:work")?),
+            ("url", _) => RelationTarget::Url(reader.read("./mb:target/text()")?),
+            _ => RelationTarget::Other {
+                target_type: target_type.clone(),
+                mbid: reader.read("./mb:target/text()")?,
+            },
+        };
+
+        Ok(Relation {
+            relation_type: reader.read("./@type")?,
+            type_id: reader.read("./@type-id")?,
+            direction: direction.unwrap_or(RelationDirection::Forward),
+            target,
+            target_rels: reader.read("./*/mb:relation-list/mb:relation")?,
+            attributes: reader.read("./mb:attribute-list/mb:attribute/text()")?,
+            begin_date: reader.read("./mb:begin/text()")?,
+            end_date: reader.read("./mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+        })
+    }
+}
+
+impl DatedRelation for Relation {
+    fn begin_date(&self) -> Option<&PartialDate> {
+        self.begin_date.as_ref()
+    }
+
+    fn end_date(&self) -> Option<&PartialDate> {
+        self.end_date.as_ref()
+    }
+
+    fn ended(&self) -> bool {
+        self.ended
+    }
+}
+
+/// A relationship with an optional begin and end date.
+///
+/// Dates are compared with the precision of the less precise one, so a
+/// relationship which began in `1975` is active on `1975-01-01` as well as on
+/// `1975-12-31`. A missing begin date means the relationship was active since
+/// the beginning, a missing end date means it is still active. If it is marked
+/// as ended at an unknown date instead, it is only known to have been active
+/// on its begin date.
+pub trait DatedRelation {
+    /// The date the relationship started.
+    fn begin_date(&self) -> Option<&PartialDate>;
+
+    /// The date the relationship ended.
+    fn end_date(&self) -> Option<&PartialDate>;
+
+    /// Whether the relationship has ended, even if the exact end date is not
+    /// known.
+    fn ended(&self) -> bool;
+
+    /// Whether the relationship was active on `date`.
+    fn active_at(&self, date: &PartialDate) -> bool {
+        self.active_during(date, date)
+    }
+
+    /// Whether the relationship was active at any time between `from` and
+    /// `to`, both inclusive.
+    fn active_during(&self, from: &PartialDate, to: &PartialDate) -> bool {
+        let began = self
+            .begin_date()
+            .map_or(true, |begin| begin.cmp_coarse(to) != Ordering::Greater);
+        let not_ended = match self.end_date() {
+            Some(end) => end.cmp_coarse(from) != Ordering::Less,
+            None if self.ended() => self
+                .begin_date()
+                .map_or(false, |begin| begin.cmp_coarse(from) != Ordering::Less),
+            None => true,
+        };
+        began && not_ended
+    }
+
+    /// Whether the relationship is still active.
+    fn is_current(&self) -> bool {
+        !self.ended() && self.end_date().is_none()
+    }
+}
+
+/// Filters for collections of relationships, see `DatedRelation`.
+pub trait DatedRelations<R: DatedRelation> {
+    /// The relationships which were active on `date`.
+    fn active_at(&self, date: &PartialDate) -> Vec<&R>;
+
+    /// The relationships which were active at any time between `from` and
+    /// `to`, both inclusive.
+    fn active_during(&self, from: &PartialDate, to: &PartialDate) -> Vec<&R>;
+
+    /// The relationships which are still active.
+    fn current(&self) -> Vec<&R>;
+}
+
+impl<R: DatedRelation> DatedRelations<R> for [R] {
+    fn active_at(&self, date: &PartialDate) -> Vec<&R> {
+        self.iter().filter(|r| r.active_at(date)).collect()
+    }
+
+    fn active_during(&self, from: &PartialDate, to: &PartialDate) -> Vec<&R> {
+        self.iter().filter(|r| r.active_during(from, to)).collect()
+    }
+
+    fn current(&self) -> Vec<&R> {
+        self.iter().filter(|r| r.is_current()).collect()
+    }
+}
+
+#[cfg(feature = "streaming")]
+mod streaming {
+    use super::*;
+    use crate::streaming::{parse, require, Node};
+    use crate::Error;
+
+    /// Parse the relationships listed in the relation lists of `node`.
+    pub(crate) fn relations_from_node(node: &Node) -> Result<Vec<Relation>, Error> {
+        node.children("relation-list")
+            .flat_map(|list| {
+                let target_type = list.attribute("target-type").unwrap_or("");
+                list.children("relation")
+                    .map(move |r| Relation::from_node(r, target_type))
+            })
+            .collect()
+    }
+
+    impl Relation {
+        fn from_node(relation: &Node, target_type: &str) -> Result<Self, Error> {
+            let child = |name: &str| {
+                relation
+                    .child(name)
+                    .ok_or_else(|| Error::parse_error(format!("Missing relation {}.", name)))
+            };
+            let target = match (target_type, relation.child("area")) {
+                ("area", Some(area)) => RelationTarget::Area(AreaRef::from_node(area)?),
+                ("artist", _) => RelationTarget::Artist(ArtistRef::from_node(child("artist")?)?),
+                ("event", _) => RelationTarget::Event(EventRef::from_node(child("event")?)?),
+                ("label", _) => RelationTarget::Label(LabelRef::from_node(child("label")?)?),
+                ("place", _) => RelationTarget::Place(PlaceRef::from_node(child("place")?)?),
+                ("recording", _) => {
+                    RelationTarget::Recording(RecordingRef::from_node(child("recording")?)?)
+                }
+                ("release", _) => {
+                    RelationTarget::Release(ReleaseRef::from_node(child("release")?)?)
+                }
+                ("release_group", _) => RelationTarget::ReleaseGroup(ReleaseGroupRef::from_node(
+                    child("release-group")?,
+                )?),
+                ("work", _) => RelationTarget::Work(WorkRef::from_node(child("work")?)?),
+                ("url", _) => RelationTarget::Url(child("target")?.text.clone()),
+                _ => RelationTarget::Other {
+                    target_type: target_type.to_string(),
+                    mbid: parse(relation.text_at(&["target"]))?,
+                },
+            };
+
+            Ok(Relation {
+                relation_type: require(relation.attribute("type"), "relation type")?,
+                type_id: parse(relation.attribute("type-id"))?,
+                direction: parse(relation.text_at(&["direction"]))?
+                    .unwrap_or(RelationDirection::Forward),
+                target,
+                target_rels: match relation
+                    .children
+                    .iter()
+                    .find(|c| c.child("relation-list").is_some())
+                {
+                    Some(node) => relations_from_node(node)?,
+                    None => Vec::new(),
+                },
+                attributes: match relation.child("attribute-list") {
+                    Some(list) => list.children("attribute").map(|a| a.text.clone()).collect(),
+                    None => Vec::new(),
+                },
+                begin_date: parse(relation.text_at(&["begin"]))?,
+                end_date: parse(relation.text_at(&["end"]))?,
+                ended: relation.text_at(&["ended"]) == Some("true"),
+            })
+        }
+    }
+}
+
+#[cfg(feature = "streaming")]
+pub(crate) use self::streaming::relations_from_node;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[derive(Debug, PartialEq)]
+    struct Membership {
+        name: &'static str,
+        begin: Option<PartialDate>,
+        end: Option<PartialDate>,
+        ended: bool,
+    }
+
+    impl DatedRelation for Membership {
+        fn begin_date(&self) -> Option<&PartialDate> {
+            self.begin.as_ref()
+        }
+
+        fn end_date(&self) -> Option<&PartialDate> {
+            self.end.as_ref()
+        }
+
+        fn ended(&self) -> bool {
+            self.ended
+        }
+    }
+
+    fn date(s: &str) -> PartialDate {
+        PartialDate::from_str(s).unwrap()
+    }
+
+    fn member(name: &'static str, begin: Option<&str>, end: Option<&str>) -> Membership {
+        Membership {
+            name,
+            begin: begin.map(date),
+            end: end.map(date),
+            ended: end.is_some(),
+        }
+    }
+
+    /// A member who left the band at an unknown date.
+    fn former_member(name: &'static str, begin: Option<&str>) -> Membership {
+        Membership {
+            name,
+            begin: begin.map(date),
+            end: None,
+            ended: true,
+        }
+    }
+
+    #[test]
+    fn active_members() {
+        let members = vec![
+            member("founder", Some("1970"), Some("1975-03")),
+            member("replacement", Some("1975-04-01"), None),
+            member("unknown", None, None),
+            member("later", Some("1980"), Some("1982")),
+            former_member("left", Some("1972")),
+            former_member("forgotten", None),
+        ];
+        let names = |rels: Vec<&Membership>| rels.iter().map(|m| m.name).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(members.active_at(&date("1975"))),
+            vec!["founder", "replacement", "unknown"]
+        );
+        assert_eq!(
+            names(members.active_at(&date("1975-03-10"))),
+            vec!["founder", "unknown"]
+        );
+        assert_eq!(
+            names(members.active_during(&date("1976"), &date("1980-06"))),
+            vec!["replacement", "unknown", "later"]
+        );
+        assert_eq!(names(members.current()), vec!["replacement", "unknown"]);
+        assert_eq!(
+            names(members.active_at(&date("1972-05"))),
+            vec!["founder", "unknown", "left"]
+        );
+    }
+}
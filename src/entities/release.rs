@@ -1,10 +1,16 @@
 //! Attempt at prototyping the new entity API exemplary for the release entity.
 
-use crate::entities::{Alias, Mbid, PartialDate, Language, Duration};
-use crate::entities::refs::{ArtistRef, LabelRef, RecordingRef};
+use crate::entities::{Alias, Country, Genre, Mbid, PartialDate, Language, Relation,
+                      RelationTarget, ReleaseGroupSecondaryType, Script, Tag, TrackLength,
+                      TrackNumber};
+use crate::entities::refs::{AreaRef, ArtistCredit, ArtistRef, LabelRef, RecordingRef, ReleaseGroupRef, WorkRef};
 use xpath_reader::{FromXml, FromXmlOptional, Reader};
 use crate::client::Request;
-use crate::entities::{OnRequest, Resource};
+use crate::entities::{write_summary, OnRequest, Resource};
+use crate::entities::{include, join_includes, EntityKind, Include};
+use crate::error::ParseWarning;
+
+use std::fmt;
 
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
 pub enum ReleaseComponent {
@@ -40,40 +46,108 @@ enum_mb_xml_optional! {
         /// These don't correspond to a real release and should be linked to the
         /// actual release using the transliteration relationship.
         var PseudoRelease = "Pseudo-Release",
+        unknown Unknown,
+    }
+}
+
+enum_mb_xml_optional! {
+    /// How thoroughly the data of a release was verified by the editors.
+    pub enum ReleaseQuality {
+        /// The release needs major work, e.g. it was imported and never
+        /// reviewed.
+        var Low = "low",
+
+        /// The default quality of all releases.
+        var Normal = "normal",
+
+        /// The release was reviewed thoroughly, changes to it are voted on.
+        var High = "high",
     }
 }
 
 /// A `Release` is any publication of one or more tracks.
-#[derive(Clone, Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Release {
     response: ReleaseResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
     options: ReleaseOptions,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ReleaseResponse {
     mbid: Mbid,
     title: String,
     artists: Vec<ArtistRef>,
+    artist_credit: Vec<ArtistCredit>,
+    artist_rels: Vec<Relation>,
     date: Option<PartialDate>,
-    country: Option<String>,
+    country: Option<Country>,
+    events: Vec<ReleaseEvent>,
     labels: Vec<LabelInfo>,
     barcode: Option<String>,
     status: Option<ReleaseStatus>,
+    status_id: Option<Mbid>,
+    quality: Option<ReleaseQuality>,
     packaging: Option<String>,
+    packaging_id: Option<Mbid>,
     language: Option<Language>,
-    script: Option<String>,
+    script: Option<Script>,
     disambiguation: Option<String>,
     annotation: Option<String>,
+    aliases: Vec<Alias>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
     mediums: Vec<ReleaseMedium>,
+    medium_count: Option<u16>,
+    track_counts: Vec<u32>,
+    release_group: Option<ReleaseGroupRef>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warnings: Vec<ParseWarning>,
 }
 
 #[derive(Clone, Debug)]
 pub struct ReleaseOptions {
+    pub aliases: bool,
     pub annotation: bool,
     pub artists: bool,
     pub recordings: bool,
     pub labels: bool,
+    pub release_group: bool,
+    pub tags: bool,
+    pub genres: bool,
+
+    /// Request the list of mediums with their track counts, but without the
+    /// tracks themselves.
+    pub media: bool,
+
+    /// Request the disc IDs of the mediums, which are only available if
+    /// `recordings` are requested too.
+    pub disc_ids: bool,
+
+    /// Request relationships to artists, e.g. the performers of the
+    /// recordings if `recording_level_rels` are requested too.
+    pub artist_rels: bool,
+
+    /// Request the relationships of the recordings of the tracks, which are
+    /// only available if `recordings` are requested too.
+    ///
+    /// Only relationships requested by the other relationship options are
+    /// listed, see `ReleaseTrack::recording_rels`.
+    pub recording_level_rels: bool,
+
+    /// Request relationships to works, e.g. the works performed in the
+    /// recordings if `recording_level_rels` are requested too.
+    pub work_rels: bool,
+
+    /// Request the relationships of the works performed in the recordings,
+    /// e.g. their composers and lyricists with `artist_rels`.
+    ///
+    /// Only available if `recording_level_rels` and `work_rels` are
+    /// requested too, see `ReleaseTrack::works`.
+    pub work_level_rels: bool,
 }
 
 /// A medium is a collection of multiple `ReleaseTrack`.
@@ -82,6 +156,7 @@ pub struct ReleaseOptions {
 /// as a release with two CDs would have two associated `ReleaseMedium`
 /// instances.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ReleaseMedium {
     /// The medium's position number providing a total order between all
     /// mediums of one `Release`.
@@ -92,12 +167,23 @@ pub struct ReleaseMedium {
     /// TODO: Parse into `ReleaseMediumFormat` enum.
     format: Option<String>,
 
-    /// The tracks stored on this medium.
+    /// The tracks stored on this medium, including data tracks.
     tracks: Vec<ReleaseTrack>,
+
+    /// Hidden track in the pregap before the first track.
+    pregap: Option<ReleaseTrack>,
+
+    /// Disc IDs of CDs matching this medium.
+    disc_ids: Vec<String>,
+
+    /// Tracks which were skipped because they couldn't be parsed.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warnings: Vec<ParseWarning>,
 }
 
 /// Describes a single track, `Releases` consist of multiple `ReleaseTrack`s.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ReleaseTrack {
     /// MBID of the entity in the MusicBrainz database.
     pub mbid: Mbid,
@@ -118,10 +204,22 @@ pub struct ReleaseTrack {
     pub title: String,
 
     /// The length of the track.
-    pub length: Option<Duration>,
+    pub length: Option<TrackLength>,
 
     /// The recording used for the track.
     pub recording: RecordingRef,
+
+    /// The artists credited for the track, empty if the response doesn't
+    /// list them.
+    pub artist_credit: Vec<ArtistCredit>,
+
+    /// Relationships of the recording, only listed if
+    /// `ReleaseOptions::recording_level_rels` were requested.
+    pub recording_rels: Vec<Relation>,
+
+    /// Whether this is a data track, e.g. a video on an enhanced CD, instead
+    /// of an audio track.
+    pub data_track: bool,
 }
 
 /// Lists information about a `Release`.
@@ -129,6 +227,7 @@ pub struct ReleaseTrack {
 /// Note that its both possible to find a `LabelInfo` with only one of `label`
 /// or `cat_num`.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LabelInfo {
     /// A reference to the label issuing the release.
     pub label: Option<LabelRef>,
@@ -137,12 +236,36 @@ pub struct LabelInfo {
     pub catalog_number: Option<String>,
 }
 
+/// The date a `Release` was issued in an area.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReleaseEvent {
+    /// The date the release was issued in the area.
+    pub date: Option<PartialDate>,
+
+    /// The area the release was issued in, usually a country but also
+    /// pseudo-areas like `Europe` (`XE`) or `[Worldwide]` (`XW`).
+    pub area: Option<AreaRef>,
+}
+
+from_xml_paths! {
+    ReleaseEvent at "./" {
+        date: "mb:date/text()",
+        area: "mb:area",
+    }
+}
+
 impl Release {
     /// MBID of the entity in the MusicBrainz database.
     pub fn mbid(&self) -> &Mbid {
         &self.response.mbid
     }
 
+    /// Link to the release on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Release.permalink(self.mbid())
+    }
+
     /// The title of the release.
     pub fn title(&self) -> &String {
         &self.response.title
@@ -154,15 +277,42 @@ impl Release {
     }
 
     /// The country the release was issued in.
-    pub fn country(&self) -> Option<&String> {
+    pub fn country(&self) -> Option<&Country> {
         self.response.country.as_ref()
     }
 
+    /// The area of the release event `country` was taken from, which unlike
+    /// the country code identifies pseudo-areas like `Europe` by their MBID.
+    pub fn country_area(&self) -> Option<&AreaRef> {
+        let country = self.country()?;
+        self.response
+            .events
+            .iter()
+            .filter_map(|e| e.area.as_ref())
+            .find(|a| a.iso_3166.as_ref() == Some(country))
+    }
+
+    /// All dates and areas the release was issued in, in the order the server
+    /// lists them, which isn't necessarily chronological.
+    pub fn release_events(&self) -> &[ReleaseEvent] {
+        self.response.events.as_slice()
+    }
+
     /// Release status of the release.
     pub fn status(&self) -> Option<ReleaseStatus> {
         self.response.status.clone()
     }
 
+    /// MBID of the release status, which unlike its name is never translated.
+    pub fn status_id(&self) -> Option<&Mbid> {
+        self.response.status_id.as_ref()
+    }
+
+    /// The data quality of the release.
+    pub fn quality(&self) -> Option<ReleaseQuality> {
+        self.response.quality
+    }
+
     /// Barcode of the release, if it has one.
     pub fn barcode(&self) -> Option<&String> {
         self.response.barcode.as_ref()
@@ -174,14 +324,19 @@ impl Release {
         self.response.packaging.as_ref()
     }
 
+    /// MBID of the packaging of the release.
+    pub fn packaging_id(&self) -> Option<&Mbid> {
+        self.response.packaging_id.as_ref()
+    }
+
     /// Language of the release. (ISO 639-3 conformant string in DB.)
     pub fn language(&self) -> Option<&Language> {
         self.response.language.as_ref()
     }
 
-    /// Script used to write the track list. (ISO 15924 conformant string in DB.)
-    pub fn script(&self) -> Option<&String> {
-        self.response.script.as_ref()
+    /// Script used to write the track list.
+    pub fn script(&self) -> Option<Script> {
+        self.response.script
     }
 
     /// A disambiguation comment if present, which allows to differentiate this
@@ -195,120 +350,559 @@ impl Release {
         OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
     }
 
+    /// Aliases of the release's title, e.g. transliterations or translations.
+    pub fn aliases(&self) -> OnRequest<&[Alias]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
+
+    /// The tags users attached to the release.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the release.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
     /// The mediums (disks) of the release.
     pub fn mediums(&self) -> OnRequest<&[ReleaseMedium]> {
-        if self.options.recordings {
-            OnRequest::Some(self.response.mediums.as_ref())
-        } else {
-            OnRequest::NotRequested
+        OnRequest::from_value(self.response.mediums.as_ref(), self.options.recordings)
+    }
+
+    /// The medium one of whose disc IDs is `disc_id`, e.g. to find the
+    /// medium of a CD which was looked up by its disc ID.
+    ///
+    /// This is available if both `recordings` and `disc_ids` were requested.
+    pub fn medium_by_disc_id(&self, disc_id: &str) -> OnRequest<&ReleaseMedium> {
+        OnRequest::from_option(
+            self.response
+                .mediums
+                .iter()
+                .find(|m| m.disc_ids.iter().any(|d| d == disc_id)),
+            self.options.recordings && self.options.disc_ids,
+        )
+    }
+
+    /// The number of mediums of the release.
+    ///
+    /// This is available if either `media` or `recordings` were requested.
+    pub fn medium_count(&self) -> OnRequest<u16> {
+        OnRequest::from_option(
+            self.response.medium_count,
+            self.options.media || self.options.recordings,
+        )
+    }
+
+    /// The total number of tracks on all mediums of the release, including
+    /// data tracks but not pregap tracks.
+    ///
+    /// This is available if either `media` or `recordings` were requested.
+    pub fn track_count(&self) -> OnRequest<u32> {
+        match self.medium_count() {
+            OnRequest::Some(_) => OnRequest::Some(self.response.track_counts.iter().sum()),
+            OnRequest::NotAvailable => OnRequest::NotAvailable,
+            OnRequest::NotRequested => OnRequest::NotRequested,
         }
     }
 
     /// The artists that the release is primarily credited to.
     pub fn artists(&self) -> OnRequest<&[ArtistRef]> {
-        if self.options.artists {
-            OnRequest::Some(self.response.artists.as_slice())
-        } else {
-            OnRequest::NotRequested
-        }
+        OnRequest::from_value(self.response.artists.as_slice(), self.options.artists)
+    }
+
+    /// The artist credit of the release, with the names the artists are
+    /// credited with and the phrases joining them.
+    pub fn artist_credit(&self) -> OnRequest<&[ArtistCredit]> {
+        OnRequest::from_value(self.response.artist_credit.as_slice(), self.options.artists)
+    }
+
+    /// Relationships of the release itself to artists, e.g. its producer or
+    /// the designer of its cover art.
+    ///
+    /// Relationships of the recordings are listed with the tracks, see
+    /// `ReleaseTrack::recording_rels`.
+    pub fn artist_rels(&self) -> OnRequest<&[Relation]> {
+        OnRequest::from_value(
+            self.response.artist_rels.as_slice(),
+            self.options.artist_rels,
+        )
     }
 
     /// The labels which issued this release.
     pub fn labels(&self) -> OnRequest<&[LabelInfo]> {
-        if self.options.labels {
-            OnRequest::Some(self.response.labels.as_slice())
-        } else {
-            OnRequest::NotRequested
+        OnRequest::from_value(self.response.labels.as_slice(), self.options.labels)
+    }
+
+    /// The release group this release belongs to.
+    pub fn release_group(&self) -> OnRequest<&ReleaseGroupRef> {
+        OnRequest::from_option(
+            self.response.release_group.as_ref(),
+            self.options.release_group,
+        )
+    }
+
+    /// Whether the release is a compilation, i.e. it is credited to `Various
+    /// Artists` or its release group has the secondary type `Compilation`.
+    ///
+    /// Requires `artists` or `release_group` to be requested. If only one of
+    /// them was requested and it doesn't indicate a compilation, the release
+    /// might still be one, so `NotRequested` is returned.
+    pub fn is_compilation(&self) -> OnRequest<bool> {
+        let various_artists = self
+            .artists()
+            .into_option()
+            .map(|artists| artists.iter().any(|a| a.mbid.is_various_artists()));
+        let compilation_type = match self.release_group() {
+            OnRequest::Some(group) => Some(
+                group
+                    .release_type
+                    .secondary
+                    .contains(&ReleaseGroupSecondaryType::Compilation),
+            ),
+            OnRequest::NotAvailable => Some(false),
+            OnRequest::NotRequested => None,
+        };
+
+        match (various_artists, compilation_type) {
+            (Some(true), _) | (_, Some(true)) => OnRequest::Some(true),
+            (Some(false), Some(false)) => OnRequest::Some(false),
+            _ => OnRequest::NotRequested,
+        }
+    }
+
+    /// Parts of the release which were skipped because they couldn't be
+    /// parsed, only possible if the client is configured to be lenient.
+    pub fn parse_warnings(&self) -> &[ParseWarning] {
+        self.response.warnings.as_slice()
+    }
+}
+
+impl fmt::Debug for Release {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Release")
+            .field("mbid", self.mbid())
+            .field("title", self.title())
+            .field("date", &self.date())
+            .field("country", &self.country())
+            .field("release_events", &self.release_events())
+            .field("status", &self.status())
+            .field("quality", &self.quality())
+            .field("barcode", &self.barcode())
+            .field("packaging", &self.packaging())
+            .field("language", &self.language())
+            .field("script", &self.script())
+            .field("disambiguation", &self.disambiguation())
+            .field("annotation", &self.annotation())
+            .field("aliases", &self.aliases())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("artists", &self.artists())
+            .field("artist_credit", &self.artist_credit())
+            .field("artist_rels", &self.artist_rels())
+            .field("labels", &self.labels())
+            .field("release_group", &self.release_group())
+            .field("mediums", &self.mediums())
+            .finish()
+    }
+}
+
+impl fmt::Display for Release {
+    /// Formats the release as e.g. `OK Computer (1997, GB)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_summary(
+            f,
+            self.title(),
+            &[
+                self.date().and_then(|d| d.year()).map(|y| y.to_string()),
+                self.country().map(|c| c.to_string()),
+            ],
+        )
+    }
+}
+
+impl ReleaseMedium {
+    /// The medium's position on the release, starting at 1.
+    pub fn position(&self) -> u16 {
+        self.position
+    }
+
+    /// The format of the medium, e.g. `CD`.
+    pub fn format(&self) -> Option<&String> {
+        self.format.as_ref()
+    }
+
+    /// The tracks on this medium.
+    ///
+    /// Data tracks are listed after the audio tracks, the pregap is not
+    /// included.
+    pub fn tracks(&self) -> &[ReleaseTrack] {
+        self.tracks.as_slice()
+    }
+
+    /// The hidden track in the pregap of the medium, if there is one.
+    ///
+    /// On CDs this is stored before the first track and has position 0.
+    pub fn pregap(&self) -> Option<&ReleaseTrack> {
+        self.pregap.as_ref()
+    }
+
+    /// The disc IDs of CDs matching this medium, calculated from their table
+    /// of contents.
+    pub fn disc_ids(&self) -> &[String] {
+        self.disc_ids.as_slice()
+    }
+
+    /// The audio tracks on this medium, i.e. without data tracks and the
+    /// pregap.
+    pub fn audio_tracks(&self) -> impl Iterator<Item = &ReleaseTrack> {
+        self.tracks.iter().filter(|t| !t.data_track)
+    }
+
+    /// Tracks of this medium which were skipped because they couldn't be
+    /// parsed.
+    pub fn parse_warnings(&self) -> &[ParseWarning] {
+        self.warnings.as_slice()
+    }
+
+    /// Split the results of parsing the tracks, which are listed as the
+    /// tracks followed by the data tracks, into the tracks and warnings about
+    /// the ones which failed.
+    fn collect_tracks<I>(position: u16, results: I) -> (Vec<ReleaseTrack>, Vec<ParseWarning>)
+    where
+        I: IntoIterator<Item = Result<ReleaseTrack, String>>,
+    {
+        let mut tracks = Vec::new();
+        let mut warnings = Vec::new();
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(track) => tracks.push(track),
+                Err(message) => warnings.push(ParseWarning {
+                    location: format!("medium {}, track {}", position, index + 1),
+                    message,
+                }),
+            }
         }
+        (tracks, warnings)
     }
 }
 
 impl ReleaseOptions {
-    /// Request everything from the server.
+    /// Request everything from the server, except for relationships.
     pub fn everything() -> Self {
         ReleaseOptions {
+            aliases: true,
             annotation: true,
             artists: true,
             recordings: true,
             labels: true,
+            release_group: true,
+            tags: true,
+            genres: true,
+            media: true,
+            disc_ids: true,
+            artist_rels: false,
+            recording_level_rels: false,
+            work_rels: false,
+            work_level_rels: false,
         }
     }
 
     /// Only request the minimal amount of fields.
     pub fn minimal() -> Self {
         ReleaseOptions {
+            aliases: false,
             annotation: false,
             artists: false,
             recordings: false,
             labels: false,
+            release_group: false,
+            tags: false,
+            genres: false,
+            media: false,
+            disc_ids: false,
+            artist_rels: false,
+            recording_level_rels: false,
+            work_rels: false,
+            work_level_rels: false,
         }
     }
-}
 
-impl Resource for Release {
-    type Options = ReleaseOptions;
-    type Response = ReleaseResponse;
+    /// Request what is needed to tag files: the artists and the tracks with
+    /// their recordings.
+    pub fn for_tagging() -> Self {
+        ReleaseOptions {
+            artists: true,
+            recordings: true,
+            ..ReleaseOptions::minimal()
+        }
+    }
 
-    const NAME: &'static str = "release";
+    /// Request what is usually shown on a release page: the artists, labels,
+    /// release group and tracks.
+    pub fn for_display() -> Self {
+        ReleaseOptions {
+            artists: true,
+            labels: true,
+            recordings: true,
+            release_group: true,
+            ..ReleaseOptions::minimal()
+        }
+    }
 
-    fn request(options: &Self::Options) -> Request {
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        ReleaseOptions {
+            annotation: true,
+            genres: true,
+            ..ReleaseOptions::minimal()
+        }
+    }
+
+    /// Request the tracks along with the performers of their recordings and
+    /// the composers and lyricists of the performed works.
+    ///
+    /// This gets the credits of all tracks in one request, but the response
+    /// can be large for releases with many tracks.
+    pub fn for_credits() -> Self {
+        ReleaseOptions {
+            artists: true,
+            recordings: true,
+            artist_rels: true,
+            recording_level_rels: true,
+            work_rels: true,
+            work_level_rels: true,
+            ..ReleaseOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for releases.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = ReleaseOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Artists => options.artists = true,
+                Include::DiscIds => options.disc_ids = true,
+                Include::Genres => options.genres = true,
+                Include::Labels => options.labels = true,
+                Include::Media => options.media = true,
+                Include::Recordings => options.recordings = true,
+                Include::ReleaseGroups => options.release_group = true,
+                Include::Tags => options.tags = true,
+                Include::ArtistRels => options.artist_rels = true,
+                Include::RecordingLevelRels => options.recording_level_rels = true,
+                Include::WorkRels => options.work_rels = true,
+                Include::WorkLevelRels => options.work_level_rels = true,
+                _ => return Err(include::unsupported(EntityKind::Release, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
         let mut includes = Vec::new();
 
-        if options.annotation {
-            includes.push("annotation");
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.artist_rels {
+            includes.push(Include::ArtistRels);
+        }
+        if self.artists {
+            includes.push(Include::Artists);
+        }
+        if self.disc_ids {
+            includes.push(Include::DiscIds);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
         }
-        if options.artists {
-            includes.push("artists");
+        if self.labels {
+            includes.push(Include::Labels);
         }
-        if options.labels {
-            includes.push("labels");
+        if self.media {
+            includes.push(Include::Media);
         }
-        if options.recordings {
-            includes.push("recordings");
+        if self.recording_level_rels {
+            includes.push(Include::RecordingLevelRels);
         }
+        if self.recordings {
+            includes.push(Include::Recordings);
+        }
+        if self.release_group {
+            includes.push(Include::ReleaseGroups);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.work_level_rels {
+            includes.push(Include::WorkLevelRels);
+        }
+        if self.work_rels {
+            includes.push(Include::WorkRels);
+        }
+        includes
+    }
+}
 
+impl Resource for Release {
+    type Options = ReleaseOptions;
+    type Response = ReleaseResponse;
+
+    const NAME: &'static str = "release";
+
+    fn request(options: &Self::Options) -> Request {
         Request {
             name: "release".into(),
-            include: includes.join("+"),
+            include: join_includes(&options.includes()),
         }
     }
 
+    #[cfg(feature = "diagnostics")]
+    const PARSED_ELEMENTS: Option<&'static [&'static str]> = Some(&[
+        "title",
+        "status",
+        "quality",
+        "packaging",
+        "text-representation",
+        "artist-credit",
+        "date",
+        "country",
+        "release-event-list",
+        "barcode",
+        "label-info-list",
+        "medium-list",
+        "release-group",
+        "annotation",
+        "alias-list",
+        "disambiguation",
+        "tag-list",
+        "genre-list",
+    ]);
+
     fn from_response(response: Self::Response, options: Self::Options) -> Self {
         Release { response, options }
     }
+
+    fn parse_warnings(&self) -> &[ParseWarning] {
+        Release::parse_warnings(self)
+    }
+
+    #[cfg(feature = "streaming")]
+    fn parse_streaming(
+        body: &str,
+        _: &Self::Options,
+    ) -> Option<Result<Self::Response, crate::Error>> {
+        Some(streaming::parse_release(body))
+    }
 }
 
 impl FromXml for ReleaseResponse {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        let mediums: Vec<ReleaseMedium> = reader.read(".//mb:release/mb:medium-list/mb:medium")?;
+        let warnings = mediums.iter().flat_map(|m| m.warnings.iter().cloned()).collect();
+
         Ok(ReleaseResponse {
+            aliases: reader.read(".//mb:release/mb:alias-list/mb:alias")?,
             annotation: reader.read(".//mb:release/mb:annotation/mb:text/text()")?,
             artists: reader.read(".//mb:release/mb:artist-credit/mb:name-credit")?,
+            artist_credit: reader.read(".//mb:release/mb:artist-credit/mb:name-credit")?,
+            artist_rels: reader.read(
+                "//mb:metadata/mb:release/mb:relation-list[@target-type='artist']/mb:relation",
+            )?,
             barcode: reader.read(".//mb:release/mb:barcode/text()")?,
             country: reader.read(".//mb:release/mb:country/text()")?,
             date: reader.read(".//mb:release/mb:date/text()")?,
             disambiguation: reader.read(".//mb:release/mb:disambiguation/text()")?,
+            events: reader.read(".//mb:release/mb:release-event-list/mb:release-event")?,
+            genres: reader.read("//mb:metadata/mb:release/mb:genre-list/mb:genre")?,
             labels: reader.read(".//mb:release/mb:label-info-list/mb:label-info")?,
             language: reader.read(".//mb:release/mb:text-representation/mb:language/text()")?,
             mbid: reader.read(".//mb:release/@id")?,
-            mediums: reader.read(".//mb:release/mb:medium-list/mb:medium")?,
+            mediums,
+            medium_count: reader.read(".//mb:release/mb:medium-list/@count")?,
             packaging: reader.read(".//mb:release/mb:packaging/text()")?,
+            packaging_id: reader.read(".//mb:release/mb:packaging/@id")?,
+            release_group: reader.read(".//mb:release/mb:release-group")?,
             script: reader.read(".//mb:release/mb:text-representation/mb:script/text()")?,
             status: reader.read(".//mb:release/mb:status/text()")?,
+            status_id: reader.read(".//mb:release/mb:status/@id")?,
+            tags: reader.read("//mb:metadata/mb:release/mb:tag-list/mb:tag")?,
+            quality: reader.read(".//mb:release/mb:quality/text()")?,
             title: reader.read(".//mb:release/mb:title/text()")?,
+            track_counts: reader.read(
+                ".//mb:release/mb:medium-list/mb:medium/mb:track-list/@count | \
+                 .//mb:release/mb:medium-list/mb:medium/mb:data-track-list/@count",
+            )?,
+            warnings,
         })
     }
 }
 
+/// The result of parsing an element, so a single broken element doesn't fail
+/// the whole list it's contained in.
+struct Parsed<T>(Result<T, String>);
+
+impl<T: FromXml> FromXml for Parsed<T> {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        Ok(Parsed(T::from_xml(reader).map_err(|e| e.to_string())))
+    }
+}
+
 impl FromXml for ReleaseMedium {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        let position = reader.read(".//mb:position/text()")?;
+        let tracks: Vec<Parsed<ReleaseTrack>> = reader.read(".//mb:track-list/mb:track")?;
+        let data_tracks: Vec<Parsed<ReleaseTrack>> =
+            reader.read(".//mb:data-track-list/mb:track")?;
+        let results = tracks.into_iter().map(|t| t.0).chain(
+            data_tracks
+                .into_iter()
+                .map(|t| t.0.map(|t| ReleaseTrack { data_track: true, ..t })),
+        );
+        let (tracks, warnings) = ReleaseMedium::collect_tracks(position, results);
+
         Ok(ReleaseMedium {
-            position: reader.read(".//mb:position/text()")?,
+            position,
             format: reader.read(".//mb:format/text()")?,
-            tracks: reader.read(".//mb:track-list/mb:track")?,
+            tracks,
+            pregap: reader.read(".//mb:pregap")?,
+            disc_ids: reader.read(".//mb:disc-list/mb:disc/@id")?,
+            warnings,
         })
     }
 }
 
+impl ReleaseTrack {
+    /// The track number parsed for sorting and comparison.
+    pub fn track_number(&self) -> TrackNumber {
+        TrackNumber::parse(&self.number)
+    }
+
+    /// The works related to the recording, e.g. the performed song, along
+    /// with their relationships like the composers.
+    ///
+    /// Empty unless `ReleaseOptions::recording_level_rels` and `work_rels`
+    /// were requested, the relationships of the works are only listed if
+    /// `work_level_rels` were requested too.
+    pub fn works(&self) -> Vec<(&WorkRef, &[Relation])> {
+        self.recording_rels
+            .iter()
+            .filter_map(|r| match r.target {
+                RelationTarget::Work(ref work) => Some((work, r.target_rels.as_slice())),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
 impl FromXml for ReleaseTrack {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
         Ok(ReleaseTrack {
@@ -316,12 +910,25 @@ impl FromXml for ReleaseTrack {
             position: reader.read(".//mb:position/text()")?,
             number: reader.read(".//mb:number/text()")?,
             title: reader.read(".//mb:title/text()")?,
-            length: crate::entities::helper::read_mb_duration(reader, ".//mb:length/text()")?,
+            length: reader.read(".//mb:length/text()")?,
             recording: reader.read(".//mb:recording")?,
+            artist_credit: reader.read("./mb:artist-credit/mb:name-credit")?,
+            recording_rels: reader.read("./mb:recording/mb:relation-list/mb:relation")?,
+            data_track: false,
         })
     }
 }
 
+impl FromXmlOptional for ReleaseTrack {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, xpath_reader::Error> {
+        if reader.anchor_nodeset().size() < 1 {
+            Ok(None)
+        } else {
+            Ok(Some(ReleaseTrack::from_xml(reader)?))
+        }
+    }
+}
+
 impl FromXml for LabelInfo {
     fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
         Ok(LabelInfo {
@@ -337,15 +944,266 @@ impl FromXml for LabelInfo {
     }
 }
 
+#[cfg(feature = "streaming")]
+mod streaming {
+    use super::*;
+    use crate::entities::relation::relations_from_node;
+    use crate::streaming::{check_response_error, parse, parse_document, require, Node};
+    use crate::Error;
+
+    pub(super) fn parse_release(body: &str) -> Result<ReleaseResponse, Error> {
+        let root = parse_document(body)?;
+        check_response_error(&root)?;
+        let release = root
+            .child("release")
+            .ok_or_else(|| Error::parse_error("Missing release element."))?;
+        ReleaseResponse::from_node(release)
+    }
+
+    impl ReleaseResponse {
+        fn from_node(release: &Node) -> Result<Self, Error> {
+            let text = |path: &[&str]| release.text_at(path).map(String::from);
+            let medium_list = release.child("medium-list");
+            let mediums: Vec<ReleaseMedium> = match medium_list {
+                Some(list) => list
+                    .children("medium")
+                    .map(ReleaseMedium::from_node)
+                    .collect::<Result<_, _>>()?,
+                None => Vec::new(),
+            };
+            let warnings = mediums.iter().flat_map(|m| m.warnings.iter().cloned()).collect();
+
+            Ok(ReleaseResponse {
+                aliases: match release.child("alias-list") {
+                    Some(list) => list
+                        .children("alias")
+                        .map(Alias::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                annotation: text(&["annotation", "text"]),
+                artists: match release.child("artist-credit") {
+                    Some(credit) => credit
+                        .children("name-credit")
+                        .map(ArtistRef::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                artist_credit: credits_from_node(release)?,
+                artist_rels: relations_from_node(release)?
+                    .into_iter()
+                    .filter(|r| match r.target {
+                        RelationTarget::Artist(_) => true,
+                        _ => false,
+                    })
+                    .collect(),
+                barcode: text(&["barcode"]),
+                country: parse(release.text_at(&["country"]))?,
+                date: parse(release.text_at(&["date"]))?,
+                disambiguation: text(&["disambiguation"]),
+                events: match release.child("release-event-list") {
+                    Some(list) => list
+                        .children("release-event")
+                        .map(ReleaseEvent::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                genres: match release.child("genre-list") {
+                    Some(list) => list
+                        .children("genre")
+                        .map(Genre::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                labels: match release.child("label-info-list") {
+                    Some(list) => list
+                        .children("label-info")
+                        .map(LabelInfo::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                language: match release.text_at(&["text-representation", "language"]) {
+                    Some(code) => Some(Language::from_639_3(code)?),
+                    None => None,
+                },
+                mbid: require(release.attribute("id"), "release id")?,
+                mediums,
+                medium_count: parse(medium_list.and_then(|l| l.attribute("count")))?,
+                packaging: text(&["packaging"]),
+                packaging_id: parse(release.child("packaging").and_then(|p| p.attribute("id")))?,
+                release_group: match release.child("release-group") {
+                    Some(group) => Some(ReleaseGroupRef::from_node(group)?),
+                    None => None,
+                },
+                script: parse(release.text_at(&["text-representation", "script"]))?,
+                status: parse(release.text_at(&["status"]))?,
+                status_id: parse(release.child("status").and_then(|s| s.attribute("id")))?,
+                tags: match release.child("tag-list") {
+                    Some(list) => list
+                        .children("tag")
+                        .map(Tag::from_node)
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                quality: parse(release.text_at(&["quality"]))?,
+                title: require(release.text_at(&["title"]), "release title")?,
+                track_counts: match medium_list {
+                    Some(list) => list
+                        .children("medium")
+                        .flat_map(|m| m.children("track-list").chain(m.children("data-track-list")))
+                        .filter_map(|t| t.attribute("count"))
+                        .map(|c| require(Some(c), "track count"))
+                        .collect::<Result<_, _>>()?,
+                    None => Vec::new(),
+                },
+                warnings,
+            })
+        }
+    }
+
+    impl ReleaseMedium {
+        fn from_node(medium: &Node) -> Result<Self, Error> {
+            let position = require(medium.descendant_text("position"), "medium position")?;
+            let results = medium
+                .children("track-list")
+                .chain(medium.children("data-track-list"))
+                .flat_map(|list| {
+                    let data_track = list.name == "data-track-list";
+                    list.children("track").map(move |t| {
+                        ReleaseTrack::from_node(t)
+                            .map(|t| ReleaseTrack { data_track, ..t })
+                            .map_err(|e| e.to_string())
+                    })
+                });
+            let (tracks, warnings) = ReleaseMedium::collect_tracks(position, results);
+
+            Ok(ReleaseMedium {
+                position,
+                format: medium.descendant_text("format").map(String::from),
+                tracks,
+                pregap: match medium.child("pregap") {
+                    Some(pregap) => Some(ReleaseTrack::from_node(pregap)?),
+                    None => None,
+                },
+                disc_ids: match medium.child("disc-list") {
+                    Some(list) => list
+                        .children("disc")
+                        .filter_map(|d| d.attribute("id"))
+                        .map(String::from)
+                        .collect(),
+                    None => Vec::new(),
+                },
+                warnings,
+            })
+        }
+    }
+
+    impl ReleaseTrack {
+        fn from_node(track: &Node) -> Result<Self, Error> {
+            Ok(ReleaseTrack {
+                mbid: require(track.descendant_attribute("id"), "track id")?,
+                position: require(track.descendant_text("position"), "track position")?,
+                number: require(track.descendant_text("number"), "track number")?,
+                title: require(track.descendant_text("title"), "track title")?,
+                length: parse(track.descendant_text("length"))?,
+                recording: match track.descendant("recording") {
+                    Some(recording) => RecordingRef::from_node(recording)?,
+                    None => return Err(Error::parse_error("Missing track recording.")),
+                },
+                artist_credit: credits_from_node(track)?,
+                recording_rels: match track.child("recording") {
+                    Some(recording) => relations_from_node(recording)?,
+                    None => Vec::new(),
+                },
+                data_track: false,
+            })
+        }
+    }
+
+    /// The artist credit listed directly in `node`.
+    fn credits_from_node(node: &Node) -> Result<Vec<ArtistCredit>, Error> {
+        match node.child("artist-credit") {
+            Some(credit) => credit
+                .children("name-credit")
+                .map(ArtistCredit::from_node)
+                .collect(),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    impl ReleaseEvent {
+        fn from_node(event: &Node) -> Result<Self, Error> {
+            Ok(ReleaseEvent {
+                date: parse(event.text_at(&["date"]))?,
+                area: match event.child("area") {
+                    Some(area) => Some(AreaRef::from_node(area)?),
+                    None => None,
+                },
+            })
+        }
+    }
+
+    impl LabelInfo {
+        fn from_node(info: &Node) -> Result<Self, Error> {
+            Ok(LabelInfo {
+                label: match info.descendant_attribute("id") {
+                    Some(_) => Some(LabelRef::from_node(info)?),
+                    None => None,
+                },
+                catalog_number: info.descendant_text("catalog-number").map(String::from),
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const XML: &'static str = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><tag-list><tag count="4"><name>alternative rock</name></tag></tag-list><genre-list><genre count="4" id="ceeaa283-5d7b-4202-8d1d-e25d116b2a18"><name>alternative rock</name></genre></genre-list><status id="4e304316-386d-3409-af2e-78857eec5cfe">Official</status><quality>normal</quality><packaging id="ec27701a-4a22-37f4-bfac-6616e0f9750a">Jewel Case</packaging><text-representation><language>eng</language><script>Latn</script></text-representation><artist-credit><name-credit><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit><date>1992-09-21</date><country>GB</country><release-event-list count="1"><release-event><date>1992-09-21</date><area id="8a754a16-0027-3a29-b6d7-2b40ea0481ed"><name>United Kingdom</name><sort-name>United Kingdom</sort-name><iso-3166-1-code-list><iso-3166-1-code>GB</iso-3166-1-code></iso-3166-1-code-list></area></release-event></release-event-list><barcode>724388023429</barcode><label-info-list count="2"><label-info><catalog-number>CDR 6078</catalog-number><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name><sort-name>Parlophone</sort-name><label-code>299</label-code></label></label-info><label-info><catalog-number>BIRD 4</catalog-number></label-info></label-info-list><medium-list count="2"><medium><position>1</position><format id="9712d52a-4509-3d4b-a1a2-67c88c643e31">CD</format><disc-list count="1"><disc id="6Yus2nxrrWcJzVnq0GaPiZcqUVQ-"><sectors>40385</sectors></disc></disc-list><pregap id="3c0b2c4a-1f57-4a0e-9b44-42f6e6a1c0f1"><position>0</position><number>0</number><length>63000</length><recording id="9b1c5e0a-7d5b-4f0e-8c2a-1d7e0f3b6a55"><title>Hidden</title></recording></pregap><track-list offset="0" count="2"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>1</position><number>1</number><length>232000</length><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Creep</title><length>232000</length><relation-list target-type="artist"><relation type="vocal" type-id="0fdbe3c6-7700-4a31-ae54-b53f06ae1cfa"><target>8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b</target><direction>backward</direction><attribute-list><attribute>lead vocals</attribute></attribute-list><begin>1992</begin><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></relation></relation-list></recording></track><track id="21648b0b-deaf-4b93-a257-5fc18363b25d"><position>2</position><number>2</number><recording id="0eeb0621-8013-4c0e-8e49-ddfd78d56051"><title>Lurgee &amp; Co</title></recording></track></track-list><data-track-list count="1"><track id="5d6e7f80-9a1b-4c2d-8e3f-405162738495"><position>3</position><number>3</number><recording id="6e7f8091-a2b3-4c4d-9e5f-a06172839405"><title>Video</title></recording></track></data-track-list></medium><medium><position>2</position><track-list offset="0" count="1"><track id="e57b3990-eb36-476e-beac-583e0bbe6f87"><position>1</position><number>A</number><length>228000</length><recording id="53f87e98-351e-453e-b949-bdacf4cbeccd"><title>Inside My Head</title><length>228000</length></recording></track></track-list></medium></medium-list><release-group type="Single" id="b8e9e3a4-1b42-3c52-8b6f-4a1e0d4d9f9f"><title>Creep</title><first-release-date>1992-09-21</first-release-date><primary-type>Single</primary-type><secondary-type-list><secondary-type>Live</secondary-type></secondary-type-list></release-group><relation-list target-type="artist"><relation type="producer" type-id="8bf377ba-8d71-4ecc-97f2-7bb2d8a2a75f"><target>a3b4c5d6-e7f8-4a9b-8c0d-1e2f3a4b5c6d</target><direction>backward</direction><artist id="a3b4c5d6-e7f8-4a9b-8c0d-1e2f3a4b5c6d"><name>Sean Slade</name><sort-name>Slade, Sean</sort-name></artist></relation></relation-list></release></metadata>"#;
+
+        #[test]
+        fn same_as_xpath() {
+            let context = crate::util::musicbrainz_context();
+            let reader = Reader::from_str(XML, Some(&context)).unwrap();
+            let expected = ReleaseResponse::from_xml(&reader).unwrap();
+            assert_eq!(expected.quality, Some(ReleaseQuality::Normal));
+            assert_eq!(
+                expected.mediums[0].disc_ids(),
+                &["6Yus2nxrrWcJzVnq0GaPiZcqUVQ-".to_string()]
+            );
+            assert_eq!(expected.tags[0].count, 4);
+            assert_eq!(expected.genres[0].name, "alternative rock".to_string());
+            assert_eq!(expected.mediums[0].tracks[0].recording_rels.len(), 1);
+            assert_eq!(
+                expected.artist_rels[0].relation_type,
+                "producer".to_string()
+            );
+            assert_eq!(
+                expected.events[0].area.as_ref().unwrap().name,
+                "United Kingdom"
+            );
+
+            assert_eq!(parse_release(XML).unwrap(), expected);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::str::FromStr;
+    use crate::entities::{AliasType, Artist, ReleaseGroupPrimaryType, ReleaseGroupType};
 
     #[test]
     fn release_read_xml1() {
         let mbid = Mbid::from_str("ed118c5f-d940-4b52-a37b-b1a205374abe").unwrap();
-        let options = ReleaseOptions::everything();
+        let options = ReleaseOptions {
+            annotation: true,
+            artists: true,
+            recordings: true,
+            labels: true,
+            ..ReleaseOptions::minimal()
+        };
         let release: Release = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
 
         assert_eq!(release.mbid(), &mbid);
@@ -362,7 +1220,7 @@ mod tests {
             release.date(),
             Some(&PartialDate::from_str("1992-09-21").unwrap())
         );
-        assert_eq!(release.country(), Some(&"GB".to_string()));
+        assert_eq!(release.country().map(|c| c.as_str()), Some("GB"));
         assert_eq!(
             release.labels().unwrap(),
             &[
@@ -388,13 +1246,19 @@ mod tests {
         );
         assert_eq!(release.barcode(), Some(&"724388023429".to_string()));
         assert_eq!(release.status(), Some(ReleaseStatus::Official));
+        assert_eq!(
+            release.status_id(),
+            Some(&Mbid::from_str("4e304316-386d-3409-af2e-78857eec5cfe").unwrap())
+        );
         assert_eq!(
             release.language(),
             Some(&Language::from_639_3("eng").unwrap())
         );
-        assert_eq!(release.script(), Some(&"Latn".to_string()));
+        assert_eq!(release.script(), Some(Script::from_code("Latn").unwrap()));
         assert_eq!(release.disambiguation(), None);
         assert_eq!(release.mediums().unwrap().len(), 1);
+        assert_eq!(release.medium_count(), OnRequest::Some(1));
+        assert_eq!(release.track_count(), OnRequest::Some(4));
     }
 
     #[test]
@@ -406,6 +1270,27 @@ mod tests {
         assert_eq!(release.disambiguation(), Some(&"通常盤".to_string()));
     }
 
+    #[test]
+    fn release_events() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="b84ee12a-09ef-421b-82de-0441a926375b"><title>OK Computer</title><date>1997-05-21</date><country>XE</country><release-event-list count="2"><release-event><date>1997-05-21</date><area id="89a675c2-3e37-3518-b83c-418bad59a85a"><name>Europe</name><sort-name>Europe</sort-name><iso-3166-1-code-list><iso-3166-1-code>XE</iso-3166-1-code></iso-3166-1-code-list></area></release-event><release-event><date>1997-07-01</date></release-event></release-event-list></release></metadata>"#;
+        let release: Release =
+            crate::util::test_utils::parse_entity(xml, ReleaseOptions::minimal()).unwrap();
+
+        let europe = release.country_area().unwrap();
+        assert_eq!(
+            europe.mbid,
+            Mbid::from_str("89a675c2-3e37-3518-b83c-418bad59a85a").unwrap()
+        );
+        assert_eq!(europe.name, "Europe".to_string());
+        assert_eq!(
+            release.release_events()[1],
+            ReleaseEvent {
+                date: Some(PartialDate::from_str("1997-07-01").unwrap()),
+                area: None,
+            }
+        );
+    }
+
     #[test]
     fn release_read_xml2() {
         let mbid = Mbid::from_str("785d7c67-a920-4cee-a871-8cd9896eb8aa").unwrap();
@@ -466,6 +1351,7 @@ mod tests {
             ]
         );
         assert_eq!(release.mediums(), OnRequest::NotRequested);
+        assert_eq!(release.medium_count(), OnRequest::NotRequested);
     }
 
     #[test]
@@ -487,12 +1373,15 @@ mod tests {
                 position: 1,
                 number: "1".to_string(),
                 title: "puella tenebrarum".to_string(),
-                length: Some(Duration::from_millis(232000)),
+                length: Some(TrackLength::from_millis(232000)),
                 recording: RecordingRef {
                     mbid: Mbid::from_str("fd6f4cd8-9cff-43da-8cd7-3351357b6f5a").unwrap(),
                     title: "Puella Tenebrarum".to_string(),
-                    length: Some(Duration::from_millis(232000)),
+                    length: Some(TrackLength::from_millis(232000)),
                 },
+                artist_credit: Vec::new(),
+                recording_rels: Vec::new(),
+                data_track: false,
             }
         );
         assert_eq!(
@@ -502,12 +1391,15 @@ mod tests {
                 position: 2,
                 number: "2".to_string(),
                 title: "LAMINA MALEDICTUM".to_string(),
-                length: Some(Duration::from_millis(258000)),
+                length: Some(TrackLength::from_millis(258000)),
                 recording: RecordingRef {
                     mbid: Mbid::from_str("0eeb0621-8013-4c0e-8e49-ddfd78d56051").unwrap(),
                     title: "Lamina Maledictum".to_string(),
-                    length: Some(Duration::from_millis(258000)),
+                    length: Some(TrackLength::from_millis(258000)),
                 },
+                artist_credit: Vec::new(),
+                recording_rels: Vec::new(),
+                data_track: false,
             }
         );
         assert_eq!(
@@ -517,16 +1409,245 @@ mod tests {
                 position: 3,
                 number: "3".to_string(),
                 title: "SARNATH".to_string(),
-                length: Some(Duration::from_millis(228000)),
+                length: Some(TrackLength::from_millis(228000)),
                 recording: RecordingRef {
                     mbid: Mbid::from_str("53f87e98-351e-453e-b949-bdacf4cbeccd").unwrap(),
                     title: "Sarnath".to_string(),
-                    length: Some(Duration::from_millis(228000)),
+                    length: Some(TrackLength::from_millis(228000)),
                 },
+                artist_credit: Vec::new(),
+                recording_rels: Vec::new(),
+                data_track: false,
             }
         );
     }
 
+    #[test]
+    fn pregap_and_data_tracks() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><medium-list count="1"><medium><position>1</position><format>Enhanced CD</format><pregap id="3c0b2c4a-1f57-4a0e-9b44-42f6e6a1c0f1"><position>0</position><number>0</number><length>63000</length><recording id="9b1c5e0a-7d5b-4f0e-8c2a-1d7e0f3b6a55"><title>Hidden</title></recording></pregap><track-list count="1"><track id="bfc618d9-8595-3d97-8d19-5b8a2dcc9104"><position>1</position><number>1</number><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title></recording></track></track-list><data-track-list count="1"><track id="5d6e7f80-9a1b-4c2d-8e3f-405162738495"><position>2</position><number>2</number><recording id="6e7f8091-a2b3-4c4d-9e5f-a06172839405"><title>Creep (video)</title></recording></track></data-track-list></medium></medium-list></release></metadata>"#;
+        let mut options = ReleaseOptions::minimal();
+        options.recordings = true;
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+
+        let ref medium = release.mediums().unwrap()[0];
+        let pregap = medium.pregap().unwrap();
+        assert_eq!(pregap.position, 0);
+        assert_eq!(pregap.title, "Hidden".to_string());
+        assert_eq!(pregap.data_track, false);
+
+        assert_eq!(medium.tracks().len(), 2);
+        assert_eq!(medium.tracks()[1].title, "Creep (video)".to_string());
+        assert!(medium.tracks()[1].data_track);
+        assert_eq!(medium.audio_tracks().count(), 1);
+        assert_eq!(release.track_count(), OnRequest::Some(2));
+    }
+
+    #[test]
+    fn option_presets() {
+        let include = |options: ReleaseOptions| Release::request(&options).include;
+        assert_eq!(include(ReleaseOptions::for_tagging()), "artists+recordings");
+        assert_eq!(
+            include(ReleaseOptions::for_display()),
+            "artists+labels+recordings+release-groups"
+        );
+    }
+
+    #[test]
+    fn from_includes() {
+        let includes = [Include::Recordings, Include::ReleaseGroups, Include::Artists];
+        let options = ReleaseOptions::from_includes(&includes).unwrap();
+        assert!(options.artists && options.recordings && options.release_group);
+        assert!(!options.labels);
+        assert_eq!(
+            options.includes(),
+            vec![Include::Artists, Include::Recordings, Include::ReleaseGroups]
+        );
+
+        let options = ReleaseOptions::from_includes(&[Include::Tags, Include::Genres]).unwrap();
+        assert_eq!(options.includes(), vec![Include::Genres, Include::Tags]);
+
+        assert!(ReleaseOptions::from_includes(&[Include::Ratings]).is_err());
+    }
+
+    #[test]
+    fn lenient_broken_track() {
+        // The second track is missing its recording.
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><medium-list count="1"><medium><position>1</position><track-list count="3"><track id="bfc618d9-8595-3d97-8d19-5b8a2dcc9104"><position>1</position><number>1</number><recording id="70595637-9310-45f2-a266-58f8de4874a7"><title>Creep</title></recording></track><track id="21648b0b-deaf-4b93-a257-5fc18363b25d"><position>2</position><number>2</number></track><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>3</position><number>3</number><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Lurgee</title></recording></track></track-list></medium></medium-list></release></metadata>"#;
+        let mut options = ReleaseOptions::minimal();
+        options.recordings = true;
+
+        let strict: Result<Release, _> =
+            crate::util::test_utils::parse_entity(xml, options.clone());
+        assert!(strict.is_err());
+
+        let release: Release = crate::client::parse_response(xml, options, true).unwrap();
+        let ref medium = release.mediums().unwrap()[0];
+        assert_eq!(medium.tracks().len(), 2);
+        assert_eq!(medium.tracks()[1].title, "Lurgee".to_string());
+        assert_eq!(release.parse_warnings().len(), 1);
+        assert_eq!(release.parse_warnings()[0].location, "medium 1, track 2".to_string());
+        assert_eq!(medium.parse_warnings(), release.parse_warnings());
+    }
+
+    #[test]
+    fn disc_ids() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><medium-list count="2"><medium><position>1</position><disc-list count="2"><disc id="6Yus2nxrrWcJzVnq0GaPiZcqUVQ-"><sectors>40385</sectors></disc><disc id="lwHl8fGzJyLXQR33ug60E8jhf4k-"><sectors>40410</sectors></disc></disc-list><track-list count="1"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>1</position><number>1</number><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Creep</title></recording></track></track-list></medium><medium><position>2</position><track-list count="0"></track-list></medium></medium-list></release></metadata>"#;
+        let options = ReleaseOptions {
+            recordings: true,
+            disc_ids: true,
+            ..ReleaseOptions::minimal()
+        };
+        assert_eq!(Release::request(&options).include, "discids+recordings");
+
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+        let mediums = release.mediums().unwrap();
+        assert_eq!(mediums[0].disc_ids().len(), 2);
+        assert!(mediums[1].disc_ids().is_empty());
+        assert_eq!(
+            release.medium_by_disc_id("lwHl8fGzJyLXQR33ug60E8jhf4k-").unwrap().position(),
+            1
+        );
+        assert_eq!(release.medium_by_disc_id("unknown"), OnRequest::NotAvailable);
+    }
+
+    #[test]
+    fn recording_level_rels() {
+        use crate::entities::RelationTarget;
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><medium-list count="1"><medium><position>1</position><track-list count="2" offset="0"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>1</position><number>1</number><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Creep</title><relation-list target-type="artist"><relation type="vocal" type-id="0fdbe3c6-7700-4a31-ae54-b53f06ae1cfa"><target>8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b</target><direction>backward</direction><attribute-list><attribute>lead vocals</attribute></attribute-list><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></relation><relation type="guitar" type-id="59054b12-01ac-43ee-a618-285fd397e461"><target>5a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d</target><direction>backward</direction><artist id="5a1b2c3d-4e5f-4a6b-8c7d-9e0f1a2b3c4d"><name>Jonny Greenwood</name><sort-name>Greenwood, Jonny</sort-name></artist></relation></relation-list></recording></track><track id="21648b0b-deaf-4b93-a257-5fc18363b25d"><position>2</position><number>2</number><recording id="0eeb0621-8013-4c0e-8e49-ddfd78d56051"><title>Lurgee</title></recording></track></track-list></medium></medium-list><relation-list target-type="artist"><relation type="producer" type-id="8bf377ba-8d71-4ecc-97f2-7bb2d8a2a75f"><target>a3b4c5d6-e7f8-4a9b-8c0d-1e2f3a4b5c6d</target><direction>backward</direction><artist id="a3b4c5d6-e7f8-4a9b-8c0d-1e2f3a4b5c6d"><name>Sean Slade</name><sort-name>Slade, Sean</sort-name></artist></relation></relation-list></release></metadata>"#;
+        let options = ReleaseOptions {
+            recordings: true,
+            artist_rels: true,
+            recording_level_rels: true,
+            ..ReleaseOptions::minimal()
+        };
+        assert_eq!(
+            Release::request(&options).include,
+            "artist-rels+recording-level-rels+recordings"
+        );
+
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+        let tracks = release.mediums().unwrap()[0].tracks();
+        let performers: Vec<(&str, &str)> = tracks[0]
+            .recording_rels
+            .iter()
+            .filter_map(|r| match r.target {
+                RelationTarget::Artist(ref artist) => {
+                    Some((r.relation_type.as_str(), artist.name.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            performers,
+            vec![("vocal", "Thom Yorke"), ("guitar", "Jonny Greenwood")]
+        );
+        assert_eq!(tracks[0].recording_rels[0].attributes, vec!["lead vocals".to_string()]);
+        assert!(tracks[1].recording_rels.is_empty());
+
+        let producers: Vec<&str> = release
+            .artist_rels()
+            .unwrap()
+            .iter()
+            .filter_map(|r| match r.target {
+                RelationTarget::Artist(ref artist) if r.relation_type == "producer" => {
+                    Some(artist.name.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(producers, vec!["Sean Slade"]);
+
+        assert_eq!(
+            ReleaseOptions::from_includes(&[Include::ArtistRels, Include::RecordingLevelRels])
+                .unwrap()
+                .includes(),
+            vec![Include::ArtistRels, Include::RecordingLevelRels]
+        );
+    }
+
+    #[test]
+    fn work_level_rels() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><medium-list count="1"><medium><position>1</position><track-list count="1" offset="0"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><position>1</position><number>1</number><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a"><title>Creep</title><relation-list target-type="artist"><relation type="vocal" type-id="0fdbe3c6-7700-4a31-ae54-b53f06ae1cfa"><target>8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b</target><direction>backward</direction><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></relation></relation-list><relation-list target-type="work"><relation type="performance" type-id="a3005666-a872-32c3-ad06-98af558e99b0"><target>a2a5b2b4-3b8c-3cf3-b5a6-4d5e6f708192</target><direction>forward</direction><work id="a2a5b2b4-3b8c-3cf3-b5a6-4d5e6f708192"><title>Creep</title><relation-list target-type="artist"><relation type="composer" type-id="d59d99ea-23d4-4a80-b066-edca32ee158f"><target>a74b1b7f-71a5-4011-9441-d0b5e4122711</target><direction>backward</direction><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></relation><relation type="lyricist" type-id="3e48faba-ec01-47fd-8e89-30e81161661c"><target>8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b</target><direction>backward</direction><artist id="8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b"><name>Thom Yorke</name><sort-name>Yorke, Thom</sort-name></artist></relation></relation-list></work></relation></relation-list></recording></track></track-list></medium></medium-list></release></metadata>"#;
+        let options = ReleaseOptions::for_credits();
+        assert_eq!(
+            Release::request(&options).include,
+            "artist-rels+artists+recording-level-rels+recordings+work-level-rels+work-rels"
+        );
+
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+        let track = &release.mediums().unwrap()[0].tracks()[0];
+        assert_eq!(track.recording_rels.len(), 2);
+        assert!(track.recording_rels[0].target_rels.is_empty());
+
+        let works = track.works();
+        assert_eq!(works.len(), 1);
+        let (work, rels) = works[0];
+        assert_eq!(work.title, "Creep".to_string());
+        let credits: Vec<(&str, Option<&Mbid>)> = rels
+            .iter()
+            .map(|r| (r.relation_type.as_str(), r.target.mbid()))
+            .collect();
+        let radiohead = Mbid::from_str("a74b1b7f-71a5-4011-9441-d0b5e4122711").unwrap();
+        let thom_yorke = Mbid::from_str("8ed2e0b3-aa4c-4e13-bec3-dc7393ed4d6b").unwrap();
+        assert_eq!(
+            credits,
+            vec![
+                ("composer", Some(&radiohead)),
+                ("lyricist", Some(&thom_yorke))
+            ]
+        );
+    }
+
+    #[test]
+    fn compilation() {
+        let xml = |artist: &str, secondary: &str| {
+            format!(r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="b8b5a7d6-4bb2-4d8b-9a2f-6b5c2d4e1f3a"><title>Now That's What I Call Music! 4</title><artist-credit><name-credit><artist id="{}"><name>Artist</name><sort-name>Artist</sort-name></artist></name-credit></artist-credit><release-group id="7a1d5c53-1b2a-3f8e-9f3d-3e5c7b0a9d2e"><title>Now That's What I Call Music! 4</title><primary-type>Album</primary-type>{}</release-group></release></metadata>"#, artist, secondary)
+        };
+        let various = Artist::VARIOUS_ARTISTS_MBID;
+        let radiohead = "a74b1b7f-71a5-4011-9441-d0b5e4122711";
+        let compilation = r#"<secondary-type-list><secondary-type>Compilation</secondary-type></secondary-type-list>"#;
+        let options = ReleaseOptions {
+            artists: true,
+            release_group: true,
+            ..ReleaseOptions::minimal()
+        };
+        let parse = |xml: String, options: ReleaseOptions| -> Release {
+            crate::util::test_utils::parse_entity(&xml, options).unwrap()
+        };
+
+        let release = parse(xml(various, ""), options.clone());
+        assert_eq!(release.is_compilation(), OnRequest::Some(true));
+        let release = parse(xml(radiohead, compilation), options.clone());
+        assert_eq!(release.is_compilation(), OnRequest::Some(true));
+        let release = parse(xml(radiohead, ""), options.clone());
+        assert_eq!(release.is_compilation(), OnRequest::Some(false));
+
+        let only_artists = ReleaseOptions {
+            artists: true,
+            ..ReleaseOptions::minimal()
+        };
+        let release = parse(xml(radiohead, compilation), only_artists);
+        assert_eq!(release.is_compilation(), OnRequest::NotRequested);
+    }
+
+    #[test]
+    fn read_aliases() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="d3d2a860-0093-461d-8d95-b77939c2e944"><title>霊魂消滅</title><alias-list count="1"><alias locale="en" sort-name="Reikon Shoumetsu" type="Release name" primary="primary">Reikon Shoumetsu</alias></alias-list></release></metadata>"#;
+        let mut options = ReleaseOptions::minimal();
+        let release: Release = crate::util::test_utils::parse_entity(xml, options.clone()).unwrap();
+        assert_eq!(release.aliases(), OnRequest::NotRequested);
+
+        options.aliases = true;
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+        let aliases = release.aliases().unwrap();
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(aliases[0].name(), &"Reikon Shoumetsu".to_string());
+        assert_eq!(aliases[0].alias_type(), Some(AliasType::ReleaseName));
+        assert_eq!(aliases[0].locale(), Some(&Language::from_639_1("en").unwrap()));
+        assert!(aliases[0].primary());
+    }
+
     #[test]
     fn tracks_without_length() {
         let mbid = Mbid::from_str("02173013-59ed-4229-b0a5-e5aa486ed5d7").unwrap();
@@ -551,6 +1672,8 @@ mod tests {
         let mediums = release.mediums().unwrap();
 
         assert_eq!(mediums.len(), 2);
+        assert_eq!(release.medium_count(), OnRequest::Some(2));
+        assert_eq!(release.track_count(), OnRequest::Some(20));
 
         assert_eq!(mediums[0].position, 1);
         assert_eq!(mediums[0].tracks.len(), 11);
@@ -584,4 +1707,29 @@ mod tests {
             },]
         );
     }
+
+    #[test]
+    fn read_release_group() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><status id="4e304316-386d-3409-af2e-78857eec5cfe">Official</status><quality>normal</quality><text-representation><language>eng</language><script>Latn</script></text-representation><date>1992-09-21</date><country>GB</country><barcode>724388023429</barcode><release-group type="Single" type-id="d6038452-8ee0-3f68-affc-2de9a1ede0b9" id="b8e9e3a4-1b42-3c52-8b6f-4a1e0d4d9f9f"><title>Creep</title><first-release-date>1992-09-21</first-release-date><primary-type id="d6038452-8ee0-3f68-affc-2de9a1ede0b9">Single</primary-type></release-group></release></metadata>"#;
+        let mut options = ReleaseOptions::minimal();
+        options.release_group = true;
+        let release: Release = crate::util::test_utils::parse_entity(xml, options).unwrap();
+
+        assert_eq!(
+            release.release_group().unwrap(),
+            &ReleaseGroupRef {
+                mbid: Mbid::from_str("b8e9e3a4-1b42-3c52-8b6f-4a1e0d4d9f9f").unwrap(),
+                title: "Creep".to_string(),
+                release_type: ReleaseGroupType {
+                    primary: Some(ReleaseGroupPrimaryType::Single),
+                    secondary: Vec::new(),
+                    primary_id: Some(
+                        Mbid::from_str("d6038452-8ee0-3f68-affc-2de9a1ede0b9").unwrap()
+                    ),
+                    secondary_ids: Vec::new(),
+                },
+                first_release_date: Some(PartialDate::from_str("1992-09-21").unwrap()),
+            }
+        );
+    }
 }
@@ -1,8 +1,12 @@
 use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
 
-use crate::entities::{Mbid, ResourceOld};
+use crate::client::Request;
+use crate::entities::{include, join_includes, write_summary, EntityKind, Genre, Include, Mbid,
+                      OnRequest, Rating, ReleaseStatus, Resource, Tag};
 use crate::entities::refs::{ArtistRef, ReleaseRef};
 
+use std::fmt;
+
 enum_mb_xml_optional! {
     /// The primary type of a release group.
     pub enum ReleaseGroupPrimaryType {
@@ -27,6 +31,10 @@ enum_mb_xml_optional! {
         var Remix = "Remix",
         var DjMix = "DJ-mix",
         var MixtapeStreet = "Mixtape/Street",
+        var Demo = "Demo",
+        var FieldRecording = "Field recording",
+        var AudioDrama = "Audio drama",
+        unknown Unknown,
     }
 }
 
@@ -34,9 +42,16 @@ enum_mb_xml_optional! {
 ///
 /// For more information consult: https://musicbrainz.org/doc/Release_Group/Type
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ReleaseGroupType {
     pub primary: Option<ReleaseGroupPrimaryType>,
     pub secondary: Vec<ReleaseGroupSecondaryType>,
+
+    /// MBID of the primary type, which unlike its name is never translated.
+    pub primary_id: Option<Mbid>,
+
+    /// MBIDs of the secondary types, in the same order as `secondary`.
+    pub secondary_ids: Vec<Mbid>,
 }
 
 impl FromXml for ReleaseGroupType {
@@ -44,6 +59,35 @@ impl FromXml for ReleaseGroupType {
         Ok(ReleaseGroupType {
             primary: reader.read(".//mb:primary-type/text()")?,
             secondary: reader.read(".//mb:secondary-type-list/mb:secondary-type/text()")?,
+            primary_id: reader.read(".//mb:primary-type/@id")?,
+            secondary_ids: reader.read(".//mb:secondary-type-list/mb:secondary-type/@id")?,
+        })
+    }
+}
+
+#[cfg(feature = "streaming")]
+impl ReleaseGroupType {
+    pub(crate) fn from_node(node: &crate::streaming::Node) -> Result<Self, crate::Error> {
+        use crate::streaming::{parse, require};
+
+        let primary = node.descendant("primary-type");
+        let secondary: Vec<_> = match node.descendant("secondary-type-list") {
+            Some(list) => list.children("secondary-type").collect(),
+            None => Vec::new(),
+        };
+
+        Ok(ReleaseGroupType {
+            primary: parse(primary.map(|t| t.text.as_str()))?,
+            secondary: secondary
+                .iter()
+                .map(|t| t.text.parse())
+                .collect::<Result<_, _>>()?,
+            primary_id: parse(primary.and_then(|t| t.attribute("id")))?,
+            secondary_ids: secondary
+                .iter()
+                .filter_map(|t| t.attribute("id"))
+                .map(|id| require(Some(id), "secondary type id"))
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -52,49 +96,382 @@ impl FromXml for ReleaseGroupType {
 ///
 /// Even if there is only one `Release` of a kind, it belongs to exactly one
 /// `ReleaseGroup`.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct ReleaseGroup {
+    response: ReleaseGroupResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: ReleaseGroupOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReleaseGroupOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+
+    /// Request the artists the release group is credited to.
+    pub artists: bool,
+
+    /// Request the releases of the release group.
+    pub releases: bool,
+
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReleaseGroupResponse {
+    mbid: Mbid,
+    title: String,
+    artists: Vec<ArtistRef>,
+    releases: Vec<ReleaseRef>,
+    release_type: ReleaseGroupType,
+    disambiguation: Option<String>,
+    aliases: Vec<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+from_xml_paths! {
+    ReleaseGroupResponse at ".//mb:release-group" {
+        mbid: "/@id",
+        title: "/mb:title/text()",
+        releases: "/mb:release-list/mb:release",
+        artists: "/mb:artist-credit/mb:name-credit/mb:artist",
+        release_type: "",
+        disambiguation: "/mb:disambiguation/text()",
+        aliases: "/mb:alias-list/mb:alias/text()",
+        annotation: "/mb:annotation/text()",
+        tags: "/mb:tag-list/mb:tag",
+        genres: "/mb:genre-list/mb:genre",
+        rating: "/mb:rating",
+    }
+}
+
+impl Resource for ReleaseGroup {
+    type Options = ReleaseGroupOptions;
+    type Response = ReleaseGroupResponse;
+    const NAME: &'static str = "release-group";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "release-group".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        ReleaseGroup { response, options }
+    }
+}
+
+impl ReleaseGroupOptions {
+    pub fn everything() -> Self {
+        ReleaseGroupOptions {
+            aliases: true,
+            annotation: true,
+            artists: true,
+            releases: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        ReleaseGroupOptions {
+            aliases: false,
+            annotation: false,
+            artists: false,
+            releases: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+        }
+    }
+
+    /// Request what is usually shown on a release group page: the artists,
+    /// the releases, tags and genres.
+    pub fn for_display() -> Self {
+        ReleaseGroupOptions {
+            artists: true,
+            releases: true,
+            tags: true,
+            genres: true,
+            ..ReleaseGroupOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        ReleaseGroupOptions {
+            annotation: true,
+            genres: true,
+            ..ReleaseGroupOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for release groups.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = ReleaseGroupOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Artists => options.artists = true,
+                Include::Releases => options.releases = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                _ => return Err(include::unsupported(EntityKind::ReleaseGroup, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.artists {
+            includes.push(Include::Artists);
+        }
+        if self.releases {
+            includes.push(Include::Releases);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        includes
+    }
+}
+
+/// The annotation, artists and releases, which were always requested before
+/// release groups had options.
+impl Default for ReleaseGroupOptions {
+    fn default() -> Self {
+        ReleaseGroupOptions {
+            annotation: true,
+            artists: true,
+            releases: true,
+            ..ReleaseGroupOptions::minimal()
+        }
+    }
+}
+
+impl fmt::Debug for ReleaseGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ReleaseGroup")
+            .field("mbid", self.mbid())
+            .field("title", self.title())
+            .field("release_type", self.release_type())
+            .field("disambiguation", &self.disambiguation())
+            .field("artists", &self.artists())
+            .field("releases", &self.releases())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
+    }
+}
+
+impl fmt::Display for ReleaseGroup {
+    /// Formats the release group as e.g. `OK Computer (1997, Album)`, where the
+    /// year is the one of the earliest release and only known if the releases
+    /// were requested.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let year = self
+            .response
+            .releases
+            .iter()
+            .filter_map(|r| r.date.as_ref().and_then(|d| d.year()))
+            .min();
+        write_summary(
+            f,
+            self.title(),
+            &[
+                year.map(|y| y.to_string()),
+                self.release_type().primary.map(|t| t.to_string()),
+            ],
+        )
+    }
+}
+
+/// Preferences used by `ReleaseGroup::best_release` to pick one release of a
+/// release group.
+///
+/// The criteria are applied in the order status, country, format and date,
+/// each one only deciding between releases which are equal in the previous
+/// ones.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReleasePreferences {
+    prefer_official: bool,
+    countries: Vec<String>,
+    formats: Vec<String>,
+}
+
+impl Default for ReleasePreferences {
+    fn default() -> Self {
+        ReleasePreferences::new()
+    }
+}
+
+impl ReleasePreferences {
+    /// Create preferences picking the earliest official release.
+    pub fn new() -> Self {
+        ReleasePreferences {
+            prefer_official: true,
+            countries: Vec::new(),
+            formats: Vec::new(),
+        }
+    }
+
+    /// Whether official releases are preferred over promotions, bootlegs and
+    /// releases without a status, which is the default.
+    pub fn prefer_official(mut self, prefer: bool) -> Self {
+        self.prefer_official = prefer;
+        self
+    }
+
+    /// Prefer releases issued in the country with the ISO 3166-1 code `code`,
+    /// after the countries added before.
+    pub fn country<S: AsRef<str>>(mut self, code: S) -> Self {
+        self.countries.push(code.as_ref().trim().to_uppercase());
+        self
+    }
+
+    /// Prefer releases with a medium of the format `format`, e.g. `CD`, after
+    /// the formats added before.
+    ///
+    /// The formats of the releases are only known if their mediums were
+    /// requested.
+    pub fn format<S: AsRef<str>>(mut self, format: S) -> Self {
+        self.formats.push(format.as_ref().trim().to_lowercase());
+        self
+    }
+
+    /// The key by which releases are ordered, the best release has the
+    /// smallest one.
+    fn rank(&self, release: &ReleaseRef) -> (bool, usize, usize, (u16, u8, u8)) {
+        let official = release.status == Some(ReleaseStatus::Official);
+        let country = release
+            .country
+            .as_ref()
+            .and_then(|c| self.countries.iter().position(|p| p == c.as_str()))
+            .unwrap_or(self.countries.len());
+        let format = release
+            .formats
+            .iter()
+            .filter_map(|f| self.formats.iter().position(|p| *p == f.to_lowercase()))
+            .min()
+            .unwrap_or(self.formats.len());
+        // Releases without a date are ordered last, a date without a month
+        // before every date in that year.
+        let date = match release.date.as_ref() {
+            Some(d) if d.year().is_some() => (
+                d.year().unwrap(),
+                d.month().unwrap_or(0),
+                d.day().unwrap_or(0),
+            ),
+            _ => (u16::MAX, 0, 0),
+        };
+        (self.prefer_official && !official, country, format, date)
+    }
+}
+
+impl ReleaseGroup {
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the release group on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::ReleaseGroup.permalink(self.mbid())
+    }
 
     /// Title of the release group, usually the same as the title of the
     /// releases.
-    pub title: String,
+    pub fn title(&self) -> &String {
+        &self.response.title
+    }
 
     /// The artists of a release group.
-    pub artists: Vec<ArtistRef>,
+    pub fn artists(&self) -> OnRequest<&[ArtistRef]> {
+        OnRequest::from_value(self.response.artists.as_ref(), self.options.artists)
+    }
 
     /// Releases of this releaes group.
-    pub releases: Vec<ReleaseRef>,
+    pub fn releases(&self) -> OnRequest<&[ReleaseRef]> {
+        OnRequest::from_value(self.response.releases.as_ref(), self.options.releases)
+    }
 
     /// The type of this release group.
-    pub release_type: ReleaseGroupType,
+    pub fn release_type(&self) -> &ReleaseGroupType {
+        &self.response.release_type
+    }
 
     /// Additional disambiguation if there are multiple `ReleaseGroup`s with
     /// the same name.
-    pub disambiguation: Option<String>,
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
+
+    /// Alternative titles of the release group.
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
 
     /// Any additional free form annotation for this `ReleaseGroup`.
-    pub annotation: Option<String>,
-}
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
 
-impl ResourceOld for ReleaseGroup {
-    const NAME: &'static str = "release-group";
-    const INCL: &'static str = "annotation+artists+releases";
-}
+    /// The tags users attached to the release group.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
 
-impl FromXml for ReleaseGroup {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(ReleaseGroup {
-            mbid: reader.read(".//mb:release-group/@id")?,
-            title: reader.read(".//mb:release-group/mb:title/text()")?,
-            releases: reader.read(".//mb:release-group/mb:release-list/mb:release")?,
-            artists: reader
-                .read(".//mb:release-group/mb:artist-credit/mb:name-credit/mb:artist")?,
-            release_type: reader.read(".//mb:release-group")?,
-            disambiguation: reader.read(".//mb:release-group/mb:disambiguation/text()")?,
-            annotation: reader.read(".//mb:release-group/mb:annotation/text()")?,
-        })
+    /// The genres of the release group.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the release group.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
+
+    /// Pick the canonical release of a release group from `releases`, e.g.
+    /// `ReleaseGroup::best_release(group.releases().unwrap(), &preferences)`.
+    ///
+    /// If multiple releases are equal according to `preferences`, the first
+    /// one is returned. Returns `None` if `releases` is empty.
+    pub fn best_release<'r>(
+        releases: &'r [ReleaseRef],
+        preferences: &ReleasePreferences,
+    ) -> Option<&'r ReleaseRef> {
+        releases.iter().min_by_key(|r| preferences.rank(r))
     }
 }
 
@@ -107,37 +484,139 @@ mod tests {
     #[test]
     fn read_1() {
         let mbid = Mbid::from_str("76a4e2c2-bf7a-445e-8081-5a1e291f3b16").unwrap();
-        let rg: ReleaseGroup = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let rg: ReleaseGroup =
+            crate::util::test_utils::fetch_entity(&mbid, ReleaseGroupOptions::default()).unwrap();
 
-        assert_eq!(rg.mbid, mbid);
-        assert_eq!(rg.title, "Mixtape".to_string());
+        assert_eq!(rg.mbid(), &mbid);
+        assert_eq!(rg.title(), &"Mixtape".to_string());
         assert_eq!(
-            rg.artists,
-            vec![ArtistRef {
+            rg.artists().unwrap(),
+            &[ArtistRef {
                 mbid: Mbid::from_str("0e6b3a2c-6a42-4b43-a4f6-c6625c5855de").unwrap(),
                 name: "POP ETC".to_string(),
                 sort_name: "POP ETC".to_string(),
             },]
         );
         assert_eq!(
-            rg.releases,
-            vec![ReleaseRef {
+            rg.releases().unwrap(),
+            &[ReleaseRef {
                 mbid: Mbid::from_str("289bf4e7-0af5-433c-b5a2-493b863b4b47").unwrap(),
                 title: "Mixtape".to_string(),
                 date: Some(PartialDate::from_str("2012-03").unwrap()),
                 status: Some(ReleaseStatus::Official),
-                country: Some("US".to_string()),
+                country: Some(Country::from_code("US").unwrap()),
+                formats: Vec::new(),
             },]
         );
         assert_eq!(
-            rg.release_type.primary,
+            rg.release_type().primary,
             Some(ReleaseGroupPrimaryType::Album)
         );
         assert_eq!(
-            rg.release_type.secondary,
+            rg.release_type().secondary,
             vec![ReleaseGroupSecondaryType::MixtapeStreet]
         );
-        assert_eq!(rg.disambiguation, None);
-        assert_eq!(rg.annotation, None);
+        assert_eq!(rg.disambiguation(), None);
+        assert_eq!(rg.annotation(), OnRequest::NotAvailable);
+        assert_eq!(rg.tags(), OnRequest::NotRequested);
+    }
+
+    #[test]
+    fn secondary_types() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release-group id="76a4e2c2-bf7a-445e-8081-5a1e291f3b16"><primary-type>Album</primary-type><secondary-type-list><secondary-type>Demo</secondary-type><secondary-type>Field recording</secondary-type><secondary-type>Hologram</secondary-type></secondary-type-list></release-group></metadata>"#;
+        let context = crate::util::musicbrainz_context();
+        let reader = Reader::from_str(xml, Some(&context)).unwrap();
+        let release_type = ReleaseGroupType::from_xml(&reader).unwrap();
+
+        assert_eq!(
+            release_type.secondary,
+            vec![
+                ReleaseGroupSecondaryType::Demo,
+                ReleaseGroupSecondaryType::FieldRecording,
+                ReleaseGroupSecondaryType::Unknown("Hologram".to_string()),
+            ]
+        );
+        assert_eq!(
+            "Hologram".parse::<ReleaseGroupSecondaryType>().unwrap(),
+            ReleaseGroupSecondaryType::Unknown("Hologram".to_string())
+        );
+        assert_eq!(
+            ReleaseGroupSecondaryType::Unknown("Hologram".to_string()).to_string(),
+            "Hologram"
+        );
+        assert_eq!(
+            ReleaseGroupSecondaryType::FieldRecording.to_string(),
+            "Field recording"
+        );
+        assert!("Hologram".parse::<ReleaseGroupPrimaryType>().is_err());
+    }
+
+    #[test]
+    fn display() {
+        let release = |date: &str| ReleaseRef {
+            mbid: Mbid::from_str("289bf4e7-0af5-433c-b5a2-493b863b4b47").unwrap(),
+            title: "OK Computer".to_string(),
+            date: Some(PartialDate::from_str(date).unwrap()),
+            status: None,
+            country: None,
+            formats: Vec::new(),
+        };
+        let mut rg = ReleaseGroup {
+            response: ReleaseGroupResponse {
+                mbid: Mbid::from_str("b1392450-e666-3926-a536-22c65f834433").unwrap(),
+                title: "OK Computer".to_string(),
+                artists: Vec::new(),
+                releases: vec![release("2009-08-31"), release("1997-05-21")],
+                release_type: ReleaseGroupType {
+                    primary: Some(ReleaseGroupPrimaryType::Album),
+                    secondary: Vec::new(),
+                    primary_id: None,
+                    secondary_ids: Vec::new(),
+                },
+                disambiguation: None,
+                aliases: Vec::new(),
+                annotation: None,
+                tags: Vec::new(),
+                genres: Vec::new(),
+                rating: None,
+            },
+            options: ReleaseGroupOptions::default(),
+        };
+        assert_eq!(rg.to_string(), "OK Computer (1997, Album)".to_string());
+
+        rg.response.releases.clear();
+        rg.response.release_type.primary = None;
+        assert_eq!(rg.to_string(), "OK Computer".to_string());
+    }
+
+    #[test]
+    fn best_release() {
+        let release = |mbid: &str, date: &str, status, country: &str, format: &str| ReleaseRef {
+            mbid: Mbid::from_str(mbid).unwrap(),
+            title: "OK Computer".to_string(),
+            date: PartialDate::from_str(date).ok(),
+            status,
+            country: Country::from_code(country).ok(),
+            formats: vec![format.to_string()],
+        };
+        let official = Some(ReleaseStatus::Official);
+        let releases = vec![
+            release("e5f3b2a4-6c8d-4e1f-9a0b-1c2d3e4f5a6b", "1997-05-21", None, "GB", "CD"),
+            release("0b6b4ba0-d36f-47bd-b4ea-6a5b91842d29", "1997-05-28", official, "US", "CD"),
+            release("a1b2c3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d", "1997-06", official, "GB", "Vinyl"),
+            release("f47ac10b-58cc-4372-a567-0e02b2c3d479", "", official, "GB", "CD"),
+        ];
+        let best = |preferences: ReleasePreferences| {
+            ReleaseGroup::best_release(&releases, &preferences).unwrap().mbid.clone()
+        };
+
+        assert_eq!(best(ReleasePreferences::new()), releases[1].mbid);
+        assert_eq!(best(ReleasePreferences::new().country("gb")), releases[2].mbid);
+        assert_eq!(
+            best(ReleasePreferences::new().country("GB").format("cd")),
+            releases[3].mbid
+        );
+        assert_eq!(best(ReleasePreferences::new().prefer_official(false)), releases[0].mbid);
+        assert_eq!(ReleaseGroup::best_release(&[], &ReleasePreferences::new()), None);
     }
 }
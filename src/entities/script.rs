@@ -0,0 +1,91 @@
+use crate::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
+
+/// A writing system, identified by its ISO 15924 code, e.g. `Latn` for the
+/// latin alphabet or `Jpan` for Japanese.
+///
+/// Codes are validated to consist of four ASCII letters and normalized to the
+/// usual capitalization, so `latn` and `LATN` are both parsed as `Latn`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Script {
+    code: [u8; 4],
+}
+
+impl Script {
+    /// Construct a new instance from an ISO 15924 code.
+    pub fn from_code(code: &str) -> Result<Script, Error> {
+        let bytes = code.as_bytes();
+        if bytes.len() != 4 || !bytes.iter().all(u8::is_ascii_alphabetic) {
+            return Err(Error::parse_error(format!("Invalid ISO 15924 code: '{}'", code)));
+        }
+
+        let mut normalized = [0; 4];
+        for (i, b) in bytes.iter().enumerate() {
+            normalized[i] = if i == 0 {
+                b.to_ascii_uppercase()
+            } else {
+                b.to_ascii_lowercase()
+            };
+        }
+        Ok(Script { code: normalized })
+    }
+
+    /// Return the ISO 15924 code, e.g. `Latn`.
+    pub fn code(&self) -> &str {
+        // Only ASCII letters are ever stored.
+        ::std::str::from_utf8(&self.code).unwrap()
+    }
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Script [ISO 15924: {}]", self.code())
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+serialize_as_string!(Script);
+
+impl FromStr for Script {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Script::from_code(s)
+    }
+}
+
+impl FromXmlOptional for Script {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, xpath_reader::Error> {
+        match Option::<String>::from_xml(reader)? {
+            Some(s) => Script::from_code(s.as_str())
+                .map(Some)
+                .map_err(|e| xpath_reader::Error::custom_msg(format!("parse script error: {}", e))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(Script::from_code("Latn").unwrap().code(), "Latn");
+        assert_eq!(Script::from_code("jpan").unwrap().code(), "Jpan");
+        assert_eq!(Script::from_code("CYRL").unwrap().to_string(), "Cyrl");
+        assert_eq!("Qaaa".parse::<Script>().unwrap(), Script::from_code("qaaa").unwrap());
+
+        assert!(Script::from_code("Lat").is_err());
+        assert!(Script::from_code("Latin").is_err());
+        assert!(Script::from_code("La1n").is_err());
+        assert!(Script::from_code("Läti").is_err());
+    }
+}
@@ -1,6 +1,9 @@
-use crate::entities::{Mbid, PartialDate, ResourceOld};
-use crate::entities::refs::AreaRef;
-use xpath_reader::{FromXml, Error, Reader};
+use crate::client::Request;
+use crate::entities::{include, join_includes, EntityKind, Genre, Include, Mbid, OnRequest,
+                      Relation, Resource, Tag};
+use xpath_reader::{FromXml, Reader};
+
+use std::fmt;
 
 enum_mb_xml! {
     pub enum SeriesType {
@@ -13,59 +16,273 @@ enum_mb_xml! {
         var Tour = "Tour",
         var Festival = "Festival",
         var Run = "Run",
+        unknown Unknown,
     }
 }
 
-/// TODO: Can't we read some of the relationships? Like this this is a rather
-/// useless type.
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// A sequence of separate release groups, releases, recordings, works or
+/// events with a common theme, e.g. a catalogue of works.
+///
+/// Additional information can be found in the [MusicBrainz
+/// docs](https://musicbrainz.org/doc/Series).
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Series {
+    response: SeriesResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: SeriesOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct SeriesOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+    pub tags: bool,
+    pub genres: bool,
+
+    /// Request the relationships to works, e.g. the works which are part of
+    /// a catalogue.
+    pub work_rels: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SeriesResponse {
+    mbid: Mbid,
+    name: String,
+    series_type: SeriesType,
+    aliases: Vec<String>,
+    disambiguation: Option<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    work_rels: Vec<Relation>,
+}
+
+impl Series {
     /// MBID of the entity in the MusicBrainz database.
-    pub mbid: Mbid,
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the series on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Series.permalink(self.mbid())
+    }
+
+    /// The official name of the series.
+    pub fn name(&self) -> &String {
+        &self.response.name
+    }
 
     /// Type of the series.
-    pub series_type: SeriesType,
+    pub fn series_type(&self) -> SeriesType {
+        self.response.series_type.clone()
+    }
 
-    pub aliases: Vec<String>,
+    /// Alternative names of the series, e.g. the abbreviation of a catalogue.
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
 
-    pub disambiguation: Option<String>,
+    /// Disambiguation comment.
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
 
     /// Any additional free form annotation for this `Series`.
-    pub annotation: Option<String>,
-    // TODO parse work rels
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
+
+    /// The tags users attached to the series.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the series.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// Relationships to works, for a catalogue the works in it along with
+    /// their catalogue numbers.
+    pub fn work_rels(&self) -> OnRequest<&[Relation]> {
+        OnRequest::from_value(self.response.work_rels.as_ref(), self.options.work_rels)
+    }
+}
+
+impl fmt::Debug for Series {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Series")
+            .field("mbid", self.mbid())
+            .field("name", self.name())
+            .field("series_type", &self.series_type())
+            .field("disambiguation", &self.disambiguation())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("work_rels", &self.work_rels())
+            .finish()
+    }
+}
+
+impl SeriesOptions {
+    pub fn everything() -> Self {
+        SeriesOptions {
+            aliases: true,
+            annotation: true,
+            tags: true,
+            genres: true,
+            work_rels: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        SeriesOptions {
+            aliases: false,
+            annotation: false,
+            tags: false,
+            genres: false,
+            work_rels: false,
+        }
+    }
+
+    /// Request what is usually shown on a series page: the aliases and the
+    /// works in the series.
+    pub fn for_display() -> Self {
+        SeriesOptions {
+            aliases: true,
+            work_rels: true,
+            ..SeriesOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        SeriesOptions {
+            annotation: true,
+            genres: true,
+            ..SeriesOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for series.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = SeriesOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::WorkRels => options.work_rels = true,
+                _ => return Err(include::unsupported(EntityKind::Series, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.work_rels {
+            includes.push(Include::WorkRels);
+        }
+        includes
+    }
+}
+
+/// The aliases, the annotation and the works, which were always requested
+/// before series had options.
+impl Default for SeriesOptions {
+    fn default() -> Self {
+        SeriesOptions {
+            aliases: true,
+            annotation: true,
+            work_rels: true,
+            ..SeriesOptions::minimal()
+        }
+    }
 }
 
-impl FromXml for Series {
-    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
-        Ok(Series {
-            mbid: reader.read(".//mb:series/@id")?,
-            series_type: reader.read(".//mb:series/@type")?,
-            aliases: reader.read(".//mb:series/mb:alias-list/mb:alias/text()")?,
-            disambiguation: reader.read(".//mb:series/mb:disambiguation/text()")?,
-            annotation: reader.read(".//mb:series/mb:annotation/text()")?,
-        })
+from_xml_paths! {
+    SeriesResponse at ".//mb:series/" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        series_type: "@type",
+        aliases: "mb:alias-list/mb:alias/text()",
+        disambiguation: "mb:disambiguation/text()",
+        annotation: "mb:annotation/text()",
+        tags: "mb:tag-list/mb:tag",
+        genres: "mb:genre-list/mb:genre",
+        work_rels: "mb:relation-list[@target-type='work']/mb:relation",
     }
 }
 
-impl ResourceOld for Series {
+impl Resource for Series {
+    type Options = SeriesOptions;
+    type Response = SeriesResponse;
     const NAME: &'static str = "series";
-    const INCL: &'static str = "annotation+aliases+work-rels";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "series".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Series { response, options }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::entities::RelationTarget;
     use std::str::FromStr;
 
     #[test]
     fn read_series_1() {
         let mbid = Mbid::from_str("d977f7fd-96c9-4e3e-83b5-eb484a9e6582").unwrap();
-        let series: Series = crate::util::test_utils::fetch_entity_old(&mbid).unwrap();
+        let series: Series =
+            crate::util::test_utils::fetch_entity(&mbid, SeriesOptions::default()).unwrap();
+
+        assert_eq!(series.mbid(), &mbid);
+        assert_eq!(series.name(), &"Bach-Werke-Verzeichnis".to_string());
+        assert_eq!(series.series_type(), SeriesType::Catalogue);
+        assert_eq!(series.aliases().unwrap(), &["BWV".to_string()]);
+        assert_eq!(series.disambiguation(), None);
+        assert_eq!(series.annotation(), OnRequest::NotAvailable);
+        assert_eq!(series.tags(), OnRequest::NotRequested);
 
-        assert_eq!(series.mbid, mbid);
-        assert_eq!(series.series_type, SeriesType::Catalogue);
-        assert_eq!(series.aliases, vec!["BWV".to_string()]);
-        assert_eq!(series.disambiguation, None);
-        assert_eq!(series.annotation, None);
+        let work_rels = series.work_rels().unwrap();
+        assert_eq!(work_rels.len(), 1234);
+        assert_eq!(work_rels[0].relation_type, "part of");
+        assert_eq!(work_rels[0].attributes, vec!["number".to_string()]);
+        match work_rels[0].target {
+            RelationTarget::Work(ref work) => assert_eq!(
+                work.title,
+                "The Well-Tempered Clavier, Book I: Prelude and Fugue no. 9 in E major, BWV 854"
+            ),
+            ref target => panic!("Unexpected target: {:?}", target),
+        }
     }
 }
@@ -0,0 +1,103 @@
+//! Folksonomy tags, genres and ratings which users can attach to entities.
+
+use xpath_reader::{FromXml, FromXmlOptional, Reader};
+
+use crate::entities::Mbid;
+
+/// A tag users attached to an entity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Tag {
+    /// The name of the tag, always lowercase.
+    pub name: String,
+
+    /// How many users attached the tag, minus the users who voted against it.
+    pub count: i32,
+}
+
+from_xml_paths! {
+    Tag at "./" {
+        name: "mb:name/text()",
+        count: "@count",
+    }
+}
+
+/// A genre attached to an entity.
+///
+/// Genres are tags which MusicBrainz considers to be genres, so every genre
+/// is also contained in the tags of the entity.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Genre {
+    /// MBID of the genre.
+    pub mbid: Mbid,
+
+    /// The name of the genre.
+    pub name: String,
+
+    /// How many users attached the genre, minus the users who voted against
+    /// it.
+    pub count: i32,
+}
+
+from_xml_paths! {
+    Genre at "./" {
+        mbid: "@id",
+        name: "mb:name/text()",
+        count: "@count",
+    }
+}
+
+#[cfg(feature = "streaming")]
+mod streaming {
+    use super::*;
+    use crate::streaming::{require, Node};
+    use crate::Error;
+
+    impl Tag {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(Tag {
+                name: require(node.text_at(&["name"]), "tag name")?,
+                count: require(node.attribute("count"), "tag count")?,
+            })
+        }
+    }
+
+    impl Genre {
+        pub(crate) fn from_node(node: &Node) -> Result<Self, Error> {
+            Ok(Genre {
+                mbid: require(node.attribute("id"), "genre id")?,
+                name: require(node.text_at(&["name"]), "genre name")?,
+                count: require(node.attribute("count"), "genre count")?,
+            })
+        }
+    }
+}
+
+/// The average rating users gave an entity.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Rating {
+    /// The average rating between 0 and 5, `None` if nobody rated the entity.
+    pub value: Option<f32>,
+
+    /// The number of users who rated the entity.
+    pub votes: u32,
+}
+
+impl FromXmlOptional for Rating {
+    fn from_xml_optional<'d>(reader: &'d Reader<'d>) -> Result<Option<Self>, xpath_reader::Error> {
+        if reader.anchor_nodeset().size() < 1 {
+            return Ok(None);
+        }
+
+        let value: Option<String> = reader.read("./text()")?;
+        Ok(Some(Rating {
+            value: match value {
+                Some(v) => Some(v.trim().parse().map_err(xpath_reader::Error::custom_err)?),
+                None => None,
+            },
+            votes: reader.read("./@votes-count")?,
+        }))
+    }
+}
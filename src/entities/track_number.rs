@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+/// A parsed track number, as printed on a release.
+///
+/// CDs usually number their tracks, while vinyl and cassettes use the side
+/// followed by the position on the side, e.g. `A1`, `B2` or just `AA` for the
+/// flip side of a single.
+///
+/// Track numbers are ordered numerically and by side, so mixed formats sort
+/// the way they appear on the release. Numeric track numbers come before any
+/// sides and unrecognized ones come last.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum TrackNumber {
+    /// A plain number, e.g. `7`.
+    Numeric(u16),
+
+    /// A side with an optional position on it, e.g. `A1` or `AA`.
+    Side { side: String, index: Option<u16> },
+
+    /// Any other track number, kept verbatim.
+    Other(String),
+}
+
+impl TrackNumber {
+    /// Parse a track number, falling back to `Other` if it doesn't match any
+    /// known format.
+    pub fn parse(number: &str) -> Self {
+        let number = number.trim();
+        if let Ok(n) = number.parse() {
+            return TrackNumber::Numeric(n);
+        }
+
+        let split = number
+            .find(|c: char| !c.is_ascii_uppercase())
+            .unwrap_or_else(|| number.len());
+        let (side, index) = number.split_at(split);
+        if !side.is_empty() {
+            if index.is_empty() {
+                return TrackNumber::Side {
+                    side: side.to_string(),
+                    index: None,
+                };
+            }
+            if let Ok(index) = index.parse() {
+                return TrackNumber::Side {
+                    side: side.to_string(),
+                    index: Some(index),
+                };
+            }
+        }
+
+        TrackNumber::Other(number.to_string())
+    }
+
+    /// Rank of the variant in the total order.
+    fn rank(&self) -> u8 {
+        match *self {
+            TrackNumber::Numeric(_) => 0,
+            TrackNumber::Side { .. } => 1,
+            TrackNumber::Other(_) => 2,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for TrackNumber {
+    fn from(number: &'a str) -> Self {
+        TrackNumber::parse(number)
+    }
+}
+
+impl Display for TrackNumber {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            TrackNumber::Numeric(n) => write!(f, "{}", n),
+            TrackNumber::Side {
+                ref side,
+                index: Some(index),
+            } => write!(f, "{}{}", side, index),
+            TrackNumber::Side {
+                ref side,
+                index: None,
+            } => write!(f, "{}", side),
+            TrackNumber::Other(ref s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Ord for TrackNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (TrackNumber::Numeric(a), TrackNumber::Numeric(b)) => a.cmp(b),
+            // Shorter sides first, so `B` comes before `AA`.
+            (
+                TrackNumber::Side {
+                    side: side_a,
+                    index: index_a,
+                },
+                TrackNumber::Side {
+                    side: side_b,
+                    index: index_b,
+                },
+            ) => (side_a.len(), side_a, index_a).cmp(&(side_b.len(), side_b, index_b)),
+            (TrackNumber::Other(a), TrackNumber::Other(b)) => a.cmp(b),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+impl PartialOrd for TrackNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(TrackNumber::parse("12"), TrackNumber::Numeric(12));
+        assert_eq!(
+            TrackNumber::parse("B2"),
+            TrackNumber::Side {
+                side: "B".to_string(),
+                index: Some(2),
+            }
+        );
+        assert_eq!(
+            TrackNumber::parse("AA"),
+            TrackNumber::Side {
+                side: "AA".to_string(),
+                index: None,
+            }
+        );
+        assert_eq!(
+            TrackNumber::parse("1-3"),
+            TrackNumber::Other("1-3".to_string())
+        );
+        assert_eq!(TrackNumber::parse("C10").to_string(), "C10");
+    }
+
+    #[test]
+    fn ordering() {
+        let mut numbers: Vec<TrackNumber> = vec!["AA", "B1", "x", "A10", "3", "A2", "B", "1"]
+            .into_iter()
+            .map(TrackNumber::from)
+            .collect();
+        numbers.sort();
+
+        let sorted: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+        assert_eq!(sorted, vec!["1", "3", "A2", "A10", "B", "B1", "AA", "x"]);
+    }
+}
@@ -0,0 +1,412 @@
+use xpath_reader::{FromXml, FromXmlOptional, Error, Reader};
+
+use crate::client::Request;
+use crate::entities::{include, join_includes, write_summary, EntityKind, Genre, Include,
+                      Language, Mbid, OnRequest, Rating, Resource, Tag};
+
+use std::fmt;
+
+enum_mb_xml_optional! {
+    pub enum WorkType {
+        var Aria = "Aria",
+        var AudioDrama = "Audio drama",
+        var Ballet = "Ballet",
+        var BeijingOpera = "Beijing opera",
+        var Cantata = "Cantata",
+        var Concerto = "Concerto",
+        var Etude = "Étude",
+        var IncidentalMusic = "Incidental music",
+        var Madrigal = "Madrigal",
+        var Mass = "Mass",
+        var Motet = "Motet",
+        var Musical = "Musical",
+        var Opera = "Opera",
+        var Operetta = "Operetta",
+        var Oratorio = "Oratorio",
+        var Overture = "Overture",
+        var Partita = "Partita",
+        var Play = "Play",
+        var Poem = "Poem",
+        var Prose = "Prose",
+        var Quartet = "Quartet",
+        var Sonata = "Sonata",
+        var Song = "Song",
+        var SongCycle = "Song-cycle",
+        var Soundtrack = "Soundtrack",
+        var Suite = "Suite",
+        var SymphonicPoem = "Symphonic poem",
+        var Symphony = "Symphony",
+        var Zarzuela = "Zarzuela",
+        unknown Unknown,
+    }
+}
+
+/// An attribute of a work, e.g. its key or a catalogue number.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WorkAttribute {
+    /// The kind of the attribute, e.g. `Key` or `Opus number`.
+    pub attribute_type: String,
+
+    /// The value of the attribute.
+    pub value: String,
+}
+
+impl FromXml for WorkAttribute {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
+        Ok(WorkAttribute {
+            attribute_type: reader.read("./@type")?,
+            value: reader.read("./text()")?,
+        })
+    }
+}
+
+/// A distinct intellectual or artistic creation, e.g. a song or a symphony.
+///
+/// Additional information can be found in the [MusicBrainz
+/// docs](https://musicbrainz.org/doc/Work)
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Work {
+    response: WorkResponse,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    options: WorkOptions,
+}
+
+#[derive(Clone, Debug)]
+pub struct WorkOptions {
+    pub aliases: bool,
+    pub annotation: bool,
+    pub tags: bool,
+    pub genres: bool,
+    pub ratings: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WorkResponse {
+    mbid: Mbid,
+    title: String,
+    work_type: Option<WorkType>,
+    languages: Vec<Language>,
+    iswcs: Vec<String>,
+    attributes: Vec<WorkAttribute>,
+    disambiguation: Option<String>,
+    aliases: Vec<String>,
+    annotation: Option<String>,
+    tags: Vec<Tag>,
+    genres: Vec<Genre>,
+    rating: Option<Rating>,
+}
+
+impl Work {
+    /// MBID of the entity in the MusicBrainz database.
+    pub fn mbid(&self) -> &Mbid {
+        &self.response.mbid
+    }
+
+    /// Link to the work on the MusicBrainz website.
+    pub fn permalink(&self) -> String {
+        EntityKind::Work.permalink(self.mbid())
+    }
+
+    /// The canonical title of the work.
+    pub fn title(&self) -> &String {
+        &self.response.title
+    }
+
+    /// Describes what type of work this is exactly.
+    pub fn work_type(&self) -> Option<WorkType> {
+        self.response.work_type.clone()
+    }
+
+    /// The languages of the lyrics of the work.
+    ///
+    /// Empty for works without lyrics or if the languages aren't known.
+    pub fn languages(&self) -> &[Language] {
+        &self.response.languages
+    }
+
+    /// ISWCs (International Standard Musical Work Codes) assigned to the work.
+    pub fn iswcs(&self) -> &[String] {
+        &self.response.iswcs
+    }
+
+    /// Attributes of the work, like its key or catalogue numbers.
+    pub fn attributes(&self) -> &[WorkAttribute] {
+        &self.response.attributes
+    }
+
+    /// Disambiguation comment.
+    pub fn disambiguation(&self) -> Option<&String> {
+        self.response.disambiguation.as_ref()
+    }
+
+    /// Alternative titles of the work.
+    pub fn aliases(&self) -> OnRequest<&[String]> {
+        OnRequest::from_value(self.response.aliases.as_ref(), self.options.aliases)
+    }
+
+    /// Any additional free form annotation for this `Work`.
+    pub fn annotation(&self) -> OnRequest<&String> {
+        OnRequest::from_option(self.response.annotation.as_ref(), self.options.annotation)
+    }
+
+    /// The tags users attached to the work.
+    pub fn tags(&self) -> OnRequest<&[Tag]> {
+        OnRequest::from_value(self.response.tags.as_ref(), self.options.tags)
+    }
+
+    /// The genres of the work.
+    pub fn genres(&self) -> OnRequest<&[Genre]> {
+        OnRequest::from_value(self.response.genres.as_ref(), self.options.genres)
+    }
+
+    /// The average rating users gave the work.
+    pub fn rating(&self) -> OnRequest<&Rating> {
+        OnRequest::from_option(self.response.rating.as_ref(), self.options.ratings)
+    }
+
+    /// The values of all attributes of the type `attribute_type`.
+    pub fn attribute_values(&self, attribute_type: &str) -> Vec<&str> {
+        self.attributes()
+            .iter()
+            .filter(|a| a.attribute_type == attribute_type)
+            .map(|a| a.value.as_str())
+            .collect()
+    }
+
+    /// The key of the work, e.g. `D minor`.
+    pub fn key(&self) -> Option<&str> {
+        self.attribute_values("Key").into_iter().next()
+    }
+
+    /// The opus numbers of the work.
+    pub fn opus_numbers(&self) -> Vec<&str> {
+        self.attribute_values("Opus number")
+    }
+}
+
+impl fmt::Debug for Work {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Work")
+            .field("mbid", self.mbid())
+            .field("title", self.title())
+            .field("work_type", &self.work_type())
+            .field("languages", &self.languages())
+            .field("iswcs", &self.iswcs())
+            .field("attributes", &self.attributes())
+            .field("disambiguation", &self.disambiguation())
+            .field("aliases", &self.aliases())
+            .field("annotation", &self.annotation())
+            .field("tags", &self.tags())
+            .field("genres", &self.genres())
+            .field("rating", &self.rating())
+            .finish()
+    }
+}
+
+impl fmt::Display for Work {
+    /// Formats the work as e.g. `Symphony no. 9 (Symphony, D minor)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_summary(
+            f,
+            self.title(),
+            &[
+                self.work_type().map(|t| t.to_string()),
+                self.key().map(|k| k.to_string()),
+            ],
+        )
+    }
+}
+
+impl WorkOptions {
+    pub fn everything() -> Self {
+        WorkOptions {
+            aliases: true,
+            annotation: true,
+            tags: true,
+            genres: true,
+            ratings: true,
+        }
+    }
+
+    pub fn minimal() -> Self {
+        WorkOptions {
+            aliases: false,
+            annotation: false,
+            tags: false,
+            genres: false,
+            ratings: false,
+        }
+    }
+
+    /// Request what is usually shown on a work page: the aliases, tags and
+    /// genres.
+    pub fn for_display() -> Self {
+        WorkOptions {
+            aliases: true,
+            tags: true,
+            genres: true,
+            ..WorkOptions::minimal()
+        }
+    }
+
+    /// Request the annotation and genres, see `ResponseMeta::same_version`.
+    pub fn for_mirror() -> Self {
+        WorkOptions {
+            annotation: true,
+            genres: true,
+            ..WorkOptions::minimal()
+        }
+    }
+
+    /// Request the data of `includes`, failing if one of them isn't
+    /// supported for works.
+    pub fn from_includes(includes: &[Include]) -> Result<Self, crate::Error> {
+        let mut options = WorkOptions::minimal();
+        for &include in includes {
+            match include {
+                Include::Aliases => options.aliases = true,
+                Include::Annotation => options.annotation = true,
+                Include::Tags => options.tags = true,
+                Include::Genres => options.genres = true,
+                Include::Ratings => options.ratings = true,
+                _ => return Err(include::unsupported(EntityKind::Work, include)),
+            }
+        }
+        Ok(options)
+    }
+
+    /// The includes requested by these options.
+    pub fn includes(&self) -> Vec<Include> {
+        let mut includes = Vec::new();
+
+        if self.aliases {
+            includes.push(Include::Aliases);
+        }
+        if self.annotation {
+            includes.push(Include::Annotation);
+        }
+        if self.tags {
+            includes.push(Include::Tags);
+        }
+        if self.genres {
+            includes.push(Include::Genres);
+        }
+        if self.ratings {
+            includes.push(Include::Ratings);
+        }
+        includes
+    }
+}
+
+/// The annotation, which was always requested before works had options.
+impl Default for WorkOptions {
+    fn default() -> Self {
+        WorkOptions {
+            annotation: true,
+            ..WorkOptions::minimal()
+        }
+    }
+}
+
+impl FromXml for WorkResponse {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, Error> {
+        // Older responses only contain a single language element, newer ones
+        // contain a list.
+        let mut languages: Vec<String> =
+            reader.read("//mb:metadata/mb:work/mb:language-list/mb:language/text()")?;
+        if languages.is_empty() {
+            let language: Option<String> =
+                reader.read("//mb:metadata/mb:work/mb:language/text()")?;
+            languages.extend(language);
+        }
+        let languages = languages
+            .into_iter()
+            .map(|l| {
+                Language::from_639_3(l.as_str())
+                    .map_err(|e| Error::custom_msg(format!("parse language error: {}", e)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(WorkResponse {
+            mbid: reader.read("//mb:metadata/mb:work/@id")?,
+            title: reader.read("//mb:metadata/mb:work/mb:title/text()")?,
+            work_type: reader.read("//mb:metadata/mb:work/@type")?,
+            languages,
+            iswcs: reader.read("//mb:metadata/mb:work/mb:iswc-list/mb:iswc/text()")?,
+            attributes: reader.read("//mb:metadata/mb:work/mb:attribute-list/mb:attribute")?,
+            disambiguation: reader.read("//mb:metadata/mb:work/mb:disambiguation/text()")?,
+            aliases: reader.read("//mb:metadata/mb:work/mb:alias-list/mb:alias/text()")?,
+            annotation: reader.read("//mb:metadata/mb:work/mb:annotation/mb:text/text()")?,
+            tags: reader.read("//mb:metadata/mb:work/mb:tag-list/mb:tag")?,
+            genres: reader.read("//mb:metadata/mb:work/mb:genre-list/mb:genre")?,
+            rating: reader.read("//mb:metadata/mb:work/mb:rating")?,
+        })
+    }
+}
+
+impl Resource for Work {
+    type Options = WorkOptions;
+    type Response = WorkResponse;
+    const NAME: &'static str = "work";
+
+    fn request(options: &Self::Options) -> Request {
+        Request {
+            name: "work".into(),
+            include: join_includes(&options.includes()),
+        }
+    }
+
+    fn from_response(response: Self::Response, options: Self::Options) -> Self {
+        Work { response, options }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn parse(xml: &str) -> Work {
+        crate::util::test_utils::parse_entity(xml, WorkOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn read_attributes() {
+        let work = parse(r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><work type="Symphony" id="5d6e3e69-1aa9-4a6b-8e1b-4d8c1b8c0fb4"><title>Symphony no. 9 in D minor, op. 125</title><language-list><language>deu</language><language>eng</language></language-list><iswc-list><iswc>T-905.029.737-5</iswc></iswc-list><attribute-list><attribute type="Key" type-id="7526c19d-3be4-3420-b6cc-9fb6e49fa1a9">D minor</attribute><attribute type="Opus number" type-id="b9b2ffde-1b2a-4ea4-98bf-a25ee4a3ad43">125</attribute></attribute-list></work></metadata>"#);
+
+        assert_eq!(
+            work.mbid(),
+            &Mbid::from_str("5d6e3e69-1aa9-4a6b-8e1b-4d8c1b8c0fb4").unwrap()
+        );
+        assert_eq!(work.work_type(), Some(WorkType::Symphony));
+        assert_eq!(
+            work.permalink(),
+            "https://musicbrainz.org/work/5d6e3e69-1aa9-4a6b-8e1b-4d8c1b8c0fb4"
+        );
+        assert_eq!(
+            work.languages(),
+            &[
+                Language::from_639_3("deu").unwrap(),
+                Language::from_639_3("eng").unwrap(),
+            ]
+        );
+        assert_eq!(work.iswcs(), &["T-905.029.737-5".to_string()]);
+        assert_eq!(work.key(), Some("D minor"));
+        assert_eq!(work.opus_numbers(), vec!["125"]);
+        assert_eq!(work.annotation(), OnRequest::NotAvailable);
+        assert_eq!(work.aliases(), OnRequest::NotRequested);
+    }
+
+    #[test]
+    fn read_single_language() {
+        let work = parse(r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><work type="Song" id="5d6e3e69-1aa9-4a6b-8e1b-4d8c1b8c0fb4"><title>Yesterday</title><language>eng</language></work></metadata>"#);
+
+        assert_eq!(work.languages(), &[Language::from_639_3("eng").unwrap()]);
+        assert_eq!(work.key(), None);
+        assert!(work.opus_numbers().is_empty());
+    }
+}
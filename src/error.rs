@@ -1,12 +1,35 @@
 use backtrace::Backtrace;
+use std::cmp::min;
 use std::fmt;
 
+use crate::client::HttpResponse;
+
+/// Headers of failed responses which are kept in the error, as they tell why
+/// the request failed or when it can be retried.
+const RELEVANT_HEADERS: &[&str] = &[
+    "Content-Type",
+    "Retry-After",
+    "Server",
+    "X-RateLimit-Limit",
+    "X-RateLimit-Remaining",
+    "X-RateLimit-Reset",
+];
+
 #[derive(Clone, Debug)]
 pub struct Error {
     // TODO: Make it possible to disable backtraces for performance reasons?
     backtrace: Backtrace,
     message: String,
     kind: ErrorKind,
+    /// The (possibly truncated) body of the response which failed to parse,
+    /// if the client was configured to retain it.
+    raw_body: Option<String>,
+    /// The HTTP status of the response which caused the error.
+    status: Option<u16>,
+    /// The url of the request which failed.
+    url: Option<String>,
+    /// The `RELEVANT_HEADERS` of the response which caused the error.
+    headers: Vec<(String, String)>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Copy)]
@@ -22,6 +45,30 @@ pub(crate) enum ErrorKind {
 
     /// The server returned an error message.
     ServerError,
+
+    /// The requested resource doesn't exist.
+    NotFound,
+
+    /// The server rejected the request as invalid.
+    BadRequest,
+
+    /// The server refused the request because of too many requests.
+    RateLimited,
+
+    /// The request was cancelled by the user.
+    Cancelled,
+
+    /// The request took longer than allowed by the configuration.
+    Timeout,
+
+    /// The crate was used incorrectly, e.g. an invalid value was provided.
+    UsageError,
+
+    /// Reading or writing local data failed.
+    Io,
+
+    /// The response was larger than allowed by the configuration.
+    ResponseTooLarge,
 }
 
 impl ErrorKind {
@@ -29,7 +76,16 @@ impl ErrorKind {
     pub fn is_bug(&self) -> bool {
         match self {
             ErrorKind::ParseResponse | ErrorKind::Internal => true,
-            ErrorKind::Communication | ErrorKind::ServerError => false,
+            ErrorKind::Communication
+            | ErrorKind::ServerError
+            | ErrorKind::NotFound
+            | ErrorKind::BadRequest
+            | ErrorKind::RateLimited
+            | ErrorKind::Cancelled
+            | ErrorKind::Timeout
+            | ErrorKind::UsageError
+            | ErrorKind::Io
+            | ErrorKind::ResponseTooLarge => false,
         }
     }
 }
@@ -40,6 +96,10 @@ impl Error {
             message: msg.into(),
             kind,
             backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
         }
     }
 
@@ -48,8 +108,128 @@ impl Error {
             message: msg.into(),
             kind: ErrorKind::ParseResponse,
             backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
         }
     }
+
+    /// Attach the body of the response which caused the error, truncated to
+    /// at most `limit` bytes.
+    pub(crate) fn with_raw_body(mut self, body: &str, limit: usize) -> Error {
+        let mut end = min(limit, body.len());
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.raw_body = Some(body[..end].to_string());
+        self
+    }
+
+    /// Attach the url of the request which failed.
+    pub(crate) fn with_url(mut self, url: &str) -> Error {
+        self.url = Some(url.to_string());
+        self
+    }
+
+    /// Attach the url of the request and the status and relevant headers of
+    /// the response which caused the error.
+    pub(crate) fn with_response(mut self, url: &str, response: &HttpResponse) -> Error {
+        self.url = Some(url.to_string());
+        self.status = Some(response.status);
+        self.headers = RELEVANT_HEADERS
+            .iter()
+            .filter_map(|&name| {
+                response
+                    .header(name)
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        self
+    }
+
+    /// The url of the request which failed, if the error was caused by a
+    /// response.
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_ref().map(|u| u.as_str())
+    }
+
+    /// The HTTP status of the response which caused the error, if the server
+    /// answered with an error status.
+    pub fn status(&self) -> Option<u16> {
+        self.status
+    }
+
+    /// The value of a header of the response which caused the error, e.g.
+    /// `Retry-After`.
+    ///
+    /// Only headers describing the failure are kept, like the ones about
+    /// rate limiting.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, ref v)| v.as_str())
+    }
+
+    /// True if the server returned an error message, e.g. because it failed
+    /// to handle the request.
+    pub fn is_server_error(&self) -> bool {
+        self.kind == ErrorKind::ServerError
+    }
+
+    /// True if the requested resource doesn't exist, e.g. because of an
+    /// unknown MBID.
+    pub fn is_not_found(&self) -> bool {
+        self.kind == ErrorKind::NotFound
+    }
+
+    /// True if the server rejected the request as invalid, e.g. because of
+    /// an unsupported include.
+    pub fn is_bad_request(&self) -> bool {
+        self.kind == ErrorKind::BadRequest
+    }
+
+    /// True if the server refused the request because too many requests
+    /// were made.
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind == ErrorKind::RateLimited
+    }
+
+    /// True if the error was caused by a response which couldn't be parsed.
+    pub fn is_parse_error(&self) -> bool {
+        self.kind == ErrorKind::ParseResponse
+    }
+
+    /// The body of the response which couldn't be parsed.
+    ///
+    /// This is only available if enabled with `Client::retain_raw_bodies`
+    /// and might be truncated.
+    pub fn raw_body(&self) -> Option<&str> {
+        self.raw_body.as_ref().map(|b| b.as_str())
+    }
+
+    /// True if the error was caused by cancelling the request.
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == ErrorKind::Cancelled
+    }
+
+    /// True if the error was caused by the request taking too long.
+    pub fn is_timeout(&self) -> bool {
+        self.kind == ErrorKind::Timeout
+    }
+
+    /// True if the error was caused by using the crate incorrectly, e.g. by
+    /// providing invalid values.
+    pub fn is_usage_error(&self) -> bool {
+        self.kind == ErrorKind::UsageError
+    }
+
+    /// True if the response was larger than the maximal response size of
+    /// the client.
+    pub fn is_response_too_large(&self) -> bool {
+        self.kind == ErrorKind::ResponseTooLarge
+    }
 }
 
 impl std::error::Error for Error {}
@@ -69,41 +249,152 @@ impl fmt::Display for Error {
             ErrorKind::ServerError => {
                 writeln!(f, "[server error]: {}", self.message)?;
             }
+            ErrorKind::NotFound => {
+                writeln!(f, "[not found]: {}", self.message)?;
+            }
+            ErrorKind::BadRequest => {
+                writeln!(f, "[bad request]: {}", self.message)?;
+            }
+            ErrorKind::RateLimited => {
+                writeln!(f, "[rate limited]: {}", self.message)?;
+            }
+            ErrorKind::Cancelled => {
+                writeln!(f, "[cancelled]: {}", self.message)?;
+            }
+            ErrorKind::Timeout => {
+                writeln!(f, "[timeout]: {}", self.message)?;
+            }
+            ErrorKind::UsageError => {
+                writeln!(f, "[usage error]: {}", self.message)?;
+            }
+            ErrorKind::Io => {
+                writeln!(f, "[io error]: {}", self.message)?;
+            }
+            ErrorKind::ResponseTooLarge => {
+                writeln!(f, "[response too large]: {}", self.message)?;
+            }
         }
         if self.kind.is_bug() {
             writeln!(f, "This might be a bug that should be reported upstream.")?;
         }
+        if let Some(ref url) = self.url {
+            writeln!(f, "Url: {}", url)?;
+        }
+        if let Some(ref body) = self.raw_body {
+            writeln!(f, "Response body: {}", body)?;
+        }
         writeln!(f, "Backtrace: {:?}", self.backtrace)?;
         Ok(())
     }
 }
 
+/// A part of a response which couldn't be parsed and was skipped, because the
+/// client is configured to be lenient.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseWarning {
+    /// Describes which part of the response was skipped, e.g. `medium 1,
+    /// track 3`.
+    pub location: String,
+
+    /// Why the part couldn't be parsed.
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Skipped {}: {}", self.location, self.message)
+    }
+}
+
 impl From<xpath_reader::Error> for Error {
     fn from(e: xpath_reader::Error) -> Self {
         Error {
             message: format!("xpath_reader error: {}", e),
             kind: ErrorKind::ParseResponse,
             backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "default-transport")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error {
+            message: format!("reqwest error: {}", e),
+            kind: ErrorKind::Communication,
+            backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
         }
     }
 }
 
+#[cfg(any(feature = "default-transport", test))]
 impl From<reqwest_mock::Error> for Error {
     fn from(e: reqwest_mock::Error) -> Self {
         Error {
             message: format!("reqwest_mock parse error: {}", e),
             kind: ErrorKind::Internal,
             backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
         }
     }
 }
 
-impl From<reqwest_mock::UrlError> for Error {
-    fn from(e: reqwest_mock::UrlError) -> Self {
+impl From<url::ParseError> for Error {
+    fn from(e: url::ParseError) -> Self {
         Error {
-            message: format!("reqwest_mock url error: {}", e),
+            message: format!("url error: {}", e),
             kind: ErrorKind::Internal,
             backtrace: Backtrace::new(),
+            raw_body: None,
+            status: None,
+            url: None,
+            headers: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_body() {
+        let err = Error::parse_error("Missing title.").with_raw_body("<release>ä</release>", 10);
+
+        assert!(err.is_parse_error());
+        // Truncated before the multibyte character.
+        assert_eq!(err.raw_body(), Some("<release>"));
+        assert!(format!("{}", err).contains("Response body: <release>\n"));
+    }
+
+    #[test]
+    fn response() {
+        let response = HttpResponse {
+            status: 503,
+            headers: vec![
+                ("retry-after".to_string(), "2".to_string()),
+                ("Set-Cookie".to_string(), "session=secret".to_string()),
+            ],
+            body: Vec::new(),
+        };
+        let url = "https://musicbrainz.org/ws/2/area/2db42837-c832-3c27-b4a3-08198f75693c?inc=";
+        let err = Error::new("Unavailable.", ErrorKind::RateLimited).with_response(url, &response);
+
+        assert_eq!(err.url(), Some(url));
+        assert_eq!(err.status(), Some(503));
+        assert_eq!(err.header("Retry-After"), Some("2"));
+        assert_eq!(err.header("Set-Cookie"), None);
+        assert!(format!("{}", err).contains(&format!("Url: {}\n", url)));
+    }
+}
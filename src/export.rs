@@ -0,0 +1,220 @@
+//! Bulk export of entities as newline-delimited JSON.
+//!
+//! The entities are fetched one after another, respecting the rate limit of
+//! the client, and every entity is written as soon as it was fetched. If the
+//! export fails, the number of entities written so far is returned with the
+//! error, so the export can be resumed by appending to the same output.
+//!
+//! Only available with the `export` feature. The entities are written with
+//! their `Serialize` implementation, which the `serde` feature enables.
+
+use crate::client::Client;
+use crate::entities::{Mbid, Resource};
+use crate::error::{Error, ErrorKind};
+use crate::progress::Progress;
+
+use serde::Serialize;
+
+use std::fmt;
+use std::io::{BufRead, Write};
+
+/// An export which failed after writing some entities.
+#[derive(Debug)]
+pub struct ExportError {
+    /// The number of entities written, including skipped ones.
+    ///
+    /// Pass this as `skip` to resume the export.
+    pub exported: usize,
+
+    /// The reason the export failed.
+    pub error: Error,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Export failed after {} entities: {}", self.exported, self.error)
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+fn io_error(e: std::io::Error) -> Error {
+    Error::new(format!("Writing the export failed: {}", e), ErrorKind::Io)
+}
+
+/// Fetch the entities with the MBIDs `mbids` and write them to `writer`, one
+/// JSON document per line.
+///
+/// The first `skip` MBIDs are not fetched, which allows resuming an export
+/// that failed. Returns the number of entities written, including skipped
+/// ones.
+pub fn export_ndjson<Res, I, W>(
+    client: &mut Client,
+    mbids: I,
+    options: Res::Options,
+    skip: usize,
+    writer: &mut W,
+) -> Result<usize, ExportError>
+where
+    Res: Resource + Serialize,
+    Res::Options: Clone,
+    I: IntoIterator<Item = Mbid>,
+    W: Write,
+{
+    let mbids = mbids.into_iter();
+    export::<Res, _, _>(client, mbids, options, skip, writer, &mut |_| {})
+}
+
+/// Export the entities like `export_ndjson`, reporting the number of written
+/// and the total number of entities to `progress` after every entity.
+pub fn export_ndjson_with_progress<Res, I, W, P>(
+    client: &mut Client,
+    mbids: I,
+    options: Res::Options,
+    skip: usize,
+    writer: &mut W,
+    mut progress: P,
+) -> Result<usize, ExportError>
+where
+    Res: Resource + Serialize,
+    Res::Options: Clone,
+    I: IntoIterator<Item = Mbid>,
+    I::IntoIter: ExactSizeIterator,
+    W: Write,
+    P: Progress,
+{
+    let mbids = mbids.into_iter();
+    let total = mbids.len() as u64;
+    let mut report = |done| progress.on_progress(done, total);
+    export::<Res, _, _>(client, mbids, options, skip, writer, &mut report)
+}
+
+fn export<Res, I, W>(
+    client: &mut Client,
+    mbids: I,
+    options: Res::Options,
+    skip: usize,
+    writer: &mut W,
+    report: &mut dyn FnMut(u64),
+) -> Result<usize, ExportError>
+where
+    Res: Resource + Serialize,
+    Res::Options: Clone,
+    I: Iterator<Item = Mbid>,
+    W: Write,
+{
+    let mut exported = 0;
+    for mbid in mbids {
+        if exported < skip {
+            exported += 1;
+            continue;
+        }
+
+        let write = |client: &mut Client, writer: &mut W| -> Result<(), Error> {
+            let entity: Res = client.get_by_mbid(&mbid, options.clone())?;
+            let line = serde_json::to_string(&entity).map_err(|e| {
+                Error::new(format!("Serializing {} failed: {}", mbid, e), ErrorKind::Io)
+            })?;
+            // Lines are flushed one by one, so the output never ends with an
+            // incomplete entity.
+            writeln!(writer, "{}", line).map_err(io_error)?;
+            writer.flush().map_err(io_error)
+        };
+        write(client, writer).map_err(|error| ExportError { exported, error })?;
+        exported += 1;
+        report(exported as u64);
+    }
+    Ok(exported)
+}
+
+/// Count the entities in the output of a previous export, which is the
+/// number of entities to skip when resuming it.
+pub fn exported_count<R: BufRead>(reader: R) -> Result<usize, Error> {
+    let mut count = 0;
+    for line in reader.lines() {
+        if !line.map_err(io_error)?.trim().is_empty() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{HttpRequest, HttpResponse};
+    use crate::entities::Area;
+    use crate::util::test_utils::{area_xml, requested_mbid, response, FnTransport};
+
+    /// Answers with the area whose MBID was requested, failing for one of
+    /// them.
+    fn area_response(request: &HttpRequest) -> Result<HttpResponse, Error> {
+        let mbid = requested_mbid(request);
+        if mbid.starts_with("ffffffff") {
+            return Err(Error::new("Connection reset.", ErrorKind::Communication));
+        }
+        let name = format!("Area \"{}\"", &mbid[..1]);
+        Ok(response(200, area_xml(&mbid, &name)))
+    }
+
+    #[test]
+    fn export_and_resume() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let mut client = Client::with_transport(config, FnTransport(area_response));
+        let mbids: Vec<Mbid> = vec![
+            "2db42837-c832-3c27-b4a3-08198f75693c",
+            "ffffffff-c832-3c27-b4a3-08198f75693c",
+            "489ce91b-6658-3307-9877-795b68554c98",
+        ]
+        .into_iter()
+        .map(|m| m.parse().unwrap())
+        .collect();
+
+        let mut out = Vec::new();
+        let err =
+            export_ndjson::<Area, _, _>(&mut client, mbids.clone(), (), 0, &mut out).unwrap_err();
+        assert_eq!(err.exported, 1);
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            concat!(
+                r#"{"mbid":"2db42837-c832-3c27-b4a3-08198f75693c","name":"Area \"2\"","#,
+                r#""sort_name":"Area","area_type":null,"area_type_id":null,"iso_3166":null}"#,
+                "\n"
+            )
+        );
+
+        // Resume without the failing MBID.
+        let skip = exported_count(&out[..]).unwrap();
+        let mbids = vec![mbids[0].clone(), mbids[2].clone()];
+        let exported = export_ndjson::<Area, _, _>(&mut client, mbids, (), skip, &mut out).unwrap();
+        assert_eq!(exported, 2);
+        assert_eq!(exported_count(&out[..]).unwrap(), 2);
+    }
+
+    #[test]
+    fn export_progress() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let mut client = Client::with_transport(config, FnTransport(area_response));
+        let mbids: Vec<Mbid> = vec![
+            "2db42837-c832-3c27-b4a3-08198f75693c",
+            "489ce91b-6658-3307-9877-795b68554c98",
+        ]
+        .into_iter()
+        .map(|m| m.parse().unwrap())
+        .collect();
+
+        let mut out = Vec::new();
+        let mut reports = Vec::new();
+        let exported = export_ndjson_with_progress::<Area, _, _, _>(
+            &mut client,
+            mbids,
+            (),
+            1,
+            &mut out,
+            |done, total| reports.push((done, total)),
+        )
+        .unwrap();
+        assert_eq!(exported, 2);
+        assert_eq!(reports, vec![(2, 2)]);
+    }
+}
@@ -0,0 +1,325 @@
+//! Background fetching of entities.
+//!
+//! A `Fetcher` owns a `Client` and performs the requested fetches one after
+//! another on a worker thread, so e.g. a GUI thread never blocks on the rate
+//! limit. Jobs with a higher priority are performed first, and requests for
+//! an entity which is already queued are coalesced into one request.
+//!
+//! Only available with the `fetcher` feature.
+
+use crate::client::{CancellationToken, Client};
+use crate::entities::{Mbid, Resource};
+use crate::error::{Error, ErrorKind};
+
+use std::any::{Any, TypeId};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// Priority of a fetch job, jobs with a higher priority are performed first.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
+type Callback<Res> = Box<dyn FnOnce(Result<Res, Error>) + Send>;
+type Waiters<Res> = Arc<Mutex<Vec<Callback<Res>>>>;
+
+/// Identifies the fetches which can be coalesced.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+struct JobKey {
+    resource: TypeId,
+    url: String,
+}
+
+/// A job waiting to be performed.
+struct PendingJob {
+    run: Box<dyn FnOnce(&mut Client) + Send>,
+    /// The `Waiters<Res>` of the job, so further callbacks can be added.
+    waiters: Box<dyn Any + Send>,
+}
+
+/// An entry of the priority queue.
+///
+/// A job can have multiple entries if it was requested again with a higher
+/// priority, entries of jobs which were already performed are skipped.
+#[derive(Eq, PartialEq)]
+struct QueueEntry {
+    priority: Priority,
+    seq: u64,
+    key: JobKey,
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priorities first, then the oldest jobs first.
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Default)]
+struct State {
+    queue: BinaryHeap<QueueEntry>,
+    jobs: HashMap<JobKey, PendingJob>,
+    next_seq: u64,
+    shutdown: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    available: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> ::std::sync::MutexGuard<State> {
+        // The state is never left inconsistent, so a poisoned lock can be used.
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Performs fetches on a background thread, see the module documentation.
+///
+/// Dropping the fetcher stops the worker thread, jobs which weren't performed
+/// yet are discarded.
+pub struct Fetcher {
+    shared: Arc<Shared>,
+    cancellation: CancellationToken,
+    worker: Option<JoinHandle<Client>>,
+}
+
+impl Fetcher {
+    /// Start a worker thread performing the fetches with `client`.
+    pub fn new(client: Client) -> Self {
+        let shared = Arc::new(Shared::default());
+        let cancellation = client.cancellation_token();
+        let worker_shared = shared.clone();
+        let worker = thread::spawn(move || work(client, &worker_shared));
+
+        Fetcher {
+            shared,
+            cancellation,
+            worker: Some(worker),
+        }
+    }
+
+    /// Queue a fetch of the entity with the MBID `mbid`, returning a channel
+    /// on which the result is delivered.
+    ///
+    /// If the fetcher is stopped before performing the job, the channel is
+    /// closed without delivering a result.
+    pub fn fetch<Res>(
+        &self,
+        mbid: &Mbid,
+        options: Res::Options,
+        priority: Priority,
+    ) -> Receiver<Result<Res, Error>>
+    where
+        Res: Resource + Clone + Send + 'static,
+        Res::Options: Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        self.fetch_with(mbid, options, priority, move |result| {
+            // The receiver might not be interested anymore.
+            let _ = sender.send(result);
+        });
+        receiver
+    }
+
+    /// Queue a fetch of the entity with the MBID `mbid`, calling `callback`
+    /// with the result on the worker thread.
+    ///
+    /// If the same entity is already queued with the same options, no further
+    /// request is made and `callback` is called with the result of the queued
+    /// job, which is performed with the higher of both priorities.
+    pub fn fetch_with<Res, F>(
+        &self,
+        mbid: &Mbid,
+        options: Res::Options,
+        priority: Priority,
+        callback: F,
+    ) where
+        Res: Resource + Clone + Send + 'static,
+        Res::Options: Send + 'static,
+        F: FnOnce(Result<Res, Error>) + Send + 'static,
+    {
+        let key = JobKey {
+            resource: TypeId::of::<Res>(),
+            url: format!("{}/{}?inc={}", Res::NAME, mbid, Res::request(&options).include),
+        };
+
+        let mut state = self.shared.lock();
+        if state.shutdown {
+            return;
+        }
+        let queued = state
+            .jobs
+            .get(&key)
+            .and_then(|job| job.waiters.downcast_ref::<Waiters<Res>>())
+            .cloned();
+        match queued {
+            Some(waiters) => {
+                waiters
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(Box::new(callback));
+            }
+            None => {
+                let waiters: Waiters<Res> = Arc::new(Mutex::new(vec![Box::new(callback)]));
+                let job_waiters = waiters.clone();
+                let mbid = mbid.clone();
+                let run = move |client: &mut Client| {
+                    let result: Result<Res, Error> = client.get_by_mbid(&mbid, options);
+                    let callbacks: Vec<_> =
+                        job_waiters.lock().unwrap_or_else(|e| e.into_inner()).drain(..).collect();
+                    for callback in callbacks {
+                        callback(result.clone());
+                    }
+                };
+                state.jobs.insert(
+                    key.clone(),
+                    PendingJob {
+                        run: Box::new(run),
+                        waiters: Box::new(waiters),
+                    },
+                );
+            }
+        }
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queue.push(QueueEntry { priority, seq, key });
+        self.shared.available.notify_one();
+    }
+
+    /// Number of jobs which weren't performed yet.
+    pub fn pending(&self) -> usize {
+        self.shared.lock().jobs.len()
+    }
+
+    /// Stop the worker thread and return the client.
+    ///
+    /// A job which is currently performed is cancelled, all queued jobs are
+    /// discarded.
+    pub fn shutdown(mut self) -> Result<Client, Error> {
+        self.stop().unwrap_or_else(|| {
+            Err(Error::new("The fetcher was already stopped.", ErrorKind::Internal))
+        })
+    }
+
+    fn stop(&mut self) -> Option<Result<Client, Error>> {
+        let worker = self.worker.take()?;
+        {
+            let mut state = self.shared.lock();
+            state.shutdown = true;
+            state.queue.clear();
+            state.jobs.clear();
+        }
+        self.shared.available.notify_all();
+        self.cancellation.cancel();
+
+        let result = worker
+            .join()
+            .map_err(|_| Error::new("The fetcher thread panicked.", ErrorKind::Internal));
+        self.cancellation.reset();
+        Some(result)
+    }
+}
+
+impl Drop for Fetcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Perform jobs until the fetcher is stopped.
+fn work(mut client: Client, shared: &Shared) -> Client {
+    loop {
+        let job = {
+            let mut state = shared.lock();
+            loop {
+                if state.shutdown {
+                    return client;
+                }
+                match state.queue.pop() {
+                    Some(entry) => {
+                        // Entries of jobs which were already performed are
+                        // skipped.
+                        if let Some(job) = state.jobs.remove(&entry.key) {
+                            break job;
+                        }
+                    }
+                    None => {
+                        state = shared
+                            .available
+                            .wait(state)
+                            .unwrap_or_else(|e| e.into_inner());
+                    }
+                }
+            }
+        };
+        (job.run)(&mut client);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::HttpRequest;
+    use crate::entities::Area;
+    use crate::util::test_utils::{area_xml, requested_mbid, response, FnTransport};
+
+    #[test]
+    fn priorities_and_coalescing() {
+        let config = crate::util::test_utils::config().build().unwrap();
+        let (sender, urls) = channel();
+        let (gate, gate_receiver) = channel();
+        // Reports the requested urls and answers with an area once the gate
+        // is opened, or as soon as it is dropped.
+        let transport = FnTransport(move |request: &HttpRequest| {
+            sender.send(request.url.to_string()).unwrap();
+            let _ = gate_receiver.recv();
+            Ok(response(200, area_xml(&requested_mbid(request), "Area")))
+        });
+        let fetcher = Fetcher::new(Client::with_transport(config, transport));
+
+        let mbid = |s: &str| -> Mbid { s.parse().unwrap() };
+        let first = mbid("2db42837-c832-3c27-b4a3-08198f75693c");
+        let low = mbid("489ce91b-6658-3307-9877-795b68554c98");
+        let high = mbid("8a754a16-0027-3a29-b6d7-2b40ea0481ed");
+
+        // The other jobs are queued while the first one is performed.
+        let a = fetcher.fetch::<Area>(&first, (), Priority::Normal);
+        let first_url = urls.recv().unwrap();
+        let b = fetcher.fetch::<Area>(&low, (), Priority::Low);
+        let c = fetcher.fetch::<Area>(&high, (), Priority::High);
+        let d = fetcher.fetch::<Area>(&high, (), Priority::Low);
+        assert_eq!(fetcher.pending(), 2);
+        drop(gate);
+
+        assert_eq!(a.recv().unwrap().unwrap().mbid(), &first);
+        assert_eq!(b.recv().unwrap().unwrap().mbid(), &low);
+        assert_eq!(c.recv().unwrap().unwrap().mbid(), &high);
+        assert_eq!(d.recv().unwrap().unwrap().mbid(), &high);
+
+        let client = fetcher.shutdown().unwrap();
+        let urls: Vec<String> = urls.try_iter().collect();
+        assert!(first_url.contains(&first.to_string()));
+        assert_eq!(urls.len(), 2);
+        assert!(urls[0].contains(&high.to_string()));
+        assert!(urls[1].contains(&low.to_string()));
+        assert_eq!(client.stats().requests, 3);
+    }
+}
@@ -0,0 +1,210 @@
+//! Typed filters which can be used both for browsing and for searching.
+//!
+//! A filter is translated into browse parameters or search expressions as
+//! needed, so e.g. "official releases in JP since 2020" can be expressed
+//! without writing Lucene queries by hand. Criteria the server doesn't
+//! support when browsing are applied to the results instead.
+
+use crate::entities::{PartialDate, ReleaseStatus};
+use crate::search::fields::SearchValue;
+
+use std::cmp::Ordering;
+
+/// Restricts releases by country, status and release date.
+///
+/// All criteria have to match. An empty filter matches every release.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReleaseFilter {
+    countries: Vec<String>,
+    statuses: Vec<ReleaseStatus>,
+    since: Option<PartialDate>,
+    until: Option<PartialDate>,
+}
+
+impl ReleaseFilter {
+    /// Create a filter matching every release.
+    pub fn new() -> Self {
+        ReleaseFilter::default()
+    }
+
+    /// Only match releases issued in the country with the ISO 3166-1 code
+    /// `code`, or one of the other countries added.
+    pub fn country<S: AsRef<str>>(mut self, code: S) -> Self {
+        self.countries.push(code.as_ref().trim().to_uppercase());
+        self
+    }
+
+    /// Only match releases with the status `status`, or one of the other
+    /// statuses added.
+    pub fn status(mut self, status: ReleaseStatus) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    /// Only match releases issued on or after `date`.
+    ///
+    /// Dates are compared with the precision of the less precise one, so a
+    /// release in `2020` is issued on or after `2020-05`.
+    pub fn since(mut self, date: PartialDate) -> Self {
+        self.since = Some(date);
+        self
+    }
+
+    /// Only match releases issued on or before `date`, which is compared like
+    /// the date passed to `since`.
+    pub fn until(mut self, date: PartialDate) -> Self {
+        self.until = Some(date);
+        self
+    }
+
+    /// Whether the filter restricts the country or the release date, which
+    /// the server doesn't support when browsing.
+    pub(crate) fn has_local_criteria(&self) -> bool {
+        !self.countries.is_empty() || self.since.is_some() || self.until.is_some()
+    }
+
+    /// The statuses for the `status` browse parameter.
+    pub(crate) fn statuses(&self) -> &[ReleaseStatus] {
+        &self.statuses
+    }
+
+    /// Whether a release with the given country, status and date matches.
+    ///
+    /// Releases without a known country or date don't match if the filter
+    /// restricts them.
+    pub fn matches(
+        &self,
+        country: Option<&str>,
+        status: Option<ReleaseStatus>,
+        date: Option<&PartialDate>,
+    ) -> bool {
+        if !self.countries.is_empty() {
+            match country {
+                Some(c) if self.countries.iter().any(|code| code == c) => {}
+                _ => return false,
+            }
+        }
+        if !self.statuses.is_empty() {
+            match status {
+                Some(s) if self.statuses.contains(&s) => {}
+                _ => return false,
+            }
+        }
+        if self.since.is_none() && self.until.is_none() {
+            return true;
+        }
+        match date {
+            Some(date) if date.year().is_some() => {
+                let after_since = self
+                    .since
+                    .as_ref()
+                    .map_or(true, |s| date.cmp_coarse(s) != Ordering::Less);
+                let before_until = self
+                    .until
+                    .as_ref()
+                    .map_or(true, |u| date.cmp_coarse(u) != Ordering::Greater);
+                after_since && before_until
+            }
+            _ => false,
+        }
+    }
+
+    /// The search expressions for the criteria, as pairs of field name and
+    /// value.
+    pub(crate) fn search_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if !self.countries.is_empty() {
+            params.push(("country", any_of(self.countries.iter().cloned())));
+        }
+        if !self.statuses.is_empty() {
+            params.push((
+                "status",
+                any_of(self.statuses.iter().map(|s| s.to_search_value())),
+            ));
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let since = self
+                .since
+                .as_ref()
+                .map_or("*".to_string(), |d| d.to_search_value());
+            // A partial upper bound is replaced by the start of the following
+            // period, which is excluded.
+            let until = match self.until.as_ref().map(|d| (d.year(), d.month(), d.day())) {
+                None => "*]".to_string(),
+                Some((Some(y), None, _)) => format!("{:04}}}", y + 1),
+                Some((Some(y), Some(12), None)) => format!("{:04}-01}}", y + 1),
+                Some((Some(y), Some(m), None)) => format!("{:04}-{:02}}}", y, m + 1),
+                Some(_) => format!("{}]", self.until.as_ref().unwrap().to_search_value()),
+            };
+            params.push(("date", format!("[{} TO {}", since, until)));
+        }
+        params
+    }
+}
+
+/// An expression matching any of the values.
+fn any_of<I: Iterator<Item = String>>(values: I) -> String {
+    let values: Vec<String> = values.collect();
+    if values.len() == 1 {
+        values[0].clone()
+    } else {
+        format!("({})", values.join(" OR "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn date(s: &str) -> PartialDate {
+        PartialDate::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn matches() {
+        let filter = ReleaseFilter::new()
+            .country("jp")
+            .status(ReleaseStatus::Official)
+            .since(date("2020-05"));
+
+        assert!(filter.matches(Some("JP"), Some(ReleaseStatus::Official), Some(&date("2020"))));
+        assert!(filter.matches(
+            Some("JP"),
+            Some(ReleaseStatus::Official),
+            Some(&date("2021-01-10"))
+        ));
+        assert!(!filter.matches(
+            Some("JP"),
+            Some(ReleaseStatus::Official),
+            Some(&date("2020-04-30"))
+        ));
+        assert!(!filter.matches(Some("US"), Some(ReleaseStatus::Official), Some(&date("2021"))));
+        assert!(!filter.matches(Some("JP"), None, Some(&date("2021"))));
+        assert!(!filter.matches(Some("JP"), Some(ReleaseStatus::Official), None));
+        assert!(ReleaseFilter::new().matches(None, None, None));
+    }
+
+    #[test]
+    fn search_params() {
+        let filter = ReleaseFilter::new()
+            .country("JP")
+            .country("US")
+            .status(ReleaseStatus::Official)
+            .since(date("2020"))
+            .until(date("2021-12"));
+
+        assert_eq!(
+            filter.search_params(),
+            vec![
+                ("country", "(JP OR US)".to_string()),
+                ("status", "official".to_string()),
+                ("date", "[2020 TO 2022-01}".to_string()),
+            ]
+        );
+        assert_eq!(
+            ReleaseFilter::new().since(date("2020")).search_params(),
+            vec![("date", "[2020 TO *]".to_string())]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Normalizing tags and genres into a consistent set of genre names.
+//!
+//! Tags are entered freely by users, so the same genre is often tagged as
+//! `hip hop`, `Hip-Hop` and `hiphop` at once. A `GenreNormalizer` folds such
+//! spellings into one genre, summing up their counts, and can be configured
+//! with mappings for the taxonomy of an application.
+
+use crate::entities::{Genre, Tag};
+
+use std::collections::{HashMap, HashSet};
+
+/// Spellings which are mapped to the name of a genre by default.
+///
+/// The spellings are folded like tag names, so e.g. `hip-hop` and `Hip Hop`
+/// don't need an entry.
+const DEFAULT_MAPPINGS: &[(&str, &str)] = &[
+    ("hiphop", "hip hop"),
+    ("rnb", "r&b"),
+    ("r and b", "r&b"),
+    ("r n b", "r&b"),
+    ("rhythm and blues", "r&b"),
+    ("dnb", "drum and bass"),
+    ("d&b", "drum and bass"),
+    ("drum & bass", "drum and bass"),
+    ("drum n bass", "drum and bass"),
+    ("drum'n'bass", "drum and bass"),
+    ("rock & roll", "rock and roll"),
+    ("rock n roll", "rock and roll"),
+    ("rock'n'roll", "rock and roll"),
+    ("synthpop", "synth-pop"),
+    ("synth pop", "synth-pop"),
+    ("lofi", "lo-fi"),
+    ("lo fi", "lo-fi"),
+    ("post rock", "post-rock"),
+    ("post punk", "post-punk"),
+    ("electronica", "electronic"),
+];
+
+/// Maps tags to genre names, see the module documentation.
+#[derive(Clone, Debug)]
+pub struct GenreNormalizer {
+    /// Genre names by folded spelling.
+    mappings: HashMap<String, String>,
+    /// Folded spellings of tags which aren't genres.
+    ignored: HashSet<String>,
+}
+
+impl Default for GenreNormalizer {
+    fn default() -> Self {
+        GenreNormalizer::new()
+    }
+}
+
+impl GenreNormalizer {
+    /// Create a normalizer with mappings for common alternative spellings,
+    /// e.g. `hiphop` or `drum n bass`.
+    pub fn new() -> Self {
+        DEFAULT_MAPPINGS
+            .iter()
+            .fold(GenreNormalizer::empty(), |n, &(from, to)| n.map(from, to))
+    }
+
+    /// Create a normalizer without any mappings, which only folds spellings
+    /// differing in case, hyphens and whitespace.
+    pub fn empty() -> Self {
+        GenreNormalizer {
+            mappings: HashMap::new(),
+            ignored: HashSet::new(),
+        }
+    }
+
+    /// Map tags spelled like `from` to the genre `to`, replacing a previous
+    /// mapping of the spelling.
+    ///
+    /// Mapping multiple genres to the same genre merges them, e.g. `trip hop`
+    /// could be mapped to `electronic`.
+    pub fn map<S: AsRef<str>, T: AsRef<str>>(mut self, from: S, to: T) -> Self {
+        self.mappings.insert(fold(from.as_ref()), clean(to.as_ref()));
+        self
+    }
+
+    /// Drop tags spelled like `name`, e.g. `seen live`.
+    pub fn ignore<S: AsRef<str>>(mut self, name: S) -> Self {
+        self.ignored.insert(fold(name.as_ref()));
+        self
+    }
+
+    /// The genre a single tag name is normalized to, `None` if it is ignored.
+    ///
+    /// Names without a mapping are lowercased and their whitespace is
+    /// collapsed.
+    pub fn normalize_name(&self, name: &str) -> Option<String> {
+        let key = fold(name);
+        if key.is_empty() || self.ignored.contains(&key) {
+            return None;
+        }
+        Some(match self.mappings.get(&key) {
+            Some(genre) => genre.clone(),
+            None => clean(name),
+        })
+    }
+
+    /// Normalize the tags of an entity.
+    ///
+    /// Tags normalized to the same genre are merged, summing up their counts.
+    /// Tags without a positive count are dropped. The genres are ordered by
+    /// count, the most popular first.
+    pub fn normalize_tags(&self, tags: &[Tag]) -> Vec<Tag> {
+        self.normalize(tags.iter().map(|t| (t.name.as_str(), t.count)))
+    }
+
+    /// Normalize the genres of an entity, like `normalize_tags`.
+    pub fn normalize_genres(&self, genres: &[Genre]) -> Vec<Tag> {
+        self.normalize(genres.iter().map(|g| (g.name.as_str(), g.count)))
+    }
+
+    fn normalize<'a, I>(&self, tags: I) -> Vec<Tag>
+    where
+        I: Iterator<Item = (&'a str, i32)>,
+    {
+        let mut tags: Vec<(&str, i32)> = tags.filter(|&(_, count)| count > 0).collect();
+        // Spellings without a mapping are merged into the most popular one.
+        tags.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let mut genres: Vec<Tag> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        for (name, count) in tags {
+            let genre = match self.normalize_name(name) {
+                Some(genre) => genre,
+                None => continue,
+            };
+            let key = fold(&genre);
+            match index.get(&key) {
+                Some(&i) => genres[i].count += count,
+                None => {
+                    index.insert(key, genres.len());
+                    genres.push(Tag { name: genre, count });
+                }
+            }
+        }
+
+        genres.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+        genres
+    }
+}
+
+/// Lowercase a name and collapse its whitespace.
+fn clean(name: &str) -> String {
+    name.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Like `clean`, but also treat hyphens, underscores and slashes as spaces,
+/// so all spellings of a genre have the same key.
+fn fold(name: &str) -> String {
+    clean(&name.replace(&['-', '_', '/'][..], " "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str, count: i32) -> Tag {
+        Tag {
+            name: name.to_string(),
+            count,
+        }
+    }
+
+    #[test]
+    fn names() {
+        let normalizer = GenreNormalizer::new().ignore("Seen Live");
+        assert_eq!(normalizer.normalize_name("Hip-Hop").unwrap(), "hip-hop");
+        assert_eq!(normalizer.normalize_name("hiphop").unwrap(), "hip hop");
+        assert_eq!(normalizer.normalize_name(" Drum  N Bass").unwrap(), "drum and bass");
+        assert_eq!(normalizer.normalize_name("synth_pop").unwrap(), "synth-pop");
+        assert_eq!(normalizer.normalize_name("seen-live"), None);
+        assert_eq!(normalizer.normalize_name("  "), None);
+        assert_eq!(GenreNormalizer::empty().normalize_name("hiphop").unwrap(), "hiphop");
+    }
+
+    #[test]
+    fn merge_tags() {
+        let normalizer = GenreNormalizer::new().map("trip hop", "Electronic");
+        let tags = vec![
+            tag("hip-hop", 2),
+            tag("Hip Hop", 5),
+            tag("hiphop", 1),
+            tag("trip-hop", 3),
+            tag("electronica", 1),
+            tag("rnb", 4),
+            tag("pop", -1),
+        ];
+        assert_eq!(
+            normalizer.normalize_tags(&tags),
+            vec![tag("hip hop", 8), tag("electronic", 4), tag("r&b", 4)]
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Identify albums from the basic tags of music files.
+//!
+//! This is the core workflow of music taggers: Given the artist, album and
+//! track titles found in some files, search MusicBrainz for matching releases
+//! and map every track to the MBID of its recording.
+//!
+//! Every candidate release has to be looked up separately, so with the
+//! default rate limit identifying an album takes a couple of seconds.
+
+use crate::client::Client;
+use crate::entities::{Mbid, Release, ReleaseOptions, ReleaseTrack};
+use crate::error::Error;
+use crate::search::fields::release_group::{ArtistName, ReleaseGroupName};
+use crate::search::SearchBuilder;
+
+/// The tags of a single file.
+#[derive(Clone, Debug)]
+pub struct TrackTags {
+    /// The track number, counting across all mediums of the release.
+    pub number: Option<u32>,
+
+    /// The title of the track.
+    pub title: String,
+}
+
+/// The tags of all files belonging to one album.
+#[derive(Clone, Debug)]
+pub struct AlbumTags {
+    /// The artist of the album.
+    pub artist: String,
+
+    /// The title of the album.
+    pub album: String,
+
+    /// The tracks of the album, in any order.
+    pub tracks: Vec<TrackTags>,
+}
+
+/// Options for `identify`.
+#[derive(Clone, Debug)]
+pub struct IdentifyOptions {
+    /// Maximal number of releases to look up and rank.
+    pub max_lookups: usize,
+}
+
+impl Default for IdentifyOptions {
+    fn default() -> Self {
+        IdentifyOptions { max_lookups: 5 }
+    }
+}
+
+/// A release which possibly matches the provided tags.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    /// MBID of the release.
+    pub release: Mbid,
+
+    /// The title of the release.
+    pub title: String,
+
+    /// How well the release matches the tags, from 0 to 1.
+    pub score: f32,
+
+    /// For every entry of `AlbumTags::tracks` the MBID of the recording it
+    /// was matched with, if any.
+    pub recordings: Vec<Option<Mbid>>,
+}
+
+/// Search for releases matching `tags` and return them ranked by how well
+/// they match, the best candidate first.
+pub fn identify(
+    client: &mut Client,
+    tags: &AlbumTags,
+    options: &IdentifyOptions,
+) -> Result<Vec<Candidate>, Error> {
+    let groups = client
+        .search_release_group()
+        .add(ReleaseGroupName(tags.album.clone()))
+        .add(ArtistName(tags.artist.clone()))
+        .search()?;
+
+    // Look up the releases of the best matching release groups first.
+    let mut lookups = Vec::new();
+    for group in groups.iter() {
+        for release in group.entity.releases.iter() {
+            if lookups.len() < options.max_lookups {
+                lookups.push((release.mbid.clone(), group.score));
+            }
+        }
+    }
+
+    let mut options = ReleaseOptions::minimal();
+    options.recordings = true;
+
+    let mut candidates = Vec::with_capacity(lookups.len());
+    for (mbid, search_score) in lookups {
+        let release: Release = client.get_by_mbid(&mbid, options.clone())?;
+        candidates.push(rate_release(tags, &release, search_score));
+    }
+
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(::std::cmp::Ordering::Equal)
+    });
+    Ok(candidates)
+}
+
+/// Rate how well a release fetched with its recordings matches the tags.
+///
+/// The score combines the score of the search which found the release, how
+/// close its track count is to the number of tagged tracks and how many
+/// tagged tracks could be matched by title.
+pub fn rate_release(tags: &AlbumTags, release: &Release, search_score: u8) -> Candidate {
+    let tracks: Vec<&ReleaseTrack> = release
+        .mediums()
+        .into_option()
+        .unwrap_or(&[])
+        .iter()
+        .flat_map(|m| m.tracks().iter())
+        .collect();
+
+    let recordings: Vec<Option<Mbid>> = tags
+        .tracks
+        .iter()
+        .map(|tag| match_track(tag, &tracks).map(|t| t.recording.mbid.clone()))
+        .collect();
+
+    let matched = recordings.iter().filter(|r| r.is_some()).count();
+    let title_score = if tags.tracks.is_empty() {
+        0.
+    } else {
+        matched as f32 / tags.tracks.len() as f32
+    };
+
+    let (fewer, more) = if tracks.len() < tags.tracks.len() {
+        (tracks.len(), tags.tracks.len())
+    } else {
+        (tags.tracks.len(), tracks.len())
+    };
+    let count_score = if more == 0 { 0. } else { fewer as f32 / more as f32 };
+
+    Candidate {
+        release: release.mbid().clone(),
+        title: release.title().clone(),
+        score: 0.3 * (search_score as f32 / 100.) + 0.3 * count_score + 0.4 * title_score,
+        recordings: recordings,
+    }
+}
+
+/// Find the track matching the tag, preferring the one at the tagged position
+/// if its title matches.
+fn match_track<'a>(tag: &TrackTags, tracks: &[&'a ReleaseTrack]) -> Option<&'a ReleaseTrack> {
+    let title = normalize(&tag.title);
+    let by_number = tag
+        .number
+        .and_then(|n| tracks.get((n as usize).wrapping_sub(1)))
+        .filter(|t| normalize(&t.title) == title);
+
+    by_number
+        .or_else(|| tracks.iter().find(|t| normalize(&t.title) == title))
+        .cloned()
+}
+
+/// Normalize a title for comparison, ignoring case, whitespace and
+/// punctuation.
+fn normalize(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate() {
+        let mbid: Mbid = "ed118c5f-d940-4b52-a37b-b1a205374abe".parse().unwrap();
+        let options = ReleaseOptions {
+            annotation: true,
+            artists: true,
+            recordings: true,
+            labels: true,
+            ..ReleaseOptions::minimal()
+        };
+        let release: Release = crate::util::test_utils::fetch_entity(&mbid, options).unwrap();
+
+        let track = |number, title: &str| TrackTags {
+            number: number,
+            title: title.to_string(),
+        };
+        let tags = AlbumTags {
+            artist: "Radiohead".to_string(),
+            album: "Creep".to_string(),
+            tracks: vec![
+                track(Some(1), "Creep"),
+                track(None, "inside my head"),
+                track(Some(4), "Million Dollar Question!"),
+                track(Some(2), "Not On This Release"),
+            ],
+        };
+        let candidate = rate_release(&tags, &release, 100);
+
+        assert_eq!(
+            candidate.recordings,
+            vec![
+                Some("70595637-9310-45f2-a266-58f8de4874a7".parse().unwrap()),
+                Some("10b05f6e-e670-48c2-b269-6b442fde6736".parse().unwrap()),
+                Some("57af66b8-c5cf-482a-9149-477f6604ac43".parse().unwrap()),
+                None,
+            ]
+        );
+        assert!((candidate.score - (0.3 + 0.3 + 0.4 * 0.75)).abs() < 1e-6);
+    }
+}
@@ -5,20 +5,56 @@
 
 extern crate isolang;
 extern crate regex;
+#[cfg(feature = "default-transport")]
+extern crate reqwest;
+#[cfg(any(feature = "default-transport", test))]
 extern crate reqwest_mock;
 extern crate uuid;
 extern crate url;
 extern crate xpath_reader;
 
 mod error;
-pub use self::error::Error;
+pub use self::error::{Error, ParseWarning};
 
+pub mod browse;
+pub mod bulk;
 pub mod client;
+pub mod diff;
 pub mod entities;
+#[cfg(feature = "fetcher")]
+pub mod fetcher;
+pub mod filter;
+pub mod genres;
+pub mod identify;
+#[cfg(feature = "listenbrainz")]
+pub mod listenbrainz;
+pub mod paging;
+pub mod progress;
 pub mod search;
+pub mod seed;
+pub mod sort;
 
 mod util;
 
+#[cfg(feature = "test-support")]
+pub mod test_support;
+
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+
+#[cfg(feature = "streaming")]
+extern crate quick_xml;
+#[cfg(feature = "streaming")]
+mod streaming;
+
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(feature = "export")]
+extern crate serde_json;
+#[cfg(feature = "export")]
+pub mod export;
+
 #[cfg(feature = "rusqlite")]
 extern crate rusqlite;
 #[cfg(feature = "rusqlite")]
@@ -0,0 +1,180 @@
+//! Submission of listens to [ListenBrainz](https://listenbrainz.org/).
+//!
+//! Listens are submitted through a `Client`, so they are sent with the same
+//! user agent and share the rate limit with the requests to MusicBrainz.
+//! Only available with the `listenbrainz` feature.
+
+use crate::client::Client;
+use crate::entities::Mbid;
+use crate::error::{Error, ErrorKind};
+
+use std::fmt::Write;
+use url::Url;
+
+const SUBMIT_URL: &'static str = "https://api.listenbrainz.org/1/submit-listens";
+
+/// A single listen of a track.
+#[derive(Clone, Debug)]
+pub struct Listen {
+    /// The name of the artist of the track.
+    pub artist: String,
+
+    /// The title of the track.
+    pub track: String,
+
+    /// The title of the release the track was listened from.
+    pub release: Option<String>,
+
+    /// MBID of the recording, if known.
+    pub recording: Option<Mbid>,
+
+    /// When the track was listened to, in seconds since the unix epoch.
+    pub listened_at: u64,
+}
+
+/// Submits listens to ListenBrainz on behalf of a user.
+#[derive(Clone, Debug)]
+pub struct ListenBrainz {
+    token: String,
+}
+
+impl ListenBrainz {
+    /// Create a new instance submitting with the user token of a
+    /// ListenBrainz account.
+    pub fn new<S: Into<String>>(token: S) -> Self {
+        ListenBrainz {
+            token: token.into(),
+        }
+    }
+
+    /// Submit one listen which just finished.
+    pub fn submit(&self, client: &mut Client, listen: &Listen) -> Result<(), Error> {
+        self.send(client, "single", ::std::slice::from_ref(listen), true)
+    }
+
+    /// Submit multiple listens at once, e.g. to import the history of a
+    /// user.
+    pub fn import(&self, client: &mut Client, listens: &[Listen]) -> Result<(), Error> {
+        self.send(client, "import", listens, true)
+    }
+
+    /// Notify ListenBrainz that the user is currently listening to a track.
+    ///
+    /// The `listened_at` field of the listen is ignored.
+    pub fn playing_now(&self, client: &mut Client, listen: &Listen) -> Result<(), Error> {
+        self.send(client, "playing_now", ::std::slice::from_ref(listen), false)
+    }
+
+    fn send(
+        &self,
+        client: &mut Client,
+        listen_type: &str,
+        listens: &[Listen],
+        timestamps: bool,
+    ) -> Result<(), Error> {
+        let body = build_payload(listen_type, listens, timestamps);
+        let headers = vec![
+            ("Authorization".to_string(), format!("Token {}", self.token)),
+            ("Content-Type".to_string(), "application/json".to_string()),
+        ];
+        let response = client.send(Url::parse(SUBMIT_URL)?, headers, Some(body.as_bytes()))?;
+
+        if response.status == 200 {
+            Ok(())
+        } else {
+            Err(Error::new(
+                format!(
+                    "ListenBrainz returned {}: {}",
+                    response.status,
+                    String::from_utf8_lossy(&response.body)
+                ),
+                ErrorKind::ServerError,
+            )
+            .with_response(SUBMIT_URL, &response))
+        }
+    }
+}
+
+/// Build the JSON payload of a submission.
+fn build_payload(listen_type: &str, listens: &[Listen], timestamps: bool) -> String {
+    let mut json = format!(r#"{{"listen_type":"{}","payload":["#, listen_type);
+    for (i, listen) in listens.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push('{');
+        if timestamps {
+            write!(json, r#""listened_at":{},"#, listen.listened_at).unwrap();
+        }
+        json.push_str(r#""track_metadata":{"artist_name":"#);
+        push_json_string(&mut json, &listen.artist);
+        json.push_str(r#","track_name":"#);
+        push_json_string(&mut json, &listen.track);
+        if let Some(ref release) = listen.release {
+            json.push_str(r#","release_name":"#);
+            push_json_string(&mut json, release);
+        }
+        if let Some(ref recording) = listen.recording {
+            write!(
+                json,
+                r#","additional_info":{{"recording_mbid":"{}"}}"#,
+                recording
+            )
+            .unwrap();
+        }
+        json.push_str("}}");
+    }
+    json.push_str("]}");
+    json
+}
+
+/// Append `s` as a quoted and escaped JSON string.
+fn push_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload() {
+        let listens = vec![
+            Listen {
+                artist: "Radiohead".to_string(),
+                track: "Creep".to_string(),
+                release: Some("Creep".to_string()),
+                recording: Some("70595637-9310-45f2-a266-58f8de4874a7".parse().unwrap()),
+                listened_at: 1500000000,
+            },
+            Listen {
+                artist: "A \"quoted\"\\artist".to_string(),
+                track: "Line\nbreak".to_string(),
+                release: None,
+                recording: None,
+                listened_at: 1500000300,
+            },
+        ];
+
+        assert_eq!(
+            build_payload("import", &listens, true),
+            r#"{"listen_type":"import","payload":[{"listened_at":1500000000,"track_metadata":{"artist_name":"Radiohead","track_name":"Creep","release_name":"Creep","additional_info":{"recording_mbid":"70595637-9310-45f2-a266-58f8de4874a7"}}},{"listened_at":1500000300,"track_metadata":{"artist_name":"A \"quoted\"\\artist","track_name":"Line\nbreak"}}]}"#
+        );
+        assert_eq!(
+            build_payload("playing_now", &listens[1..], false),
+            r#"{"listen_type":"playing_now","payload":[{"track_metadata":{"artist_name":"A \"quoted\"\\artist","track_name":"Line\nbreak"}}]}"#
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Limits and offsets of paged requests, i.e. browsing and searching.
+//!
+//! The API returns at most 100 entities per request and silently clamps
+//! larger limits, so a request for 500 entities would look like it returned
+//! all of them. `Limit` only accepts values the API supports, which turns such
+//! mistakes into a usage error before any request is made.
+
+use crate::error::{Error, ErrorKind};
+
+use std::fmt;
+
+/// The maximal number of entities per request, as a `u8` for use in
+/// constants.
+const MAX: u8 = 100;
+
+/// The number of entities requested per page, between 1 and 100.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Limit(u8);
+
+impl Limit {
+    /// The largest limit supported by the API.
+    pub const MAX: Limit = Limit(MAX);
+
+    /// Returns a usage error unless `limit` is between 1 and 100.
+    pub fn new(limit: u32) -> Result<Self, Error> {
+        if limit == 0 || limit > u32::from(MAX) {
+            Err(Error::new(
+                format!("The limit has to be between 1 and {}, got {}.", MAX, limit),
+                ErrorKind::UsageError,
+            ))
+        } else {
+            Ok(Limit(limit as u8))
+        }
+    }
+
+    /// The number of entities.
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// The index of the first entity of a page in all results.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Offset(u32);
+
+impl Offset {
+    /// Start at the entity with index `offset`, starting at 0.
+    pub fn new(offset: u32) -> Self {
+        Offset(offset)
+    }
+
+    /// The index of the entity.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// The offset of the page following a page of `len` entities starting at
+    /// this offset.
+    pub(crate) fn advance(self, len: u32) -> Self {
+        Offset(self.0.saturating_add(len))
+    }
+}
+
+impl From<u32> for Offset {
+    fn from(offset: u32) -> Self {
+        Offset(offset)
+    }
+}
+
+impl fmt::Display for Offset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        assert_eq!(Limit::new(1).unwrap().get(), 1);
+        assert_eq!(Limit::new(100).unwrap(), Limit::MAX);
+        assert!(Limit::new(0).unwrap_err().is_usage_error());
+        assert!(Limit::new(101).unwrap_err().is_usage_error());
+        assert!(Limit::new(u32::MAX).is_err());
+
+        assert_eq!(Offset::from(100).advance(25), Offset::new(125));
+        assert_eq!(Offset::new(u32::MAX).advance(1).get(), u32::MAX);
+        assert_eq!(Offset::default().to_string(), "0");
+    }
+}
@@ -0,0 +1,27 @@
+//! Progress reporting of operations performing many requests.
+//!
+//! Fetching all pages of a browse or many entities with a `BulkFetch` can
+//! take minutes because of the rate limit. A `Progress` attached to them is
+//! told how far they got, so e.g. a command line tool can show a progress bar
+//! with the library of its choice.
+
+/// Receives the progress of an operation.
+///
+/// Implemented for closures taking the same arguments as `on_progress`.
+pub trait Progress {
+    /// Called whenever the operation advanced, with the number of items
+    /// which are done and the total number of items.
+    ///
+    /// The total is only known once the first request was performed, so the
+    /// first call might only happen after it.
+    fn on_progress(&mut self, done: u64, total: u64);
+}
+
+impl<F> Progress for F
+where
+    F: FnMut(u64, u64),
+{
+    fn on_progress(&mut self, done: u64, total: u64) {
+        self(done, total)
+    }
+}
@@ -1,8 +1,8 @@
-use super::entities::{Date, Mbid};
+use super::entities::{Mbid, PartialDate, TrackLength};
 use rusqlite::Error as RusqliteError;
 use rusqlite::types::{FromSql, FromSqlError, ToSql, ToSqlOutput, Value, ValueRef};
 
-impl FromSql for Date {
+impl FromSql for PartialDate {
     fn column_result(value: ValueRef) -> Result<Self, FromSqlError> {
         match value {
             ValueRef::Text(s) => s.parse().map_err(|e| FromSqlError::Other(From::from(e))),
@@ -11,7 +11,7 @@ impl FromSql for Date {
     }
 }
 
-impl ToSql for Date {
+impl ToSql for PartialDate {
     fn to_sql(&self) -> Result<ToSqlOutput, RusqliteError> {
         let s = self.to_string();
         Ok(ToSqlOutput::Owned(Value::Text(s)))
@@ -33,3 +33,19 @@ impl ToSql for Mbid {
         Ok(ToSqlOutput::Owned(Value::Text(s)))
     }
 }
+
+/// Stored as the number of milliseconds.
+impl FromSql for TrackLength {
+    fn column_result(value: ValueRef) -> Result<Self, FromSqlError> {
+        match value {
+            ValueRef::Integer(i) if i >= 0 => Ok(TrackLength::from_millis(i as u64)),
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+impl ToSql for TrackLength {
+    fn to_sql(&self) -> Result<ToSqlOutput, RusqliteError> {
+        Ok(ToSqlOutput::Owned(Value::Integer(self.as_millis() as i64)))
+    }
+}
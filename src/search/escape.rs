@@ -0,0 +1,275 @@
+//! Escaping of user input for search queries.
+//!
+//! The search builders already escape the values of the fields added to them,
+//! these functions are meant for applications which assemble lucene queries
+//! themselves, e.g. to use syntax not supported by the builders.
+//!
+//! All functions guarantee that the escaped text is matched literally by the
+//! search server, i.e. no character of the input is interpreted as lucene
+//! syntax. The escaping is reversed by the server, so the text isn't altered
+//! otherwise.
+//!
+//! [Lucene syntax](https://lucene.apache.org/core/4_3_0/queryparser/org/apache/lucene/queryparser/classic/package-summary.html#Escaping_Special_Characters)
+
+use url::percent_encoding::{DEFAULT_ENCODE_SET, utf8_percent_encode};
+
+/// Escape all lucene special characters, so `text` is matched literally.
+///
+/// This includes the operators `AND`, `OR` and `NOT`, which are only
+/// operators if they are written in upper case and stand alone.
+///
+/// Whitespace is not escaped, so text containing whitespace is still split
+/// into separate terms. Use `escape_value` to search for it as a phrase.
+///
+/// ```
+/// use musicbrainz::search::escape::escape_lucene;
+///
+/// assert_eq!(escape_lucene("AC/DC"), r"AC\/DC");
+/// assert_eq!(escape_lucene("Rock && Roll"), r"Rock \&& Roll");
+/// assert_eq!(escape_lucene("Rock AND Roll"), r"Rock \AND Roll");
+/// ```
+pub fn escape_lucene(text: &str) -> String {
+    // Replace all special lucene syntax elements.
+    //
+    // This is done by hand instead of with a regex, as this function is called
+    // for every single search parameter.
+    let mut sanitized = String::with_capacity(text.len() * 2);
+    let mut chars = text.char_indices().peekable();
+    let mut word_start = true;
+    while let Some((i, c)) = chars.next() {
+        if word_start && is_operator_at(text, i) {
+            sanitized.push('\\');
+        }
+        word_start = c.is_whitespace();
+        match c {
+            '+' | '-' | '!' | '(' | ')' | '{' | '}' | '[' | ']' | '^' | '"' | '~' | '*' | '?'
+            | ':' | '\\' | '/' => {
+                sanitized.push('\\');
+                sanitized.push(c);
+            }
+            '&' | '|' if chars.peek().map_or(false, |&(_, n)| n == '&' || n == '|') => {
+                sanitized.push('\\');
+                sanitized.push(c);
+                sanitized.push(chars.next().unwrap().1);
+            }
+            _ => sanitized.push(c),
+        }
+    }
+    sanitized
+}
+
+/// Whether one of the word operators starts at byte `index` of `text` and is
+/// followed by whitespace or the end of `text`.
+fn is_operator_at(text: &str, index: usize) -> bool {
+    let rest = &text[index..];
+    ["AND", "OR", "NOT"].iter().any(|op| {
+        rest.starts_with(op)
+            && rest[op.len()..]
+                .chars()
+                .next()
+                .map_or(true, char::is_whitespace)
+    })
+}
+
+/// Escape `text` for use as the value of a search field, e.g. in
+/// `release:<value>`.
+///
+/// Values containing whitespace are quoted, so they are searched as a phrase
+/// instead of being split into separate terms.
+///
+/// ```
+/// use musicbrainz::search::escape::escape_value;
+///
+/// assert_eq!(escape_value("Halo"), "Halo");
+/// assert_eq!(escape_value("Nine Inch Nails"), "\"Nine Inch Nails\"");
+/// ```
+pub fn escape_value(text: &str) -> String {
+    let escaped = escape_lucene(text);
+    if text.contains(char::is_whitespace) {
+        format!("\"{}\"", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// Combine pairs of field name and lucene expression into a query, which
+/// matches if all of the expressions match.
+///
+/// The expressions are used as they are, so values have to be escaped with
+/// `escape_value` first. The query isn't percent encoded, which is done when
+/// passing it as the `query` parameter of a url, e.g. with
+/// `Url::parse_with_params`.
+///
+/// ```
+/// use musicbrainz::search::escape::{and_query, escape_value};
+///
+/// let query = and_query(&[
+///     ("artist", escape_value("Nine Inch Nails")),
+///     ("date", "[2000 TO *]".to_string()),
+/// ]);
+/// assert_eq!(query, "artist:\"Nine Inch Nails\" AND date:[2000 TO *]");
+/// ```
+pub fn and_query<S: AsRef<str>>(params: &[(&str, S)]) -> String {
+    params
+        .iter()
+        .map(|&(name, ref expression)| format!("{}:{}", name, expression.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// Escape all lucene special characters and then percent encode the result,
+/// so it can be used directly in the query component of a url.
+pub fn escape_full(text: &str) -> String {
+    let sanitized = escape_lucene(text);
+
+    // Now escape the result so it can be used in the query.
+    let s = escape_query(&sanitized);
+
+    // Percent encode = and & which haven't been touched by escape_query.
+    let s = s.replace("&", "%26");
+    let s = s.replace("=", "%3D");
+    s
+}
+
+fn escape_query(text: &str) -> String {
+    utf8_percent_encode(text, DEFAULT_ENCODE_SET).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    #[test]
+    fn test_escape_full() {
+        // lucene syntax elements
+        assert_eq!(escape_full("+"), escape_query(r"\+"));
+        assert_eq!(escape_full("-"), escape_query(r"\-"));
+        assert_eq!(escape_full("&&"), "\\%26%26".to_string());
+        assert_eq!(escape_full("||"), escape_query(r"\||"));
+        assert_eq!(escape_full("!"), escape_query(r"\!"));
+        assert_eq!(escape_full("("), escape_query(r"\("));
+        assert_eq!(escape_full(")"), escape_query(r"\)"));
+        assert_eq!(escape_full("{"), escape_query(r"\{"));
+        assert_eq!(escape_full("}"), escape_query(r"\}"));
+        assert_eq!(escape_full("["), escape_query(r"\["));
+        assert_eq!(escape_full("]"), escape_query(r"\]"));
+        assert_eq!(escape_full("^"), escape_query(r"\^"));
+        assert_eq!(escape_full("\""), escape_query("\\\""));
+        assert_eq!(escape_full("~"), escape_query(r"\~"));
+        assert_eq!(escape_full("*"), escape_query(r"\*"));
+        assert_eq!(escape_full("?"), escape_query(r"\?"));
+        assert_eq!(escape_full(":"), escape_query(r"\:"));
+        assert_eq!(escape_full(r"\"), escape_query(r"\\"));
+        assert_eq!(escape_full("/"), escape_query(r"\/"));
+
+        // & and = are not to be touched by escape query but we have to escape them at
+        // this point
+        // too because it would mess up the query component.
+        assert_eq!(escape_full("&"), "%26".to_string());
+        assert_eq!(escape_full("="), "%3D".to_string());
+
+        // sanity check that the whitespace in the regex is actually ignored
+        assert_eq!(escape_full(" "), escape_query(" "));
+        assert_eq!(escape_full("  "), escape_query("  "));
+    }
+
+    /// Reverse `escape_value`, like the lucene query parser does.
+    fn unescape(value: &str) -> String {
+        let value = if value.len() > 1 && value.starts_with('"') && value.ends_with('"') {
+            &value[1..value.len() - 1]
+        } else {
+            value
+        };
+        let mut text = String::new();
+        let mut chars = value.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                text.extend(chars.next());
+            } else {
+                text.push(c);
+            }
+        }
+        text
+    }
+
+    /// Every combination of up to three of the characters, which covers the
+    /// lucene syntax, url delimiters and non ascii characters.
+    fn texts() -> Vec<String> {
+        let chars = [
+            'a', ' ', '+', '-', '!', '(', ')', '{', '}', '[', ']', '^', '"', '~', '*', '?', ':',
+            '\\', '/', '&', '|', '=', '%', '#', 'ä', '霊',
+        ];
+        let mut texts = Vec::new();
+        let mut layer = vec![String::new()];
+        for _ in 0..3 {
+            layer = layer
+                .iter()
+                .flat_map(|t| chars.iter().map(move |c| format!("{}{}", t, c)))
+                .collect();
+            texts.extend(layer.iter().cloned());
+        }
+        texts
+    }
+
+    #[test]
+    fn round_trip() {
+        for text in texts() {
+            let query = and_query(&[("release", escape_value(&text))]);
+            let url = Url::parse_with_params(
+                "https://musicbrainz.org/ws/2/release/",
+                &[("query", &query)],
+            ).unwrap();
+            let (key, decoded) = url.query_pairs().next().unwrap();
+
+            assert_eq!(key, "query");
+            assert!(decoded.starts_with("release:"));
+            assert_eq!(unescape(&decoded["release:".len()..]), text);
+        }
+    }
+
+    #[test]
+    fn escaped_text_is_literal() {
+        // Outside of escape sequences and quotes no syntax characters remain.
+        for text in texts() {
+            let escaped = escape_lucene(&text);
+            let mut chars = escaped.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' | '(' | ')' | ':' | '*' | '?' | '/' | '[' | ']' | '{' | '}' => {
+                        panic!("unescaped '{}' in {:?}", c, escaped)
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn operators_are_literal() {
+        assert_eq!(escape_lucene("AND"), r"\AND");
+        assert_eq!(escape_lucene("NOT OR"), r"\NOT \OR");
+        assert_eq!(escape_lucene("Love\tAND Hate"), "Love\t\\AND Hate");
+        assert_eq!(escape_value("Rock AND Roll"), r#""Rock \AND Roll""#);
+
+        // Only upper case words on their own are operators.
+        assert_eq!(escape_lucene("ANDROID"), "ANDROID");
+        assert_eq!(escape_lucene("SAND OR"), r"SAND \OR");
+        assert_eq!(escape_lucene("and or not"), "and or not");
+        assert_eq!(escape_lucene("(NOT)"), r"\(NOT\)");
+
+        let query = and_query(&[("artist", escape_value("NOT"))]);
+        assert_eq!(query, r"artist:\NOT");
+        assert_eq!(unescape(&query["artist:".len()..]), "NOT");
+    }
+
+    #[test]
+    fn test_escape_query() {
+        // these are all legal in query component
+        let legal = r#"/:@-._~!$&'()*+,;="#;
+        assert_eq!(escape_query(legal), legal.to_string());
+    }
+}
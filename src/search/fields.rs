@@ -9,13 +9,128 @@
 
 use super::full_entities;
 // use super::query::QueryExpression;
-use super::full_entities::{Mbid, PartialDate};
+use super::full_entities::{Mbid, PartialDate, TrackLength};
 // use super::entities;
 
 pub trait SearchField {
-    type Value;
+    type Value: SearchValue;
 
     fn to_string(&self) -> String;
+
+    /// Check the value of the field, returning a description of the problem
+    /// if it is invalid.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Conversion of a value into the form expected by the search index.
+///
+/// This differs from `Display` for some types, e.g. enum values have to be
+/// lowercase (`official` instead of `Official`) to actually match anything.
+pub trait SearchValue {
+    fn to_search_value(&self) -> String;
+
+    /// Check the value, returning a description of the problem if it can't
+    /// possibly match anything.
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+macro_rules! search_value_display {
+    ( $( $type:ty ),* ) => {
+        $(
+            impl SearchValue for $type {
+                fn to_search_value(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    }
+}
+
+macro_rules! search_value_lowercase {
+    ( $( $type:ty ),* ) => {
+        $(
+            impl SearchValue for $type {
+                fn to_search_value(&self) -> String {
+                    self.to_string().to_lowercase()
+                }
+            }
+        )*
+    }
+}
+
+search_value_display!(Mbid, bool, u16, u32, f64, full_entities::Script);
+
+impl SearchValue for String {
+    fn to_search_value(&self) -> String {
+        self.clone()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.trim().is_empty() {
+            Err("value is empty".to_string())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+search_value_lowercase!(
+    full_entities::AreaType,
+    full_entities::ArtistType,
+    full_entities::EventType,
+    full_entities::LabelType,
+    full_entities::PlaceType,
+    full_entities::ReleaseGroupPrimaryType,
+    full_entities::ReleaseGroupSecondaryType,
+    full_entities::ReleaseStatus,
+    full_entities::SeriesType
+);
+
+/// Dates are searched with only their known leading components, e.g. `1992`
+/// or `1992-09`, which the index matches against all dates in that period.
+impl SearchValue for PartialDate {
+    fn to_search_value(&self) -> String {
+        match (self.year(), self.month(), self.day()) {
+            (Some(y), Some(m), Some(d)) => format!("{:04}-{:02}-{:02}", y, m, d),
+            (Some(y), Some(m), None) => format!("{:04}-{:02}", y, m),
+            (Some(y), _, _) => format!("{:04}", y),
+            (None, _, _) => String::new(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match (self.year(), self.month(), self.day()) {
+            (None, _, _) => Err("date without a year".to_string()),
+            (Some(_), None, Some(_)) => Err("date with a day but no month".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Lengths are searched in milliseconds.
+impl SearchValue for TrackLength {
+    fn to_search_value(&self) -> String {
+        self.as_millis().to_string()
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        // Lengths are stored as 32 bit integers by MusicBrainz.
+        if self.as_millis() > i32::max_value() as u64 {
+            Err(format!("length of {} ms is out of range", self.as_millis()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl SearchValue for full_entities::Language {
+    fn to_search_value(&self) -> String {
+        self.to_639_3().to_string()
+    }
 }
 
 macro_rules! define_fields {
@@ -28,7 +143,11 @@ macro_rules! define_fields {
                 type Value = $value;
 
                 fn to_string(&self) -> String {
-                    self.0.to_string()
+                    self.0.to_search_value()
+                }
+
+                fn validate(&self) -> Result<(), String> {
+                    self.0.validate()
                 }
             }
         )*
@@ -39,10 +158,12 @@ macro_rules! define_fields {
 // something like that to
 // be more consistent with the rest of the crate.
 //
-// TODO: enums for quality, script, etc
+// TODO: enums for quality, etc
 // TODO it's a bit ugly we have `-` at the beginning of every line but its a
 // workaround around the parsing ambiguity we'd have if we didn't.
 define_fields!(
+    /// The address of a `Place`.
+    - Address, String;
     /// Alias of the searched entity's name.
     - Alias, String;
     /// The MBID of the `Area`.
@@ -76,6 +197,7 @@ define_fields!(
     ///
     /// Check the searched entity's documentation for more information what this means concretely.
     - BeginDate, PartialDate;
+    /// A catalog number of the `Release`, as assigned by one of its labels.
     - CatalogNumber, String;
     /// Disambiguation comment of the searched entity.
     - Comment, String;
@@ -87,6 +209,17 @@ define_fields!(
     ///
     /// Check the searched entity's documentation for more information what this means concretely.
     - EndDate, PartialDate;
+    /// The MBID of the `Event`.
+    - EventMbid, Mbid;
+    /// The release date of the earliest release in a `ReleaseGroup`, or the
+    /// earliest release a `Recording` appears on.
+    - FirstReleaseDate, PartialDate;
+    /// The name of the `Event` without accented characters.
+    - EventName, String;
+    /// The name of the `Event` with accented characters.
+    - EventNameAccent, String;
+    /// The type of the `Event`.
+    - EventType, full_entities::EventType;
     /// Whether the searched entity has already ended.
     ///
     /// Check the searched entity's documentation for more information what this means concretely.
@@ -94,8 +227,25 @@ define_fields!(
     /// The gender of an `Artist`.
     - Gender, String;
     - IpiCode, String;
-    - LabelId, String;
+    /// ISNI code of the searched entity.
+    - IsniCode, String;
+    /// ISRC (International Standard Recording Code) of a `Recording`.
+    - Isrc, String;
+    /// The label code of a `Label`, without the `LC` prefix.
+    - LabelCode, u32;
+    /// The MBID of a `Label` which issued the `Release`.
+    - LabelId, Mbid;
+    /// The name of the `Label` without accented characters.
+    - LabelName, String;
+    /// The name of the `Label` with accented characters.
+    - LabelNameAccent, String;
+    /// The type of the `Label`.
+    - LabelType, full_entities::LabelType;
     - Language, full_entities::Language;
+    /// The latitude of a `Place`.
+    - Latitude, f64;
+    /// The longitude of a `Place`.
+    - Longitude, f64;
     - MediumCount, u32;
     - MediumFormat, String;
     /// The searched entity's name. (TODO implement for all relevant searches)
@@ -104,6 +254,18 @@ define_fields!(
     - NumDiscIdsMedium, u32;
     - NumTracks, u32;
     - NumTracksMedium, u32;
+    /// Total number of tracks of a `Release` a `Recording` appears on.
+    - NumTracksRelease, u32;
+    /// The packaging of a `Release`.
+    - Packaging, String;
+    /// The MBID of the `Place`.
+    - PlaceMbid, Mbid;
+    /// The name of the `Place` without accented characters.
+    - PlaceName, String;
+    /// The name of the `Place` with accented characters.
+    - PlaceNameAccent, String;
+    /// The type of the `Place`.
+    - PlaceType, full_entities::PlaceType;
     - PrimaryType, full_entities::ReleaseGroupPrimaryType;
     - ReleaseDate, full_entities::PartialDate;
     - ReleaseGroupId, Mbid;
@@ -115,12 +277,41 @@ define_fields!(
     /// The name of the `Release`, including special accent characters.
     - ReleaseNameAccent, String;
     - ReleaseNumber, u16;
+    /// The MBID of the `Recording`.
+    - RecordingMbid, Mbid;
+    /// The title of the `Recording` without accented characters.
+    - RecordingName, String;
+    /// The title of the `Recording` with accented characters.
+    - RecordingNameAccent, String;
+    /// The length of a `Recording` in a form allowing approximate matches,
+    /// i.e. divided by 2000 and rounded.
+    - RecordingDurationQuantized, u32;
+    /// Whether the `Recording` is a video.
+    - Video, bool;
     - ReleaseStatus, full_entities::ReleaseStatus;
-    - Script, String;
+    /// The script of the track list of a `Release`.
+    - Script, full_entities::Script;
     - SecondaryType, String;
+    /// The MBID of the `Series`.
+    - SeriesMbid, Mbid;
+    /// The name of the `Series`.
+    - SeriesName, String;
+    /// The type of the `Series`.
+    - SeriesType, full_entities::SeriesType;
     /// The sort name of the searched entity.
     - SortName, String;
-    - Tag, String
+    /// An alias of the searched entity which is marked as primary for its
+    /// locale.
+    - PrimaryAlias, String;
+    - Tag, String;
+    /// The length of a track or `Recording`.
+    - TrackDuration, TrackLength;
+    /// The MBID of a track the `Recording` appears as.
+    - TrackMbid, Mbid;
+    /// The track number as printed on the `Release`, e.g. `A1`.
+    - TrackNumber, String;
+    /// The position of the track on its medium.
+    - TrackPosition, u32
 );
 
 macro_rules! define_entity_fields {
@@ -169,6 +360,7 @@ define_entity_fields!(
     "iso2", AreaIso2;
     "iso3", AreaIso3;
     "sortname", SortName;
+    "tag", Tag;
     "type", AreaType;
 );
 
@@ -189,15 +381,112 @@ define_entity_fields!(
     "ended", Ended;
     "gender", Gender;
     "ipi", IpiCode;
+    "isni", IsniCode;
+    "primary_alias", PrimaryAlias;
     "sortname", SortName;
     "tag", Tag;
     "type", ArtistType;
 );
 
+define_entity_fields!(
+    EventSearchField, event;
+
+    "aid", AreaMbid;
+    "alias", Alias;
+    "area", AreaName;
+    "arid", ArtistMbid;
+    "artist", ArtistName;
+    "begin", BeginDate;
+    "comment", Comment;
+    "eid", EventMbid;
+    "end", EndDate;
+    "ended", Ended;
+    "event", EventName;
+    "eventaccent", EventNameAccent;
+    "pid", PlaceMbid;
+    "place", PlaceName;
+    "tag", Tag;
+    "type", EventType;
+);
+
+define_entity_fields!(
+    LabelSearchField, label;
+
+    "alias", Alias;
+    "area", AreaName;
+    "begin", BeginDate;
+    "code", LabelCode;
+    "comment", Comment;
+    "country", Country;
+    "end", EndDate;
+    "ended", Ended;
+    "ipi", IpiCode;
+    "isni", IsniCode;
+    "label", LabelName;
+    "labelaccent", LabelNameAccent;
+    "laid", LabelId;
+    "sortname", SortName;
+    "tag", Tag;
+    "type", LabelType;
+);
+
+define_entity_fields!(
+    PlaceSearchField, place;
+
+    "address", Address;
+    "alias", Alias;
+    "area", AreaName;
+    "begin", BeginDate;
+    "comment", Comment;
+    "end", EndDate;
+    "ended", Ended;
+    "lat", Latitude;
+    "long", Longitude;
+    "pid", PlaceMbid;
+    "place", PlaceName;
+    "placeaccent", PlaceNameAccent;
+    "type", PlaceType;
+);
+
+define_entity_fields!(
+    RecordingSearchField, recording;
+
+    "alias", Alias;
+    "arid", ArtistMbid;
+    "artist", ArtistCredit;
+    "artistname", ArtistName;
+    "comment", Comment;
+    "country", Country;
+    "creditname", CreditName;
+    "date", ReleaseDate;
+    "dur", TrackDuration;
+    "firstreleasedate", FirstReleaseDate;
+    "format", MediumFormat;
+    "isrc", Isrc;
+    "number", TrackNumber;
+    "position", TrackPosition;
+    "primarytype", PrimaryType;
+    "qdur", RecordingDurationQuantized;
+    "recording", RecordingName;
+    "recordingaccent", RecordingNameAccent;
+    "reid", ReleaseId;
+    "release", ReleaseName;
+    "rgid", ReleaseGroupId;
+    "rid", RecordingMbid;
+    "secondarytype", SecondaryType;
+    "status", ReleaseStatus;
+    "tag", Tag;
+    "tid", TrackMbid;
+    "tracks", NumTracksMedium;
+    "tracksrelease", NumTracksRelease;
+    "video", Video;
+);
+
 // TODO what are puids?
 define_entity_fields!(
     ReleaseSearchField, release;
 
+    "alias", Alias;
     "arid", ArtistMbid;
     "artist", ArtistName;
     "asin", Asin;
@@ -210,9 +499,11 @@ define_entity_fields!(
     "discids", NumDiscIds;
     "discidsmedium", NumDiscIdsMedium;
     "format", MediumFormat;
+    "label", LabelName;
     "laid", LabelId;
     "lang", Language;
     "mediums", MediumCount;
+    "packaging", Packaging;
     "primarytype", PrimaryType;
     "quality", DataQuality;
     "reid", ReleaseId;
@@ -227,14 +518,27 @@ define_entity_fields!(
     "tracksmedium", NumTracksMedium;
 );
 
+define_entity_fields!(
+    SeriesSearchField, series;
+
+    "alias", Alias;
+    "comment", Comment;
+    "series", SeriesName;
+    "sid", SeriesMbid;
+    "tag", Tag;
+    "type", SeriesType;
+);
+
 define_entity_fields!(
     ReleaseGroupSearchField, release_group;
 
+    "alias", Alias;
     "arid", ArtistMbid;
     "artist", ArtistCredit;
     "artistname", ArtistName;
     "comment", Comment;
     "creditname", CreditName;
+    "firstreleasedate", FirstReleaseDate;
     "primarytype", PrimaryType;
     "reid", ReleaseId;
     "release", ReleaseName;
@@ -246,3 +550,60 @@ define_entity_fields!(
     "status", ReleaseStatus;
     "tag", Tag;
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_values() {
+        assert_eq!(
+            ReleaseStatus(full_entities::ReleaseStatus::PseudoRelease).to_string(),
+            "pseudo-release".to_string()
+        );
+        assert_eq!(
+            PrimaryType(full_entities::ReleaseGroupPrimaryType::Album).to_string(),
+            "album".to_string()
+        );
+        assert_eq!(
+            Language(full_entities::Language::from_639_3("jpn").unwrap()).to_string(),
+            "jpn".to_string()
+        );
+        assert_eq!(NumTracks(12).to_string(), "12".to_string());
+        assert_eq!(
+            TrackDuration(TrackLength::from_secs(200)).to_string(),
+            "200000".to_string()
+        );
+        assert!(TrackDuration(TrackLength::from_secs(1 << 40)).validate().is_err());
+        assert_eq!(
+            LabelType(full_entities::LabelType::ProductionOriginal).to_string(),
+            "original production".to_string()
+        );
+    }
+
+    #[test]
+    fn date_fields() {
+        use crate::entities::PartialDate;
+        use std::str::FromStr;
+
+        assert_eq!(
+            FirstReleaseDate(PartialDate::from_str("1992-09").unwrap()).to_string(),
+            "1992-09".to_string()
+        );
+        assert!(FirstReleaseDate(PartialDate::new(None, Some(9), None))
+            .validate()
+            .is_err());
+        assert_eq!(
+            <FirstReleaseDate as release_group::ReleaseGroupSearchField>::name(),
+            "firstreleasedate"
+        );
+    }
+
+    #[test]
+    fn entity_field_names() {
+        assert_eq!(<LabelName as label::LabelSearchField>::name(), "label");
+        assert_eq!(<PlaceMbid as place::PlaceSearchField>::name(), "pid");
+        assert_eq!(<SeriesType as series::SeriesSearchField>::name(), "type");
+        assert_eq!(<AreaName as event::EventSearchField>::name(), "area");
+    }
+}
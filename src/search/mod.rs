@@ -14,34 +14,60 @@
 //! search entity.
 
 use crate::entities as full_entities;
-use crate::entities::ResourceOld;
-use crate::error::Error;
-use crate::client::Client;
+use crate::entities::Resource;
+use crate::error::{Error, ErrorKind};
+use crate::client::{Client, ResponseMeta};
+use crate::filter::ReleaseFilter;
+use crate::paging::{Limit, Offset};
 
-use reqwest_mock::Url;
-use url::percent_encoding::{DEFAULT_ENCODE_SET, utf8_percent_encode};
+use url::Url;
 use xpath_reader::{FromXml, Reader};
 
 pub mod fields;
-use self::fields::{AreaSearchField, ArtistSearchField, ReleaseGroupSearchField, ReleaseSearchField};
+use self::fields::{AreaSearchField, ArtistSearchField, EventSearchField, LabelSearchField,
+                   PlaceSearchField, RecordingSearchField, ReleaseGroupSearchField,
+                   ReleaseSearchField, SeriesSearchField};
 
 pub mod search_entities;
 use self::search_entities::SearchEntity;
 
 pub type SearchResult<Entity> = Result<Vec<SearchEntry<Entity>>, Error>;
 
+pub mod escape;
+
 pub mod query;
 
 pub trait SearchBuilder {
-    /// The entity from the client::search::entities module,
+    /// The entity from the `search::search_entities` module,
     /// this is the entity contained in the search result.
     type Entity: SearchEntity;
 
     /// The full entity a search entity can be expanded into.
-    type FullEntity: ResourceOld + FromXml;
+    type FullEntity: Resource;
 
     /// Perform the search.
-    fn search(self) -> SearchResult<Self::Entity>;
+    fn search(self) -> SearchResult<Self::Entity> {
+        self.search_with_meta().map(|(results, _)| results)
+    }
+
+    /// Perform the search, also returning metadata about the response.
+    fn search_with_meta(self) -> Result<(Vec<SearchEntry<Self::Entity>>, ResponseMeta), Error>;
+
+    /// Perform the search and return only the top entry, if its score is at
+    /// least `threshold`.
+    ///
+    /// This is useful if only an obvious match is of interest.
+    fn best_match(self, threshold: u8) -> Result<Option<SearchEntry<Self::Entity>>, Error>
+    where
+        Self: Sized,
+    {
+        // The server returns the entries ordered by score.
+        Ok(self
+            .search()?
+            .into_iter()
+            .next()
+            .filter(|entry| entry.score >= threshold))
+    }
 }
 
 /// One entry of the search results.
@@ -58,62 +84,218 @@ where
     pub score: u8,
 }
 
+/// A search which can be performed with `Client::run`.
+///
+/// Queries are plain values, so they can be built ahead of time, stored,
+/// cloned and performed again, e.g. to retry a search which failed.
+pub trait SearchQuery {
+    /// The entity from the `search::search_entities` module,
+    /// this is the entity contained in the search result.
+    type Entity: SearchEntity;
+
+    /// Builds the full url to be used to perform the search request.
+    fn search_url(&self) -> Result<Url, Error>;
+
+    /// Parse a response to the search request, dropping entries below the
+    /// minimal score of the query.
+    fn parse_results(&self, xml: &str) -> SearchResult<Self::Entity>;
+}
+
 macro_rules! define_search_builder {
     ( $builder:ident,
+      $query:ident,
       $fields:ident,
       $entity:ty,
       $full_entity:ty,
       $list_tag:expr ) => {
-        pub struct $builder<'cl> {
+        /// A search which isn't tied to a client, see `SearchQuery`.
+        #[derive(Clone, Debug, Default, Eq, PartialEq)]
+        pub struct $query {
+            /// Field names and the lucene expressions they have to match.
             params: Vec<(&'static str, String)>,
-            client: &'cl mut Client,
+            /// The problem with the first invalid field which was added.
+            error: Option<String>,
+            /// Entries with a lower score are dropped from the results.
+            min_score: u8,
+            limit: Option<Limit>,
+            offset: Option<Offset>,
         }
 
-        impl<'cl> $builder<'cl> {
-            pub fn new(client: &'cl mut Client) -> Self {
-                Self {
-                    params: Vec::new(),
-                    client: client,
-                }
+        impl $query {
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            /// Drop entries with a score below `score` from the results.
+            ///
+            /// The filtering is done after receiving the response, so the
+            /// number of entries requested from the server is not affected.
+            pub fn min_score(mut self, score: u8) -> Self {
+                self.min_score = score;
+                self
             }
 
             /// Specify an additional parameter for the query.
             ///
-            /// Currently all parameters will be combined using `AND`.
+            /// Currently all parameters will be combined using `AND`. The
+            /// value is matched literally, i.e. lucene syntax in it is
+            /// escaped.
+            ///
+            /// If the value of the field is invalid, the search will fail
+            /// with a usage error without performing a request.
             pub fn add<F>(mut self, field: F) -> Self
             where
                 F: $fields,
             {
-                self.params.push((F::name(), field.to_string()));
+                if let Err(problem) = field.validate() {
+                    if self.error.is_none() {
+                        self.error = Some(format!(
+                            "Invalid value for search field `{}`: {}",
+                            F::name(),
+                            problem
+                        ));
+                    }
+                }
+                self.params.push((F::name(), escape::escape_value(&field.to_string())));
                 self
             }
 
-            /// Builds the full url to be used to perform the search request.
-            fn build_url(&self) -> Result<Url, Error> {
-                let mut query_parts: Vec<String> = Vec::new();
-                for &(p_name, ref p_value) in self.params.iter() {
-                    // TODO (FIXME): Does this also encode ":" ?
-                    let value = utf8_percent_encode(p_value.as_ref(), DEFAULT_ENCODE_SET);
-                    query_parts.push(format!("{}:{}", p_name, value));
+            /// The maximal number of entries to return, the server returns 25
+            /// entries if no limit is specified.
+            pub fn limit(mut self, limit: Limit) -> Self {
+                self.limit = Some(limit);
+                self
+            }
+
+            /// Skip the first `offset` entries, e.g. to fetch the next page
+            /// of results.
+            pub fn offset(mut self, offset: Offset) -> Self {
+                self.offset = Some(offset);
+                self
+            }
+
+            /// Returns the lucene query which will be sent, e.g.
+            /// `artist:"Nine Inch Nails" AND type:group`.
+            ///
+            /// The values of the fields are escaped for lucene, but the query
+            /// isn't percent encoded yet, see `build_url` for the encoded
+            /// form. This is useful to find out why a search doesn't return
+            /// the expected results.
+            pub fn query_string(&self) -> Result<String, Error> {
+                if let Some(ref problem) = self.error {
+                    return Err(Error::new(problem.as_str(), ErrorKind::UsageError));
                 }
 
                 // TODO: In the future support OR queries too.
-                let query = query_parts.join("%20AND%20");
+                Ok(escape::and_query(&self.params))
+            }
+
+            /// Builds the full url to be used to perform the search request.
+            ///
+            /// This is useful to inspect exactly what would be sent, without
+            /// performing the request.
+            pub fn build_url(&self) -> Result<Url, Error> {
+                let query = self.query_string()?;
                 type FE = $full_entity;
                 let base_url = format!("https://musicbrainz.org/ws/2/{}/", FE::NAME);
-                Ok(Url::parse(
-                    format!("{}?query={}", base_url, query).as_ref(),
-                )?)
+                let mut params = vec![("query", query)];
+                if let Some(limit) = self.limit {
+                    params.push(("limit", limit.to_string()));
+                }
+                if let Some(offset) = self.offset {
+                    params.push(("offset", offset.to_string()));
+                }
+                Ok(Url::parse_with_params(&base_url, &params)?)
             }
 
             /// Parse the search result.
-            fn parse_xml(xml: &str) -> SearchResult<$entity> {
+            pub(crate) fn parse_xml(xml: &str) -> SearchResult<$entity> {
                 let mut context = crate::util::musicbrainz_context();
                 context.set_namespace("ext", "http://musicbrainz.org/ns/ext#-2.0");
 
                 let reader = Reader::from_str(xml, Some(&context))?;
                 crate::client::check_response_error(&reader)?;
-                Ok(reader.read("//mb:metadata")?)
+                Ok(reader.read(format!("//mb:metadata/mb:{}/*", $list_tag).as_str())?)
+            }
+        }
+
+        impl SearchQuery for $query {
+            type Entity = $entity;
+
+            fn search_url(&self) -> Result<Url, Error> {
+                self.build_url()
+            }
+
+            fn parse_results(&self, xml: &str) -> SearchResult<$entity> {
+                let mut entries = Self::parse_xml(xml)?;
+                entries.retain(|entry| entry.score >= self.min_score);
+                Ok(entries)
+            }
+        }
+
+        /// Builds a query and performs it with the client it was created by.
+        pub struct $builder<'cl> {
+            client: &'cl mut Client,
+            query: $query,
+        }
+
+        impl<'cl> $builder<'cl> {
+            pub fn new(client: &'cl mut Client) -> Self {
+                Self {
+                    client: client,
+                    query: $query::new(),
+                }
+            }
+
+            /// Drop entries with a score below `score` from the results, see
+            /// the method of the query.
+            pub fn min_score(mut self, score: u8) -> Self {
+                self.query = self.query.min_score(score);
+                self
+            }
+
+            /// Specify an additional parameter for the query, see the method
+            /// of the query.
+            pub fn add<F>(mut self, field: F) -> Self
+            where
+                F: $fields,
+            {
+                self.query = self.query.add(field);
+                self
+            }
+
+            /// The maximal number of entries to return.
+            pub fn limit(mut self, limit: Limit) -> Self {
+                self.query = self.query.limit(limit);
+                self
+            }
+
+            /// Skip the first `offset` entries.
+            pub fn offset(mut self, offset: Offset) -> Self {
+                self.query = self.query.offset(offset);
+                self
+            }
+
+            /// Returns the lucene query which will be sent, see the method of
+            /// the query.
+            pub fn query_string(&self) -> Result<String, Error> {
+                self.query.query_string()
+            }
+
+            /// Builds the full url to be used to perform the search request.
+            pub fn build_url(&self) -> Result<Url, Error> {
+                self.query.build_url()
+            }
+
+            /// The query built so far, which can be stored and performed
+            /// again with `Client::run`.
+            pub fn query(&self) -> &$query {
+                &self.query
+            }
+
+            /// Parse the search result.
+            pub(crate) fn parse_xml(xml: &str) -> SearchResult<$entity> {
+                $query::parse_xml(xml)
             }
         }
 
@@ -121,65 +303,118 @@ macro_rules! define_search_builder {
             type Entity = $entity;
             type FullEntity = $full_entity;
 
-            fn search(self) -> SearchResult<Self::Entity> {
-                let url = self.build_url()?;
-
-                // Perform the request.
-                let response_body = self.client.get_body(url)?;
-                Self::parse_xml(response_body.as_str())
+            fn search_with_meta(
+                self,
+            ) -> Result<(Vec<SearchEntry<Self::Entity>>, ResponseMeta), Error> {
+                self.client.run_with_meta(&self.query)
             }
         }
 
         impl FromXml for SearchEntry<$entity> {
             fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
                 Ok(Self {
-                    entity: reader.read(format!(".//mb:{}", $list_tag).as_str())?,
-                    score: reader.read(format!(".//mb:{}/*/@ext:score", $list_tag).as_str())?,
+                    entity: reader.read(".")?,
+                    score: reader.read("./@ext:score")?,
                 })
             }
         }
     };
 }
 
-/*
 define_search_builder!(
     AreaSearchBuilder,
+    AreaQuery,
     AreaSearchField,
     search_entities::Area,
     full_entities::Area,
     "area-list"
 );
-*/
-/*
 define_search_builder!(
     ArtistSearchBuilder,
+    ArtistQuery,
     ArtistSearchField,
     search_entities::Artist,
     full_entities::Artist,
     "artist-list"
 );
-*/
-/* TODO
+define_search_builder!(
+    EventSearchBuilder,
+    EventQuery,
+    EventSearchField,
+    search_entities::Event,
+    full_entities::Event,
+    "event-list"
+);
+define_search_builder!(
+    LabelSearchBuilder,
+    LabelQuery,
+    LabelSearchField,
+    search_entities::Label,
+    full_entities::Label,
+    "label-list"
+);
+define_search_builder!(
+    PlaceSearchBuilder,
+    PlaceQuery,
+    PlaceSearchField,
+    search_entities::Place,
+    full_entities::Place,
+    "place-list"
+);
+define_search_builder!(
+    RecordingSearchBuilder,
+    RecordingQuery,
+    RecordingSearchField,
+    search_entities::Recording,
+    full_entities::Recording,
+    "recording-list"
+);
 define_search_builder!(
     ReleaseSearchBuilder,
+    ReleaseQuery,
     ReleaseSearchField,
     search_entities::Release,
     full_entities::Release,
     "release-list"
 );
-*/
+
+impl ReleaseQuery {
+    /// Only find releases matching `filter`.
+    pub fn filter(mut self, filter: &ReleaseFilter) -> Self {
+        self.params.extend(filter.search_params());
+        self
+    }
+}
+
+impl<'cl> ReleaseSearchBuilder<'cl> {
+    /// Only find releases matching `filter`.
+    pub fn filter(mut self, filter: &ReleaseFilter) -> Self {
+        self.query = self.query.filter(filter);
+        self
+    }
+}
 
 define_search_builder!(
     ReleaseGroupSearchBuilder,
+    ReleaseGroupQuery,
     ReleaseGroupSearchField,
     search_entities::ReleaseGroup,
     full_entities::ReleaseGroup,
     "release-group-list"
 );
+define_search_builder!(
+    SeriesSearchBuilder,
+    SeriesQuery,
+    SeriesSearchField,
+    search_entities::Series,
+    full_entities::Series,
+    "series-list"
+);
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::test_utils::{config, stub_transport};
 
     #[test]
     fn deserialize_releasegroup() {
@@ -198,5 +433,276 @@ mod tests {
             "739de9cd-7e81-4bb0-9fdb-0feb7ea709c7".parse().unwrap()
         );
         assert_eq!(rg.entity.title, "霊魂消滅".to_string());
+        assert_eq!(
+            rg.entity.release_type.primary,
+            Some(full_entities::ReleaseGroupPrimaryType::Single)
+        );
+        assert_eq!(rg.entity.releases.len(), 1);
+        assert_eq!(
+            rg.entity.releases[0].mbid,
+            "d3d2a860-0093-461d-8d95-b77939c2e944".parse().unwrap()
+        );
+        assert_eq!(
+            rg.entity.releases[0].status,
+            Some(full_entities::ReleaseStatus::Official)
+        );
+    }
+
+    #[test]
+    fn deserialize_releasegroup_tags() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-group-list count="1" offset="0"><release-group id="b1392450-e666-3926-a536-22c65f834433" type="Album" ext:score="100"><title>OK Computer</title><primary-type>Album</primary-type><release-list count="1"><release id="0b6b4ba0-d36f-47bd-b4ea-6a5b91842d29"><title>OK Computer</title><status>Official</status></release></release-list><tag-list><tag count="9"><name>alternative rock</name></tag><tag count="2"><name>art rock</name></tag></tag-list><rating votes-count="41">4.5</rating></release-group></release-group-list></metadata>"#;
+        let res = ReleaseGroupSearchBuilder::parse_xml(xml).unwrap();
+
+        let ref rg = res[0].entity;
+        assert_eq!(
+            rg.tags,
+            vec![
+                full_entities::Tag {
+                    name: "alternative rock".to_string(),
+                    count: 9,
+                },
+                full_entities::Tag {
+                    name: "art rock".to_string(),
+                    count: 2,
+                },
+            ]
+        );
+        assert_eq!(
+            rg.rating,
+            Some(full_entities::Rating {
+                value: Some(4.5),
+                votes: 41,
+            })
+        );
+    }
+
+    #[test]
+    fn deserialize_multiple() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-group-list count="2" offset="0"><release-group id="739de9cd-7e81-4bb0-9fdb-0feb7ea709c7" type="Single" ext:score="100"><title>霊魂消滅</title><primary-type>Single</primary-type><release-list count="1"><release id="d3d2a860-0093-461d-8d95-b77939c2e944"><title>霊魂消滅</title><status>Official</status></release></release-list></release-group><release-group id="23c74936-ad4f-45bb-8b6b-527d4aeaaad6" type="Album" ext:score="62"><title>A.I Complex</title><primary-type>Album</primary-type><release-list count="0"></release-list></release-group></release-group-list></metadata>"#;
+        let res = ReleaseGroupSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 2);
+        assert_eq!(res[0].score, 100);
+        assert_eq!(res[0].entity.releases.len(), 1);
+        assert_eq!(res[1].score, 62);
+        assert_eq!(res[1].entity.title, "A.I Complex".to_string());
+        assert_eq!(res[1].entity.releases.len(), 0);
+        assert!(res[1].entity.tags.is_empty());
+        assert_eq!(res[1].entity.rating, None);
+    }
+
+    #[test]
+    fn deserialize_area() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><area-list count="2" offset="0"><area id="a1411661-be21-4290-8dc1-50f3d8e3ea67" type="City" type-id="6fd8f29a-3d0a-32fc-980d-ea697b69da78" ext:score="100"><name>Honolulu</name><sort-name>Honolulu</sort-name><life-span><ended>false</ended></life-span><relation-list target-type="area"><relation type="part of" type-id="de7cc874-8b1b-3a05-8272-f3834c968fb7"><target>6f5a1a8e-3c7f-3eb8-8a0b-64c3a8ae4f9f</target><direction>backward</direction><area id="6f5a1a8e-3c7f-3eb8-8a0b-64c3a8ae4f9f" type="Subdivision"><name>Hawaii</name><sort-name>Hawaii</sort-name><iso-3166-2-code-list><iso-3166-2-code>US-HI</iso-3166-2-code></iso-3166-2-code-list></area></relation></relation-list></area><area id="c23de9f9-e1b9-3d5c-8b53-0b2b2cb3e9c4" type="Country" ext:score="62"><name>Yugoslavia</name><sort-name>Yugoslavia</sort-name><iso-3166-1-code-list><iso-3166-1-code>YU</iso-3166-1-code></iso-3166-1-code-list><iso-3166-3-code-list><iso-3166-3-code>YUCS</iso-3166-3-code></iso-3166-3-code-list><life-span><begin>1918-12-01</begin><end>2003-02-04</end><ended>true</ended></life-span></area></area-list></metadata>"#;
+        let res = AreaSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 2);
+        let ref city = res[0].entity;
+        assert_eq!(city.name, "Honolulu".to_string());
+        assert_eq!(city.area_type, Some(full_entities::AreaType::City));
+        assert_eq!(city.iso_3166_2, Vec::<String>::new());
+        assert!(!city.ended);
+
+        let ref country = res[1].entity;
+        assert_eq!(res[1].score, 62);
+        assert_eq!(country.area_type, Some(full_entities::AreaType::Country));
+        assert_eq!(country.iso_3166_1, vec![full_entities::Country::from_code("YU").unwrap()]);
+        assert_eq!(country.iso_3166_3, vec!["YUCS".to_string()]);
+        assert_eq!(country.end_date, Some("2003-02-04".parse().unwrap()));
+        assert!(country.ended);
+    }
+
+    #[test]
+    fn deserialize_artist() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><artist-list count="1" offset="0"><artist id="90e7c2f9-273b-4d6c-a662-ab2d73ea4b8e" type="Group" type-id="e431f5f6-b5d2-343d-8b36-72607fffb74b" ext:score="100"><name>NECRONOMIDOL</name><sort-name>NECRONOMIDOL</sort-name><country>JP</country><area id="2db42837-c832-3c27-b4a3-08198f75693c" type="Country" type-id="06dd0ae4-8c74-30bb-b43d-95dcedf961de"><name>Japan</name><sort-name>Japan</sort-name><life-span><begin>1600</begin><ended>false</ended></life-span></area><disambiguation>Japanese idol group</disambiguation><life-span><begin>2014-03</begin><ended>false</ended></life-span><tag-list><tag count="1"><name>idol</name></tag><tag count="1"><name>black metal</name></tag></tag-list></artist></artist-list></metadata>"#;
+        let res = ArtistSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref artist = res[0].entity;
+        assert_eq!(artist.name, "NECRONOMIDOL".to_string());
+        assert_eq!(artist.artist_type, Some(full_entities::ArtistType::Group));
+        assert_eq!(artist.country, full_entities::Country::from_code("JP").ok());
+        assert_eq!(artist.area.as_ref().unwrap().name, "Japan".to_string());
+        assert_eq!(
+            artist.begin_date,
+            Some("2014-03".parse().unwrap())
+        );
+        assert_eq!(artist.end_date, None);
+        assert!(!artist.ended);
+        assert_eq!(
+            artist.disambiguation,
+            Some("Japanese idol group".to_string())
+        );
+        assert_eq!(
+            artist.tags,
+            vec!["idol".to_string(), "black metal".to_string()]
+        );
+    }
+
+    #[test]
+    fn deserialize_release() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><release-list count="1" offset="0"><release id="d3d2a860-0093-461d-8d95-b77939c2e944" ext:score="100"><title>霊魂消滅</title><status>Official</status><text-representation><language>jpn</language><script>Jpan</script></text-representation><artist-credit><name-credit><artist id="90e7c2f9-273b-4d6c-a662-ab2d73ea4b8e"><name>NECRONOMIDOL</name><sort-name>NECRONOMIDOL</sort-name></artist></name-credit></artist-credit><release-group id="739de9cd-7e81-4bb0-9fdb-0feb7ea709c7" type="Single"><title>霊魂消滅</title><primary-type>Single</primary-type></release-group><date>2016-04-20</date><country>JP</country></release></release-list></metadata>"#;
+        let res = ReleaseSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref release = res[0].entity;
+        assert_eq!(release.title, "霊魂消滅".to_string());
+        assert_eq!(release.status, Some(full_entities::ReleaseStatus::Official));
+        assert_eq!(release.language, full_entities::Language::from_639_3("jpn").ok());
+        assert_eq!(release.script, Some(full_entities::Script::from_code("Jpan").unwrap()));
+        assert_eq!(release.artists[0].name, "NECRONOMIDOL".to_string());
+        assert_eq!(release.country, full_entities::Country::from_code("JP").ok());
+        assert_eq!(
+            release.release_group.as_ref().unwrap().mbid,
+            "739de9cd-7e81-4bb0-9fdb-0feb7ea709c7".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_event() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><event-list count="1" offset="0"><event id="a24cb8e3-0a6f-4b3c-8d5e-3b6d6c3e1f5d" type="Festival" type-id="b6ded574-b592-3f0e-b56e-5b5f06aa0678" ext:score="100"><name>Fuji Rock Festival 2016</name><life-span><begin>2016-07-22</begin><end>2016-07-24</end><ended>true</ended></life-span><relation-list target-type="place"><relation type="held at" type-id="e2c6f697-07dc-38b1-be0b-83d740165532"><target>e1e6a0c6-9e2f-4e6c-8b0b-3a6e5f1c2d3e</target><direction>forward</direction><place id="e1e6a0c6-9e2f-4e6c-8b0b-3a6e5f1c2d3e"><name>Naeba Ski Resort</name></place></relation></relation-list></event></event-list></metadata>"#;
+        let res = EventSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref event = res[0].entity;
+        assert_eq!(event.name, "Fuji Rock Festival 2016".to_string());
+        assert_eq!(event.event_type, Some(full_entities::EventType::Festival));
+        assert_eq!(event.begin_date, Some("2016-07-22".parse().unwrap()));
+        assert_eq!(event.end_date, Some("2016-07-24".parse().unwrap()));
+        assert!(event.ended);
+    }
+
+    #[test]
+    fn deserialize_label() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><label-list count="1" offset="0"><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9" type="Original Production" type-id="7aaa37fe-2def-3476-b359-80245850062d" ext:score="100"><name>Parlophone</name><sort-name>Parlophone</sort-name><label-code>299</label-code><country>GB</country><area id="8a754a16-0027-3a29-b6d7-2b40ea0481ed" type="Country"><name>United Kingdom</name><sort-name>United Kingdom</sort-name><life-span><ended>false</ended></life-span></area><life-span><begin>1896</begin><ended>false</ended></life-span></label></label-list></metadata>"#;
+        let res = LabelSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref label = res[0].entity;
+        assert_eq!(label.name, "Parlophone".to_string());
+        assert_eq!(
+            label.label_type,
+            Some(full_entities::LabelType::ProductionOriginal)
+        );
+        assert_eq!(label.label_code, Some("299".to_string()));
+        assert_eq!(label.country, full_entities::Country::from_code("GB").ok());
+        assert_eq!(
+            label.area.as_ref().unwrap().name,
+            "United Kingdom".to_string()
+        );
+        assert_eq!(label.begin_date, Some("1896".parse().unwrap()));
+        assert!(!label.ended);
+    }
+
+    #[test]
+    fn deserialize_place() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><place-list count="1" offset="0"><place id="bea135c0-a32e-49be-85fd-9234c73fa0a8" type="Studio" type-id="05fa6a09-ff92-3d34-bdbf-5141d3c9b7c4" ext:score="100"><name>Abbey Road Studios</name><address>3 Abbey Road, St John's Wood, London, NW8 9AY, UK</address><coordinates><latitude>51.531921</latitude><longitude>-0.177453</longitude></coordinates><area id="f03d09b3-39dc-4083-afd6-159e3f0d462f" type="City"><name>London</name><sort-name>London</sort-name><life-span><ended>false</ended></life-span></area><life-span><begin>1931-11</begin><ended>false</ended></life-span></place></place-list></metadata>"#;
+        let res = PlaceSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref place = res[0].entity;
+        assert_eq!(place.name, "Abbey Road Studios".to_string());
+        assert_eq!(place.place_type, Some(full_entities::PlaceType::Studio));
+        assert_eq!(
+            place.coordinates,
+            Some(full_entities::Coordinates {
+                latitude: "51.531921".to_string(),
+                longitude: "-0.177453".to_string(),
+            })
+        );
+        assert_eq!(place.area.as_ref().unwrap().name, "London".to_string());
+        assert_eq!(place.begin_date, Some("1931-11".parse().unwrap()));
+    }
+
+    #[test]
+    fn deserialize_recording() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><recording-list count="1" offset="0"><recording id="fd6f4cd8-9cff-43da-8cd7-3351357b6f5a" ext:score="100"><title>Creep</title><length>238640</length><artist-credit><name-credit><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit><release-list><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Creep</title><status>Official</status><release-group id="b8e9e3a4-1b42-3c52-8b6f-4a1e0d4d9f9f" type="Single"><title>Creep</title><primary-type>Single</primary-type></release-group><medium-list><track-count>3</track-count><medium><position>1</position><track-list count="3" offset="0"><track id="ac898be7-2965-4d17-9ac8-48d45852d73c"><number>1</number><title>Creep</title><length>238640</length></track></track-list></medium></medium-list></release></release-list><isrc-list count="1"><isrc id="GBAYE9200070"/></isrc-list></recording></recording-list></metadata>"#;
+        let res = RecordingSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref recording = res[0].entity;
+        assert_eq!(recording.title, "Creep".to_string());
+        assert_eq!(
+            recording.length,
+            Some(full_entities::TrackLength::from_millis(238640))
+        );
+        assert_eq!(recording.artists[0].name, "Radiohead".to_string());
+        assert_eq!(recording.releases.len(), 1);
+        assert_eq!(
+            recording.releases[0].mbid,
+            "ed118c5f-d940-4b52-a37b-b1a205374abe".parse().unwrap()
+        );
+        assert_eq!(recording.isrcs, vec!["GBAYE9200070".to_string()]);
+    }
+
+    #[test]
+    fn deserialize_series() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0"><series-list count="1" offset="0"><series id="d977f7fd-96c9-4e3e-83b5-eb484a9e6581" type="Catalogue" type-id="49482ff0-fc9e-3b8c-a2d0-30e84d9df002" ext:score="100"><name>Bach-Werke-Verzeichnis</name><disambiguation>BWV</disambiguation></series></series-list></metadata>"#;
+        let res = SeriesSearchBuilder::parse_xml(xml).unwrap();
+
+        assert_eq!(res.len(), 1);
+        let ref series = res[0].entity;
+        assert_eq!(series.name, "Bach-Werke-Verzeichnis".to_string());
+        assert_eq!(
+            series.series_type,
+            Some(full_entities::SeriesType::Catalogue)
+        );
+        assert_eq!(series.disambiguation, Some("BWV".to_string()));
+    }
+
+    #[test]
+    fn release_filter() {
+        use std::str::FromStr;
+
+        let mut client = Client::with_transport(config().build().unwrap(), stub_transport(""));
+        let filter = ReleaseFilter::new()
+            .country("JP")
+            .status(full_entities::ReleaseStatus::Official)
+            .since(full_entities::PartialDate::from_str("2020").unwrap());
+        let url = client.search_release().filter(&filter).build_url().unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://musicbrainz.org/ws/2/release/?query=country%3AJP+AND+status%3Aofficial+AND+date%3A%5B2020+TO+*%5D"
+        );
+
+        let url = client
+            .search_release()
+            .filter(&filter)
+            .limit(Limit::MAX)
+            .offset(Offset::new(200))
+            .build_url()
+            .unwrap();
+        assert!(url.as_str().ends_with("&limit=100&offset=200"));
+    }
+
+    #[test]
+    fn escaped_url() {
+        use crate::search::fields::release_group::ReleaseGroupName;
+
+        let mut client = Client::with_transport(config().build().unwrap(), stub_transport(""));
+        let search = client
+            .search_release_group()
+            .add(ReleaseGroupName("AC/DC: Live & Loud".to_owned()));
+        let url = search.build_url().unwrap();
+
+        assert_eq!(
+            url.query_pairs().next().unwrap().1,
+            "releasegroup:\"AC\\/DC\\: Live & Loud\""
+        );
+        assert_eq!(url.query_pairs().next().unwrap().1, search.query_string().unwrap());
+    }
+
+    #[test]
+    fn invalid_field() {
+        use crate::search::fields::release_group::ReleaseGroupName;
+
+        let mut client = Client::with_transport(config().build().unwrap(), stub_transport(""));
+        let search = client
+            .search_release_group()
+            .add(ReleaseGroupName("  ".to_owned()));
+
+        assert!(search.query_string().unwrap_err().is_usage_error());
+        assert!(search.search().err().unwrap().is_usage_error());
     }
 }
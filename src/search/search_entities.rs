@@ -7,72 +7,383 @@
 use super::{Client, full_entities};
 use crate::Error;
 use self::full_entities::refs::*;
-use self::full_entities::{Mbid, ResourceOld};
+use self::full_entities::Mbid;
 use xpath_reader::reader::{FromXml, Reader};
 
 pub trait SearchEntity {
     /// The full entity that is refered by this search entity.
-    type FullEntity: ResourceOld + FromXml;
+    type FullEntity;
 
     /// Fetch the full entity from the API.2
     fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error>;
 }
 
-// It's the same entity.
-pub use self::full_entities::Area;
+/// An area as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Area {
+    pub mbid: Mbid,
+    pub name: String,
+    pub sort_name: String,
+    pub area_type: Option<full_entities::AreaType>,
+    /// ISO 3166-1 codes, assigned to countries.
+    pub iso_3166_1: Vec<full_entities::Country>,
+    /// ISO 3166-2 codes, assigned to subdivisions.
+    pub iso_3166_2: Vec<String>,
+    /// ISO 3166-3 codes, assigned to former countries.
+    pub iso_3166_3: Vec<String>,
+    pub begin_date: Option<full_entities::PartialDate>,
+    pub end_date: Option<full_entities::PartialDate>,
+    pub ended: bool,
+    pub disambiguation: Option<String>,
+}
 
-/*
 impl SearchEntity for Area {
-    type FullEntity = Area;
+    type FullEntity = full_entities::Area;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, ())
+    }
+}
+
+impl FromXml for Area {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // Related areas are nested, so only direct children are read.
+        let area_type: Option<String> = reader.read("./@type")?;
+        let ended: Option<String> = reader.read("./mb:life-span/mb:ended/text()")?;
+        let iso_3166_1: Vec<String> =
+            reader.read("./mb:iso-3166-1-code-list/mb:iso-3166-1-code/text()")?;
+
+        Ok(Area {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            sort_name: reader.read("./mb:sort-name/text()")?,
+            area_type: match area_type {
+                Some(t) => Some(
+                    t.parse()
+                        .map_err(|e: Error| xpath_reader::Error::custom_msg(e.to_string()))?,
+                ),
+                None => None,
+            },
+            iso_3166_1: iso_3166_1
+                .iter()
+                .map(|c| full_entities::Country::from_code(c))
+                .collect::<Result<_, _>>()
+                .map_err(|e| xpath_reader::Error::custom_msg(e.to_string()))?,
+            iso_3166_2: reader.read("./mb:iso-3166-2-code-list/mb:iso-3166-2-code/text()")?,
+            iso_3166_3: reader.read("./mb:iso-3166-3-code-list/mb:iso-3166-3-code/text()")?,
+            begin_date: reader.read("./mb:life-span/mb:begin/text()")?,
+            end_date: reader.read("./mb:life-span/mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+        })
+    }
+}
+
+/// An artist as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Artist {
+    pub mbid: Mbid,
+    pub name: String,
+    pub sort_name: String,
+    pub artist_type: Option<full_entities::ArtistType>,
+    pub gender: Option<full_entities::Gender>,
+    /// The country the artist is primarily identified with.
+    pub country: Option<full_entities::Country>,
+    pub area: Option<AreaRef>,
+    pub begin_date: Option<full_entities::PartialDate>,
+    pub end_date: Option<full_entities::PartialDate>,
+    pub ended: bool,
+    pub disambiguation: Option<String>,
+    /// Names of the tags of the artist.
+    pub tags: Vec<String>,
+}
+
+impl SearchEntity for Artist {
+    type FullEntity = full_entities::Artist;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::ArtistOptions::minimal())
+    }
+}
+
+impl FromXml for Artist {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested areas have names and life-spans too, so only direct
+        // children are read.
+        let ended: Option<String> = reader.read("./mb:life-span/mb:ended/text()")?;
+
+        Ok(Artist {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            sort_name: reader.read("./mb:sort-name/text()")?,
+            artist_type: reader.read("./@type")?,
+            gender: reader.read("./mb:gender/text()")?,
+            country: reader.read("./mb:country/text()")?,
+            area: reader.read("./mb:area")?,
+            begin_date: reader.read("./mb:life-span/mb:begin/text()")?,
+            end_date: reader.read("./mb:life-span/mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+            tags: reader.read("./mb:tag-list/mb:tag/mb:name/text()")?,
+        })
+    }
+}
+
+/// An event as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Event {
+    pub mbid: Mbid,
+    pub name: String,
+    pub event_type: Option<full_entities::EventType>,
+    pub begin_date: Option<full_entities::PartialDate>,
+    pub end_date: Option<full_entities::PartialDate>,
+    pub ended: bool,
+    pub disambiguation: Option<String>,
+}
+
+impl SearchEntity for Event {
+    type FullEntity = full_entities::Event;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::EventOptions::minimal())
+    }
+}
+
+impl FromXml for Event {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The related artists and places have names too, so only direct
+        // children are read.
+        let ended: Option<String> = reader.read("./mb:life-span/mb:ended/text()")?;
+
+        Ok(Event {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            event_type: reader.read("./@type")?,
+            begin_date: reader.read("./mb:life-span/mb:begin/text()")?,
+            end_date: reader.read("./mb:life-span/mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+        })
+    }
+}
+
+/// A label as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Label {
+    pub mbid: Mbid,
+    pub name: String,
+    pub sort_name: String,
+    pub label_type: Option<full_entities::LabelType>,
+    /// The label code, without the `LC` prefix.
+    pub label_code: Option<String>,
+    /// The country the label is primarily identified with.
+    pub country: Option<full_entities::Country>,
+    pub area: Option<AreaRef>,
+    pub begin_date: Option<full_entities::PartialDate>,
+    pub end_date: Option<full_entities::PartialDate>,
+    pub ended: bool,
+    pub disambiguation: Option<String>,
+}
+
+impl SearchEntity for Label {
+    type FullEntity = full_entities::Label;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::LabelOptions::minimal())
+    }
+}
+
+impl FromXml for Label {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested area has a name and life-span too, so only direct
+        // children are read.
+        let ended: Option<String> = reader.read("./mb:life-span/mb:ended/text()")?;
+
+        Ok(Label {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            sort_name: reader.read("./mb:sort-name/text()")?,
+            label_type: reader.read("./@type")?,
+            label_code: reader.read("./mb:label-code/text()")?,
+            country: reader.read("./mb:country/text()")?,
+            area: reader.read("./mb:area")?,
+            begin_date: reader.read("./mb:life-span/mb:begin/text()")?,
+            end_date: reader.read("./mb:life-span/mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+        })
+    }
+}
+
+/// A place as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Place {
+    pub mbid: Mbid,
+    pub name: String,
+    pub place_type: Option<full_entities::PlaceType>,
+    pub address: Option<String>,
+    pub coordinates: Option<full_entities::Coordinates>,
+    pub area: Option<AreaRef>,
+    pub begin_date: Option<full_entities::PartialDate>,
+    pub end_date: Option<full_entities::PartialDate>,
+    pub ended: bool,
+    pub disambiguation: Option<String>,
+}
+
+impl SearchEntity for Place {
+    type FullEntity = full_entities::Place;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::PlaceOptions::minimal())
+    }
+}
+
+impl FromXml for Place {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested area has a name and life-span too, so only direct
+        // children are read.
+        let ended: Option<String> = reader.read("./mb:life-span/mb:ended/text()")?;
 
-    fn fetch_full(&self, _: &mut Client) -> Result<Self::FullEntity, Error> {
-        Ok(self.to_owned())
+        Ok(Place {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            place_type: reader.read("./@type")?,
+            address: reader.read("./mb:address/text()")?,
+            coordinates: reader.read("./mb:coordinates")?,
+            area: reader.read("./mb:area")?,
+            begin_date: reader.read("./mb:life-span/mb:begin/text()")?,
+            end_date: reader.read("./mb:life-span/mb:end/text()")?,
+            ended: ended.map(|e| e == "true").unwrap_or(false),
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+        })
     }
 }
 
-pub use self::full_entities::ArtistResponse;
+/// A recording as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recording {
+    pub mbid: Mbid,
+    pub title: String,
+    pub length: Option<full_entities::TrackLength>,
+    pub artists: Vec<ArtistRef>,
+    /// The releases the recording appears on.
+    pub releases: Vec<ReleaseRef>,
+    pub isrcs: Vec<String>,
+    pub disambiguation: Option<String>,
+}
 
-impl SearchEntity for ArtistResponse {
-    type FullEntity = ArtistResponse;
+impl SearchEntity for Recording {
+    type FullEntity = full_entities::Recording;
 
-    fn fetch_full(&self, _: &mut Client) -> Result<Self::FullEntity, Error> {
-        Ok(self.to_owned())
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::RecordingOptions::minimal())
     }
 }
-*/
 
+impl FromXml for Recording {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested releases have titles and lengths too, so only direct
+        // children are read.
+        Ok(Recording {
+            mbid: reader.read("./@id")?,
+            title: reader.read("./mb:title/text()")?,
+            length: reader.read("./mb:length/text()")?,
+            artists: reader.read("./mb:artist-credit/mb:name-credit/mb:artist")?,
+            releases: reader.read("./mb:release-list/mb:release")?,
+            isrcs: reader.read("./mb:isrc-list/mb:isrc/@id")?,
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
+        })
+    }
+}
+
+/// A release as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Release {
     pub mbid: Mbid,
     pub title: String,
-    pub status: full_entities::ReleaseStatus,
-    pub language: Option<String>,
-    pub script: Option<String>,
+    pub status: Option<full_entities::ReleaseStatus>,
+    /// The language of the track titles.
+    pub language: Option<full_entities::Language>,
+    /// The script of the track titles.
+    pub script: Option<full_entities::Script>,
     pub artists: Vec<ArtistRef>,
-    // release group refs (TODO)
+    pub date: Option<full_entities::PartialDate>,
+    /// The country the release was issued in.
+    pub country: Option<full_entities::Country>,
+    pub barcode: Option<String>,
+    pub release_group: Option<ReleaseGroupRef>,
+    /// The labels and catalog numbers of the release.
+    pub labels: Vec<LabelInfo>,
 }
 
-/*
 impl SearchEntity for Release {
     type FullEntity = full_entities::Release;
 
     fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
-        client.get_by_mbid_old(&self.mbid)
+        client.get_by_mbid(&self.mbid, full_entities::ReleaseOptions::minimal())
+    }
+}
+
+impl FromXml for Release {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        // The nested release group has a title too, so only direct children
+        // are read.
+        Ok(Release {
+            mbid: reader.read("./@id")?,
+            title: reader.read("./mb:title/text()")?,
+            status: reader.read("./mb:status/text()")?,
+            language: reader.read("./mb:text-representation/mb:language/text()")?,
+            script: reader.read("./mb:text-representation/mb:script/text()")?,
+            artists: reader.read("./mb:artist-credit/mb:name-credit/mb:artist")?,
+            date: reader.read("./mb:date/text()")?,
+            country: reader.read("./mb:country/text()")?,
+            barcode: reader.read("./mb:barcode/text()")?,
+            release_group: reader.read("./mb:release-group")?,
+            labels: reader.read("./mb:label-info-list/mb:label-info")?,
+        })
+    }
+}
+
+/// A label and catalog number of a release as contained in search results.
+///
+/// Unlike `full_entities::LabelInfo` only the MBID and name of the label are
+/// contained.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LabelInfo {
+    /// MBID of the label, if it is in the database.
+    pub label: Option<Mbid>,
+    pub label_name: Option<String>,
+    pub catalog_number: Option<String>,
+}
+
+impl FromXml for LabelInfo {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        Ok(LabelInfo {
+            label: reader.read("./mb:label/@id")?,
+            label_name: reader.read("./mb:label/mb:name/text()")?,
+            catalog_number: reader.read("./mb:catalog-number/text()")?,
+        })
     }
 }
-*/
 
 pub struct ReleaseGroup {
     pub mbid: Mbid,
     pub title: String,
+    pub release_type: full_entities::ReleaseGroupType,
     pub artists: Vec<ArtistRef>,
     pub releases: Vec<ReleaseRef>,
+    /// Tags of the release group with their counts.
+    pub tags: Vec<full_entities::Tag>,
+    /// The average rating of the release group, if it is contained in the
+    /// results.
+    pub rating: Option<full_entities::Rating>,
 }
 
 impl SearchEntity for ReleaseGroup {
     type FullEntity = full_entities::ReleaseGroup;
 
     fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
-        client.get_by_mbid_old(&self.mbid)
+        client.get_by_mbid(&self.mbid, full_entities::ReleaseGroupOptions::minimal())
     }
 }
 
@@ -81,8 +392,47 @@ impl FromXml for ReleaseGroup {
         Ok(ReleaseGroup {
             mbid: reader.read(".//@id")?,
             title: reader.read(".//mb:title")?,
+            release_type: reader.read(".")?,
             artists: reader.read(".//mb:artist-credit/mb:name-credit/mb:artist")?,
             releases: reader.read(".//mb:release-list/mb:release")?,
+            tags: reader.read("./mb:tag-list/mb:tag")?,
+            rating: reader.read("./mb:rating")?,
+        })
+    }
+}
+
+/// A series as contained in search results.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Series {
+    pub mbid: Mbid,
+    pub name: String,
+    pub series_type: Option<full_entities::SeriesType>,
+    pub disambiguation: Option<String>,
+}
+
+impl SearchEntity for Series {
+    type FullEntity = full_entities::Series;
+
+    fn fetch_full(&self, client: &mut Client) -> Result<Self::FullEntity, Error> {
+        client.get_by_mbid(&self.mbid, full_entities::SeriesOptions::minimal())
+    }
+}
+
+impl FromXml for Series {
+    fn from_xml<'d>(reader: &'d Reader<'d>) -> Result<Self, xpath_reader::Error> {
+        let series_type: Option<String> = reader.read("./@type")?;
+
+        Ok(Series {
+            mbid: reader.read("./@id")?,
+            name: reader.read("./mb:name/text()")?,
+            series_type: match series_type {
+                Some(t) => Some(
+                    t.parse()
+                        .map_err(|e: Error| xpath_reader::Error::custom_msg(e.to_string()))?,
+                ),
+                None => None,
+            },
+            disambiguation: reader.read("./mb:disambiguation/text()")?,
         })
     }
 }
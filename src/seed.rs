@@ -0,0 +1,366 @@
+//! Links to the release editor of MusicBrainz, prefilled with local data.
+//!
+//! If a tagger can't find a release, it can help the user to add it instead:
+//! The fields of a `ReleaseSeed` are translated into the [seeding
+//! parameters](https://musicbrainz.org/doc/Development/Release_Editor_Seeding)
+//! of `https://musicbrainz.org/release/add`, so the user only has to review
+//! and complete the data before submitting it.
+//!
+//! No requests are performed, the user has to open the link in a browser.
+
+use crate::entities::{Mbid, PartialDate, ReleaseGroupPrimaryType, ReleaseStatus, TrackLength};
+use crate::identify::AlbumTags;
+
+use url::form_urlencoded;
+
+/// The page of the release editor accepting the seeding parameters.
+const RELEASE_ADD_URL: &str = "https://musicbrainz.org/release/add";
+
+/// A release to be added to MusicBrainz.
+///
+/// Fields which are not known can be left empty, they can be completed in
+/// the release editor.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReleaseSeed {
+    /// The title of the release.
+    pub title: String,
+
+    /// The artists credited on the release.
+    pub artists: Vec<SeedArtist>,
+
+    /// MBID of an existing release group the release should be added to.
+    pub release_group: Option<Mbid>,
+
+    /// Primary type of the release group, if a new one is created.
+    pub primary_type: Option<ReleaseGroupPrimaryType>,
+
+    pub status: Option<ReleaseStatus>,
+
+    /// The date the release was issued.
+    pub date: Option<PartialDate>,
+
+    /// ISO 3166-1 code of the country the release was issued in.
+    pub country: Option<String>,
+
+    pub barcode: Option<String>,
+
+    /// The labels and catalog numbers of the release.
+    pub labels: Vec<SeedLabel>,
+
+    /// The mediums of the release with their tracks.
+    pub mediums: Vec<SeedMedium>,
+
+    /// Note shown to other editors, e.g. where the data is from.
+    pub edit_note: Option<String>,
+}
+
+/// An artist credited on a release or track.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeedArtist {
+    /// The name the artist is credited as.
+    pub name: String,
+
+    /// MBID of the artist, if it is already known.
+    pub mbid: Option<Mbid>,
+
+    /// Text appended to the name, e.g. ` feat. ` to join it with the next
+    /// artist.
+    pub join_phrase: Option<String>,
+}
+
+/// A label and catalog number of a release.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeedLabel {
+    /// MBID of the label, if it is already known.
+    pub mbid: Option<Mbid>,
+
+    /// Name of the label, used to search the label in the editor if the MBID
+    /// isn't known.
+    pub name: Option<String>,
+
+    pub catalog_number: Option<String>,
+}
+
+/// A medium of a release, e.g. a CD.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeedMedium {
+    /// The format of the medium as named by MusicBrainz, e.g. `CD`.
+    pub format: Option<String>,
+
+    /// The title of the medium, if it has one of its own.
+    pub name: Option<String>,
+
+    /// The tracks of the medium, in order.
+    pub tracks: Vec<SeedTrack>,
+}
+
+/// A track of a medium.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeedTrack {
+    pub title: String,
+
+    /// The number of the track as printed on the release, e.g. `A1`. The
+    /// position is used if it is not set.
+    pub number: Option<String>,
+
+    pub length: Option<TrackLength>,
+
+    /// MBID of an existing recording to use for the track.
+    pub recording: Option<Mbid>,
+
+    /// The artists credited on the track, if they differ from the ones of
+    /// the release.
+    pub artists: Vec<SeedArtist>,
+}
+
+impl ReleaseSeed {
+    /// Create a seed with only the title of the release.
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        ReleaseSeed {
+            title: title.into(),
+            ..ReleaseSeed::default()
+        }
+    }
+
+    /// Create a seed from the tags of local files, e.g. an album `identify`
+    /// found no candidates for.
+    ///
+    /// All tracks are put on a single medium, ordered by their number.
+    pub fn from_tags(tags: &AlbumTags) -> Self {
+        let mut tracks: Vec<_> = tags.tracks.iter().collect();
+        // Tracks without a number are put last, keeping their order.
+        tracks.sort_by_key(|t| t.number.unwrap_or(u32::max_value()));
+
+        ReleaseSeed {
+            title: tags.album.clone(),
+            artists: vec![SeedArtist {
+                name: tags.artist.clone(),
+                ..SeedArtist::default()
+            }],
+            mediums: vec![SeedMedium {
+                tracks: tracks
+                    .into_iter()
+                    .map(|t| SeedTrack {
+                        title: t.title.clone(),
+                        ..SeedTrack::default()
+                    })
+                    .collect(),
+                ..SeedMedium::default()
+            }],
+            ..ReleaseSeed::default()
+        }
+    }
+
+    /// The seeding parameters, e.g. to submit them as a form with a `POST`
+    /// request to `https://musicbrainz.org/release/add`.
+    pub fn params(&self) -> Vec<(String, String)> {
+        let mut params = Params::default();
+
+        params.push("name", &self.title);
+        push_artists(&mut params, "artist_credit", &self.artists);
+        if let Some(ref mbid) = self.release_group {
+            params.push("release_group", mbid);
+        }
+        if let Some(primary_type) = self.primary_type {
+            params.push("type", primary_type.to_string().to_lowercase());
+        }
+        if let Some(ref status) = self.status {
+            params.push("status", status.to_string().to_lowercase());
+        }
+        if let Some(ref barcode) = self.barcode {
+            params.push("barcode", barcode);
+        }
+
+        if let Some(ref date) = self.date {
+            if let Some(year) = date.year() {
+                params.push("events.0.date.year", year);
+            }
+            if let Some(month) = date.month() {
+                params.push("events.0.date.month", month);
+            }
+            if let Some(day) = date.day() {
+                params.push("events.0.date.day", day);
+            }
+        }
+        if let Some(ref country) = self.country {
+            params.push("events.0.country", country);
+        }
+
+        for (i, label) in self.labels.iter().enumerate() {
+            if let Some(ref mbid) = label.mbid {
+                params.push(format!("labels.{}.mbid", i), mbid);
+            }
+            if let Some(ref name) = label.name {
+                params.push(format!("labels.{}.name", i), name);
+            }
+            if let Some(ref catalog_number) = label.catalog_number {
+                params.push(format!("labels.{}.catalog_number", i), catalog_number);
+            }
+        }
+
+        for (i, medium) in self.mediums.iter().enumerate() {
+            let prefix = format!("mediums.{}", i);
+            if let Some(ref format) = medium.format {
+                params.push(format!("{}.format", prefix), format);
+            }
+            if let Some(ref name) = medium.name {
+                params.push(format!("{}.name", prefix), name);
+            }
+            for (j, track) in medium.tracks.iter().enumerate() {
+                let prefix = format!("{}.track.{}", prefix, j);
+                params.push(format!("{}.name", prefix), &track.title);
+                if let Some(ref number) = track.number {
+                    params.push(format!("{}.number", prefix), number);
+                }
+                if let Some(ref length) = track.length {
+                    params.push(format!("{}.length", prefix), length.as_millis());
+                }
+                if let Some(ref recording) = track.recording {
+                    params.push(format!("{}.recording", prefix), recording);
+                }
+                push_artists(
+                    &mut params,
+                    &format!("{}.artist_credit", prefix),
+                    &track.artists,
+                );
+            }
+        }
+
+        if let Some(ref edit_note) = self.edit_note {
+            params.push("edit_note", edit_note);
+        }
+
+        params.0
+    }
+
+    /// Link to the release editor prefilled with the seed.
+    ///
+    /// The parameters are passed in the query of the link, so a release with
+    /// a lot of tracks might exceed the maximal length of URLs some browsers
+    /// support. Submit the `params` with a `POST` request in that case.
+    pub fn url(&self) -> String {
+        let query = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.params())
+            .finish();
+        format!("{}?{}", RELEASE_ADD_URL, query)
+    }
+}
+
+/// Seeding parameters in the order they were added.
+#[derive(Default)]
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn push<K: Into<String>, V: ToString>(&mut self, key: K, value: V) {
+        self.0.push((key.into(), value.to_string()));
+    }
+}
+
+/// Add an artist credit, `prefix` being e.g. `artist_credit`.
+fn push_artists(params: &mut Params, prefix: &str, artists: &[SeedArtist]) {
+    for (i, artist) in artists.iter().enumerate() {
+        params.push(format!("{}.names.{}.name", prefix, i), &artist.name);
+        if let Some(ref mbid) = artist.mbid {
+            params.push(format!("{}.names.{}.mbid", prefix, i), mbid);
+        }
+        if let Some(ref join_phrase) = artist.join_phrase {
+            params.push(format!("{}.names.{}.join_phrase", prefix, i), join_phrase);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identify::TrackTags;
+
+    fn param<'p>(params: &'p [(String, String)], key: &str) -> Option<&'p str> {
+        params
+            .iter()
+            .find(|&&(ref k, _)| k == key)
+            .map(|&(_, ref v)| v.as_str())
+    }
+
+    #[test]
+    fn params() {
+        let seed = ReleaseSeed {
+            artists: vec![SeedArtist {
+                name: "Radiohead".to_string(),
+                mbid: Some("a74b1b7f-71a5-4011-9441-d0b5e4122711".parse().unwrap()),
+                join_phrase: None,
+            }],
+            primary_type: Some(ReleaseGroupPrimaryType::Album),
+            status: Some(ReleaseStatus::Official),
+            date: Some("1997-05".parse().unwrap()),
+            country: Some("GB".to_string()),
+            labels: vec![SeedLabel {
+                name: Some("Parlophone".to_string()),
+                catalog_number: Some("NODATA 02".to_string()),
+                ..SeedLabel::default()
+            }],
+            mediums: vec![SeedMedium {
+                format: Some("CD".to_string()),
+                tracks: vec![SeedTrack {
+                    title: "Airbag".to_string(),
+                    length: Some(TrackLength::from_millis(284_000)),
+                    ..SeedTrack::default()
+                }],
+                ..SeedMedium::default()
+            }],
+            ..ReleaseSeed::new("OK Computer")
+        };
+        let params = seed.params();
+
+        assert_eq!(param(&params, "name"), Some("OK Computer"));
+        assert_eq!(
+            param(&params, "artist_credit.names.0.mbid"),
+            Some("a74b1b7f-71a5-4011-9441-d0b5e4122711")
+        );
+        assert_eq!(param(&params, "type"), Some("album"));
+        assert_eq!(param(&params, "status"), Some("official"));
+        assert_eq!(param(&params, "events.0.date.year"), Some("1997"));
+        assert_eq!(param(&params, "events.0.date.month"), Some("5"));
+        assert_eq!(param(&params, "events.0.date.day"), None);
+        assert_eq!(param(&params, "events.0.country"), Some("GB"));
+        assert_eq!(param(&params, "labels.0.catalog_number"), Some("NODATA 02"));
+        assert_eq!(param(&params, "mediums.0.format"), Some("CD"));
+        assert_eq!(param(&params, "mediums.0.track.0.name"), Some("Airbag"));
+        assert_eq!(param(&params, "mediums.0.track.0.length"), Some("284000"));
+
+        assert!(seed
+            .url()
+            .starts_with("https://musicbrainz.org/release/add?name=OK+Computer&"));
+    }
+
+    #[test]
+    fn from_tags() {
+        let tags = AlbumTags {
+            artist: "Radiohead".to_string(),
+            album: "OK Computer".to_string(),
+            tracks: vec![
+                TrackTags {
+                    number: None,
+                    title: "Hidden Track".to_string(),
+                },
+                TrackTags {
+                    number: Some(2),
+                    title: "Paranoid Android".to_string(),
+                },
+                TrackTags {
+                    number: Some(1),
+                    title: "Airbag".to_string(),
+                },
+            ],
+        };
+        let seed = ReleaseSeed::from_tags(&tags);
+
+        assert_eq!(seed.title, "OK Computer".to_string());
+        assert_eq!(seed.artists[0].name, "Radiohead".to_string());
+        let titles: Vec<&str> = seed.mediums[0]
+            .tracks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Airbag", "Paranoid Android", "Hidden Track"]);
+    }
+}
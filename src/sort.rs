@@ -0,0 +1,246 @@
+//! Ordering entities by their sort names.
+//!
+//! MusicBrainz provides a sort name for artists, labels and areas, e.g.
+//! `Beatles, The` for `The Beatles`, but comparing them as plain strings puts
+//! `beck` after `Zappa, Frank` and `Ólafur Arnalds` after everything else.
+//! The helpers here compare sort names ignoring case and the most common
+//! diacritics of latin scripts, and only fall back to the exact strings to
+//! order names which are equal otherwise. This is no full implementation of
+//! the Unicode collation algorithm, other scripts are ordered by code point.
+
+use crate::entities::{Area, AreaRef, Artist, ArtistRef, Label, LabelRef};
+
+use std::cmp::Ordering;
+
+/// Entities which have a sort name.
+pub trait SortName {
+    /// The name used to sort the entity, e.g. `Beatles, The`.
+    fn sort_name(&self) -> &str;
+}
+
+impl SortName for Artist {
+    fn sort_name(&self) -> &str {
+        Artist::sort_name(self).as_str()
+    }
+}
+
+impl SortName for Label {
+    fn sort_name(&self) -> &str {
+        Label::sort_name(self).as_str()
+    }
+}
+
+impl SortName for Area {
+    fn sort_name(&self) -> &str {
+        Area::sort_name(self).as_str()
+    }
+}
+
+impl SortName for ArtistRef {
+    fn sort_name(&self) -> &str {
+        self.sort_name.as_str()
+    }
+}
+
+impl SortName for LabelRef {
+    fn sort_name(&self) -> &str {
+        self.sort_name.as_str()
+    }
+}
+
+impl SortName for AreaRef {
+    fn sort_name(&self) -> &str {
+        self.sort_name.as_str()
+    }
+}
+
+impl<T: SortName + ?Sized> SortName for &T {
+    fn sort_name(&self) -> &str {
+        (**self).sort_name()
+    }
+}
+
+/// Compare two names like a phone book would, ignoring case and diacritics
+/// of latin letters.
+///
+/// Names which only differ in those are ordered by their exact strings, so
+/// the order is always total.
+pub fn compare_names(a: &str, b: &str) -> Ordering {
+    fold(a).cmp(fold(b)).then_with(|| a.cmp(b))
+}
+
+/// Compare two entities by their sort names, see `compare_names`.
+///
+/// Can be passed to `sort_by` directly, e.g.
+/// `artists.sort_by(musicbrainz::sort::by_sort_name)`.
+pub fn by_sort_name<T: SortName>(a: &T, b: &T) -> Ordering {
+    compare_names(a.sort_name(), b.sort_name())
+}
+
+/// Sort entities by their sort names, see `compare_names`.
+pub fn sort_by_sort_name<T: SortName>(entities: &mut [T]) {
+    entities.sort_by(by_sort_name);
+}
+
+/// Wrapper ordering an entity by its sort name, so it can be used as the key
+/// of a `BTreeMap` or in a `BinaryHeap`.
+///
+/// Two wrapped entities are only equal if their sort names are exactly equal.
+#[derive(Clone, Copy, Debug)]
+pub struct BySortName<T>(pub T);
+
+impl<T: SortName> PartialEq for BySortName<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: SortName> Eq for BySortName<T> {}
+
+impl<T: SortName> PartialOrd for BySortName<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: SortName> Ord for BySortName<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        by_sort_name(&self.0, &other.0)
+    }
+}
+
+/// Articles which are ignored by `strip_leading_article`, followed by the
+/// character separating them from the name.
+const ARTICLES: &[&str] = &[
+    "the ", "a ", "an ", "der ", "die ", "das ", "le ", "la ", "les ", "l'", "el ", "los ", "las ",
+    "il ", "lo ", "gli ", "het ",
+];
+
+/// Remove a leading article from a name, e.g. `The Beatles` becomes
+/// `Beatles`.
+///
+/// This is useful to sort names which have no sort name, like the titles of
+/// releases. English, German, French, Spanish, Italian and Dutch articles are
+/// recognized regardless of their case. A name consisting only of an article
+/// is returned unchanged.
+pub fn strip_leading_article(name: &str) -> &str {
+    for article in ARTICLES {
+        let len = article.len();
+        // All articles are ASCII, so `len` is a char boundary if the prefix
+        // matches.
+        if name.len() > len
+            && name.is_char_boundary(len)
+            && name[..len].eq_ignore_ascii_case(article)
+        {
+            let rest = name[len..].trim_start();
+            if !rest.is_empty() {
+                return rest;
+            }
+        }
+    }
+    name
+}
+
+/// Lowercase a name and replace latin letters with diacritics by their base
+/// letters.
+fn fold(name: &str) -> impl Iterator<Item = char> + '_ {
+    name.chars().flat_map(char::to_lowercase).flat_map(|c| {
+        let folded: &'static str = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+            'æ' => "ae",
+            'ç' | 'ć' | 'č' => "c",
+            'ď' | 'đ' | 'ð' => "d",
+            'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+            'ğ' => "g",
+            'ì' | 'í' | 'î' | 'ï' | 'ī' | 'į' | 'ı' => "i",
+            'ł' | 'ľ' | 'ĺ' => "l",
+            'ñ' | 'ń' | 'ň' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ő' => "o",
+            'œ' => "oe",
+            'ŕ' | 'ř' => "r",
+            'ś' | 'š' | 'ş' => "s",
+            'ß' => "ss",
+            'ť' | 'ţ' => "t",
+            'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' | 'ű' => "u",
+            'ý' | 'ÿ' => "y",
+            'ź' | 'ż' | 'ž' => "z",
+            'þ' => "th",
+            _ => "",
+        };
+        if folded.is_empty() {
+            Folded::Char(Some(c))
+        } else {
+            Folded::Str(folded.chars())
+        }
+    })
+}
+
+/// The result of folding a single character.
+enum Folded {
+    Char(Option<char>),
+    Str(::std::str::Chars<'static>),
+}
+
+impl Iterator for Folded {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match *self {
+            Folded::Char(ref mut c) => c.take(),
+            Folded::Str(ref mut chars) => chars.next(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artist(name: &str, sort_name: &str) -> ArtistRef {
+        ArtistRef {
+            mbid: "e57b3990-eb36-476e-beac-583e0bbe6f87".parse().unwrap(),
+            name: name.to_string(),
+            sort_name: sort_name.to_string(),
+        }
+    }
+
+    #[test]
+    fn order() {
+        let mut artists = vec![
+            artist("Frank Zappa", "Zappa, Frank"),
+            artist("Ólafur Arnalds", "Arnalds, Ólafur"),
+            artist("beck", "beck"),
+            artist("The Beatles", "Beatles, The"),
+            artist("Æther", "Æther"),
+        ];
+        sort_by_sort_name(&mut artists);
+        let names: Vec<&str> = artists.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Æther",
+                "Ólafur Arnalds",
+                "The Beatles",
+                "beck",
+                "Frank Zappa"
+            ]
+        );
+
+        assert_eq!(compare_names("Ärzte", "Arzte"), "Ärzte".cmp("Arzte"));
+        assert_eq!(compare_names("straße", "STRASSE"), Ordering::Greater);
+        assert!(BySortName(&artists[0]) < BySortName(&artists[1]));
+        assert!(BySortName(artist("A", "x")) == BySortName(artist("B", "x")));
+    }
+
+    #[test]
+    fn articles() {
+        assert_eq!(strip_leading_article("The Beatles"), "Beatles");
+        assert_eq!(strip_leading_article("the  beatles"), "beatles");
+        assert_eq!(strip_leading_article("L'Impératrice"), "Impératrice");
+        assert_eq!(strip_leading_article("Die Ärzte"), "Ärzte");
+        assert_eq!(strip_leading_article("The"), "The");
+        assert_eq!(strip_leading_article("The "), "The ");
+        assert_eq!(strip_leading_article("Theatre"), "Theatre");
+        assert_eq!(strip_leading_article("Ångström"), "Ångström");
+    }
+}
@@ -0,0 +1,296 @@
+//! Alternative parser backend based on `quick-xml`.
+//!
+//! Instead of building a full DOM and evaluating XPath expressions against
+//! it, the response is read in a single pass into a compact tree of elements
+//! which entities then navigate directly by element name. This is
+//! considerably faster for large responses, e.g. releases with many mediums
+//! and tracks.
+//!
+//! Only some entities support this backend, all others are still parsed with
+//! `xpath_reader`. The navigation helpers below mirror the semantics of the
+//! XPath expressions used by the entities so both backends produce identical
+//! results.
+//!
+//! Lists of entities can also be read incrementally with `parse_elements`,
+//! which only keeps one entity of the list in memory at a time instead of
+//! the whole tree.
+
+use crate::error::{Error, ErrorKind};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use std::fmt::Display;
+use std::io::BufRead;
+use std::str::FromStr;
+
+/// An element of the parsed document.
+#[derive(Debug, Default)]
+pub(crate) struct Node {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+    pub children: Vec<Node>,
+}
+
+fn xml_error<E: Display>(e: E) -> Error {
+    Error::parse_error(format!("quick-xml error: {}", e))
+}
+
+fn start_node<R: BufRead>(reader: &Reader<R>, start: &BytesStart) -> Result<Node, Error> {
+    let mut node = Node {
+        name: String::from_utf8_lossy(start.name()).into_owned(),
+        ..Node::default()
+    };
+    for attr in start.attributes() {
+        let attr = attr.map_err(xml_error)?;
+        let value = attr.unescape_and_decode_value(reader).map_err(xml_error)?;
+        node.attributes
+            .push((String::from_utf8_lossy(attr.key).into_owned(), value));
+    }
+    Ok(node)
+}
+
+/// Reads a document into a tree, returning its root element.
+pub(crate) fn parse_document(xml: &str) -> Result<Node, Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    // The elements which are currently open, innermost last.
+    let mut open: Vec<Node> = Vec::new();
+
+    loop {
+        let finished = match reader.read_event(&mut buf).map_err(xml_error)? {
+            Event::Start(ref e) => {
+                open.push(start_node(&reader, e)?);
+                None
+            }
+            Event::Empty(ref e) => Some(start_node(&reader, e)?),
+            Event::End(_) => Some(
+                open.pop()
+                    .ok_or_else(|| Error::parse_error("Unbalanced end tag."))?,
+            ),
+            Event::Text(ref e) => {
+                if let Some(node) = open.last_mut() {
+                    node.text
+                        .push_str(&e.unescape_and_decode(&reader).map_err(xml_error)?);
+                }
+                None
+            }
+            Event::Eof => return Err(Error::parse_error("Unexpected end of document.")),
+            _ => None,
+        };
+
+        if let Some(node) = finished {
+            match open.last_mut() {
+                Some(parent) => parent.children.push(node),
+                None => return Ok(node),
+            }
+        }
+        buf.clear();
+    }
+}
+
+/// Reads a document incrementally, passing every element named `name` to
+/// `element` as soon as it is complete.
+///
+/// Only the matching element which is currently read is kept in memory, the
+/// document without the matching elements is returned after all of them were
+/// passed on. Matching elements nested in other matching elements are part
+/// of the outer element.
+pub(crate) fn parse_elements<R, F>(source: R, name: &str, mut element: F) -> Result<Node, Error>
+where
+    R: BufRead,
+    F: FnMut(Node) -> Result<(), Error>,
+{
+    let mut reader = Reader::from_reader(source);
+    let mut buf = Vec::new();
+    // The elements which are currently open, innermost last.
+    let mut open: Vec<Node> = Vec::new();
+    // Index in `open` of the matching element which is currently read.
+    let mut matching: Option<usize> = None;
+
+    loop {
+        let finished = match reader.read_event(&mut buf).map_err(xml_error)? {
+            Event::Start(ref e) => {
+                let node = start_node(&reader, e)?;
+                if matching.is_none() && node.name == name {
+                    matching = Some(open.len());
+                }
+                open.push(node);
+                None
+            }
+            Event::Empty(ref e) => Some(start_node(&reader, e)?),
+            Event::End(_) => Some(
+                open.pop()
+                    .ok_or_else(|| Error::parse_error("Unbalanced end tag."))?,
+            ),
+            Event::Text(ref e) => {
+                if let Some(node) = open.last_mut() {
+                    node.text
+                        .push_str(&e.unescape_and_decode(&reader).map_err(xml_error)?);
+                }
+                None
+            }
+            Event::Eof => return Err(Error::parse_error("Unexpected end of document.")),
+            _ => None,
+        };
+
+        if let Some(node) = finished {
+            if matching == Some(open.len()) || (matching.is_none() && node.name == name) {
+                matching = None;
+                element(node)?;
+            } else {
+                match open.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+        }
+        buf.clear();
+    }
+}
+
+/// Returns an error if the document is an error message of the API, like
+/// `check_response_error` does for the XPath backend.
+pub(crate) fn check_response_error(root: &Node) -> Result<(), Error> {
+    if root.name != "error" {
+        return Ok(());
+    }
+    let texts: Vec<&str> = root.children("text").map(|t| t.text.as_str()).collect();
+    Err(Error::new(texts.join("\n"), ErrorKind::ServerError))
+}
+
+impl Node {
+    /// First child element with the given name, equivalent to `./mb:name`.
+    pub fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// All child elements with the given name.
+    pub fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    /// Follows a path of child element names.
+    pub fn path(&self, path: &[&str]) -> Option<&Node> {
+        let mut node = self;
+        for name in path {
+            node = node.child(name)?;
+        }
+        Some(node)
+    }
+
+    /// First descendant element with the given name in document order,
+    /// equivalent to `.//mb:name`.
+    pub fn descendant(&self, name: &str) -> Option<&Node> {
+        for child in self.children.iter() {
+            if child.name == name {
+                return Some(child);
+            }
+            if let Some(node) = child.descendant(name) {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    /// The value of an attribute of this element, equivalent to `./@name`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|&&(ref key, _)| key == name)
+            .map(|&(_, ref value)| value.as_str())
+    }
+
+    /// The first attribute with the given name on this element or any of its
+    /// descendants, equivalent to `.//@name`.
+    pub fn descendant_attribute(&self, name: &str) -> Option<&str> {
+        self.attribute(name).or_else(|| {
+            self.children
+                .iter()
+                .filter_map(|c| c.descendant_attribute(name))
+                .next()
+        })
+    }
+
+    /// The text of the first descendant with the given name.
+    pub fn descendant_text(&self, name: &str) -> Option<&str> {
+        self.descendant(name).map(|n| n.text.as_str())
+    }
+
+    /// The text of the element at the given path.
+    pub fn text_at(&self, path: &[&str]) -> Option<&str> {
+        self.path(path).map(|n| n.text.as_str())
+    }
+}
+
+/// Parses an optional value.
+pub(crate) fn parse<T>(value: Option<&str>) -> Result<Option<T>, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match value {
+        Some(s) => s
+            .parse()
+            .map(Some)
+            .map_err(|e| Error::parse_error(format!("Invalid value '{}': {}", s, e))),
+        None => Ok(None),
+    }
+}
+
+/// Parses a value which has to be present.
+pub(crate) fn require<T>(value: Option<&str>, what: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parse(value)?.ok_or_else(|| Error::parse_error(format!("Missing {}.", what)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree() {
+        let root = parse_document(
+            r#"<?xml version="1.0"?><a x="1"><b>one &amp; two</b><c><b y="2"/></c></a>"#,
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "a");
+        assert_eq!(root.attribute("x"), Some("1"));
+        assert_eq!(root.text_at(&["b"]), Some("one & two"));
+        assert_eq!(root.path(&["c", "b"]).unwrap().attribute("y"), Some("2"));
+        assert_eq!(root.children("b").count(), 1);
+        assert_eq!(root.descendant_attribute("y"), Some("2"));
+    }
+
+    #[test]
+    fn elements() {
+        let xml = r#"<list count="3"><e id="1"><e id="2"/></e><other/><e id="3"/></list>"#;
+        let mut ids = Vec::new();
+        let root = parse_elements(xml.as_bytes(), "e", |e| {
+            ids.push(e.attribute("id").unwrap().to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(ids, vec!["1", "3"]);
+        assert_eq!(root.attribute("count"), Some("3"));
+        assert_eq!(root.children.len(), 1);
+        assert!(root.child("other").is_some());
+
+        let err = parse_elements(xml.as_bytes(), "e", |_| Err(Error::parse_error("stop")));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn server_error() {
+        let root = parse_document("<error><text>first</text><text>second</text></error>").unwrap();
+
+        let err = check_response_error(&root).unwrap_err();
+        assert!(format!("{}", err).starts_with("[server error]: first\nsecond"));
+    }
+}
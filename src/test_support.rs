@@ -0,0 +1,127 @@
+//! Helpers for benchmarks and the test suites of applications using this
+//! crate.
+//!
+//! Only available with the `test-support` feature. The fixtures generated
+//! here have the same shape as the responses of the MusicBrainz API, but are
+//! built from templates so documents of arbitrary size can be produced
+//! without having to record them first.
+
+use crate::client::{Client, ClientConfig, HttpRequest, HttpResponse, HttpTransport};
+use crate::entities::Resource;
+use crate::error::{Error, ErrorKind};
+use crate::search::{search_entities, ReleaseGroupSearchBuilder, SearchResult};
+
+use std::fmt::Write;
+use url::Url;
+
+/// Parse a lookup response of `Res`, as `Client::get_by_mbid` would.
+pub fn parse_entity<Res: Resource>(xml: &str, options: Res::Options) -> Result<Res, Error> {
+    crate::client::parse_response(xml, options, false)
+}
+
+/// Parse a page of release group search results.
+pub fn parse_release_group_search(xml: &str) -> SearchResult<search_entities::ReleaseGroup> {
+    ReleaseGroupSearchBuilder::parse_xml(xml)
+}
+
+/// Returns the url a search would be performed with.
+pub fn release_group_search_url(builder: &ReleaseGroupSearchBuilder) -> Result<Url, Error> {
+    builder.build_url()
+}
+
+/// Escape a value for use in a search query.
+pub fn escape_query_value(text: &str) -> String {
+    crate::search::escape::escape_full(text)
+}
+
+/// Transport failing every request, so code under test can't reach the
+/// network by accident.
+struct OfflineTransport;
+
+impl HttpTransport for OfflineTransport {
+    fn get(&mut self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+        Err(Error::new(
+            format!("Offline client can't request {}.", request.url),
+            ErrorKind::Communication,
+        ))
+    }
+}
+
+/// Create a client which fails every request.
+///
+/// This is useful to construct search builders and similar without
+/// performing any requests.
+pub fn offline_client() -> Client {
+    Client::with_transport(
+        ClientConfig::builder()
+            .user_agent("MusicBrainz-Rust/Testing")
+            .max_retries(1)
+            .build()
+            .expect("valid configuration"),
+        OfflineTransport,
+    )
+}
+
+/// Generate a release lookup response including artists, labels, recordings
+/// and the release group with the specified number of mediums and tracks per
+/// medium.
+pub fn release_xml(mediums: usize, tracks_per_medium: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><release id="ed118c5f-d940-4b52-a37b-b1a205374abe"><title>Synthetic Release</title><status id="4e304316-386d-3409-af2e-78857eec5cfe">Official</status><quality>normal</quality><packaging id="ec27701a-4a22-37f4-bfac-6616e0f9750a">Jewel Case</packaging><text-representation><language>eng</language><script>Latn</script></text-representation><artist-credit><name-credit><artist id="a74b1b7f-71a5-4011-9441-d0b5e4122711"><name>Radiohead</name><sort-name>Radiohead</sort-name></artist></name-credit></artist-credit><date>1992-09-21</date><country>GB</country><barcode>724388023429</barcode><label-info-list count="1"><label-info><catalog-number>CDR 6078</catalog-number><label id="df7d1c7f-ef95-425f-8eef-445b3d7bcbd9"><name>Parlophone</name><sort-name>Parlophone</sort-name><label-code>299</label-code></label></label-info></label-info-list>"#,
+    );
+
+    write!(xml, r#"<medium-list count="{}">"#, mediums).unwrap();
+    for medium in 1..=mediums {
+        write!(
+            xml,
+            r#"<medium><position>{}</position><format>CD</format><track-list offset="0" count="{}">"#,
+            medium, tracks_per_medium
+        )
+        .unwrap();
+        for track in 1..=tracks_per_medium {
+            write!(
+                xml,
+                r#"<track id="00000000-0000-4000-8000-{:06}{:06}"><position>{}</position><number>{}</number><length>{}</length><recording id="00000000-0000-4000-9000-{:06}{:06}"><title>Track {} &amp; more</title><length>{}</length></recording></track>"#,
+                medium,
+                track,
+                track,
+                track,
+                180_000 + track,
+                medium,
+                track,
+                track,
+                180_000 + track
+            )
+            .unwrap();
+        }
+        xml.push_str("</track-list></medium>");
+    }
+    xml.push_str("</medium-list>");
+
+    xml.push_str(
+        r#"<release-group type="Album" id="b8e9e3a4-1b42-3c52-8b6f-4a1e0d4d9f9f"><title>Synthetic Release</title><first-release-date>1992-09-21</first-release-date><primary-type>Album</primary-type></release-group></release></metadata>"#,
+    );
+    xml
+}
+
+/// Generate a page of release group search results with `count` entries.
+pub fn release_group_search_xml(count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><metadata created="2017-05-06T09:45:01.432Z" xmlns="http://musicbrainz.org/ns/mmd-2.0#" xmlns:ext="http://musicbrainz.org/ns/ext#-2.0">"#,
+    );
+    write!(xml, r#"<release-group-list count="{}" offset="0">"#, count).unwrap();
+    for i in 0..count {
+        write!(
+            xml,
+            r#"<release-group id="00000000-0000-4000-8000-{:012}" type="Single" ext:score="{}"><title>Result {}</title><primary-type>Single</primary-type><artist-credit><name-credit><artist id="90e7c2f9-273b-4d6c-a662-ab2d73ea4b8e"><name>NECRONOMIDOL</name><sort-name>NECRONOMIDOL</sort-name></artist></name-credit></artist-credit><release-list count="1"><release id="00000000-0000-4000-9000-{:012}"><title>Result {}</title><status>Official</status></release></release-list></release-group>"#,
+            i,
+            100 - (i * 100 / count.max(1)),
+            i,
+            i,
+            i
+        )
+        .unwrap();
+    }
+    xml.push_str("</release-group-list></metadata>");
+    xml
+}
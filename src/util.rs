@@ -8,37 +8,92 @@ pub fn musicbrainz_context<'d>() -> Context<'d> {
 
 #[cfg(test)]
 pub mod test_utils {
-    use crate::client::{Client, ClientConfig, ClientWaits};
-    use crate::entities::{Mbid, ResourceOld, Resource};
+    use crate::client::{Client, ClientConfig, ClientConfigBuilder, HttpRequest, HttpResponse,
+                        HttpTransport};
+    use crate::entities::{Mbid, Resource};
     use crate::error::Error;
-    use reqwest_mock::GenericClient as HttpClient;
-    use xpath_reader::reader::FromXml;
+    use reqwest_mock::GenericClient;
+
+    /// Transport answering requests with the responses returned by a
+    /// closure, so the tests don't need a transport type each.
+    pub struct FnTransport<F>(pub F);
+
+    impl<F> HttpTransport for FnTransport<F>
+    where
+        F: FnMut(&HttpRequest) -> Result<HttpResponse, Error>,
+    {
+        fn get(&mut self, request: &HttpRequest) -> Result<HttpResponse, Error> {
+            (self.0)(request)
+        }
+    }
+
+    /// Transport answering every request with the same body.
+    pub fn stub_transport(body: &'static str) -> impl HttpTransport + Send {
+        FnTransport(move |_: &HttpRequest| Ok(response(200, body)))
+    }
+
+    /// A response without headers.
+    pub fn response<B: Into<Vec<u8>>>(status: u16, body: B) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// The MBID a lookup was requested for, i.e. the last segment of the
+    /// path.
+    pub fn requested_mbid(request: &HttpRequest) -> String {
+        request.url.path().rsplit('/').next().unwrap().to_string()
+    }
+
+    /// A lookup response of an area without any includes.
+    pub fn area_xml(mbid: &str, name: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><area id="{}"><name>{}</name><sort-name>Area</sort-name></area></metadata>"#,
+            mbid, name
+        )
+    }
+
+    /// The configuration used by the tests, with the defaults of the builder.
+    pub fn config() -> ClientConfigBuilder {
+        ClientConfig::builder().user_agent("MusicBrainz-Rust/Testing")
+    }
 
     pub fn fetch_entity<Res, Opt>(mbid: &Mbid, options: Opt) -> Result<Res, Error>
     where
         Res: Resource<Options = Opt>,
     {
-        let mut client = Client::with_http_client(
-            ClientConfig {
-                user_agent: "MusicBrainz-Rust/Testing".to_string(),
-                max_retries: 5,
-                waits: ClientWaits::default(),
-            },
-            HttpClient::replay_file(format!("replay/test_entities/{}/{}.json", Res::NAME, mbid)),
+        let mut client = Client::with_transport(
+            config().build().unwrap(),
+            GenericClient::replay_file(format!("replay/test_entities/{}/{}.json", Res::NAME, mbid)),
         );
         client.get_by_mbid(mbid, options)
     }
 
-    pub fn fetch_entity_old<E: ResourceOld + FromXml>(mbid: &Mbid) -> Result<E, Error> {
-        let mut client = Client::with_http_client(
-            ClientConfig {
-                user_agent: "MusicBrainz-Rust/Testing".to_string(),
-                max_retries: 5,
-                waits: ClientWaits::default(),
-            },
-            HttpClient::replay_file(format!("replay/test_entities/{}/{}.json", E::NAME, mbid)),
+    /// Parse an entity from an inline XML document instead of a replay file.
+    pub fn parse_entity<Res: Resource>(xml: &str, options: Res::Options) -> Result<Res, Error> {
+        crate::client::parse_response(xml, options, false)
+    }
+
+    /// Parse a lookup response of an entity with the type `entity_type` and
+    /// only the elements `children` otherwise, to test the parsing of types.
+    pub fn parse_typed<Res: Resource>(
+        entity_type: Option<&str>,
+        children: &str,
+        options: Res::Options,
+    ) -> Res {
+        let type_attribute = match entity_type {
+            Some(entity_type) => format!(r#" type="{}""#, entity_type),
+            None => String::new(),
+        };
+        let xml = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?><metadata xmlns="http://musicbrainz.org/ns/mmd-2.0#"><{0} id="d1ab65f8-d082-492a-bd70-ce375548dabf"{1}>{2}</{0}></metadata>"#,
+            Res::NAME,
+            type_attribute,
+            children
         );
-        client.get_by_mbid_old(mbid)
+        parse_entity(&xml, options).unwrap()
     }
 }
 
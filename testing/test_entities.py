@@ -26,7 +26,7 @@ extern crate pretty_env_logger;
 extern crate reqwest_mock;
 
 use std::str::FromStr;
-use musicbrainz::client::{Client, ClientConfig, ClientWaits};
+use musicbrainz::client::{Client, ClientConfig};
 use musicbrainz::entities::*;
 use reqwest_mock::GenericClient as HttpClient;
 
@@ -34,11 +34,11 @@ use reqwest_mock::GenericClient as HttpClient;
 fn run_tests() {
     pretty_env_logger::init();
 
-    let mut client = Client::with_http_client(ClientConfig {
-        user_agent: "musicbrainz_rust/testing (mail@leoschwarz.com)".to_owned(),
-        max_retries: 5,
-        waits: ClientWaits::default()
-    }, HttpClient::replay_dir("replay/test/test"));
+    let config = ClientConfig::builder()
+        .user_agent("musicbrainz_rust/testing (mail@leoschwarz.com)")
+        .build()
+        .unwrap();
+    let mut client = Client::with_http_client(config, HttpClient::replay_dir("replay/test/test"));
 
     let mut failures = 0;
 """